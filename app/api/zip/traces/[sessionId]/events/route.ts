@@ -3,13 +3,18 @@ import { z } from 'zod'
 import { FlowTraceDatabase } from '@/services/flowTraceDatabase'
 import { TraceEvent } from '@/types/zip'
 import { v4 as uuidv4 } from 'uuid'
+import { gunzipSync } from 'zlib'
 import { withZIPAuthorization } from '@/lib/auth/zip-middleware'
 
+// Cap on a gzipped submission once inflated, so a small body can't expand
+// without bound; matches the app's 10mb body size limit
+const MAX_DECOMPRESSED_BODY_SIZE = 10 * 1024 * 1024
+
 const traceEventSchema = z.object({
   timestamp: z.number(),
   nodeId: z.string(),
   portId: z.string().optional(),
-  eventType: z.enum(['input', 'output', 'error', 'log']),
+  eventType: z.enum(['input', 'output', 'error', 'log', 'warning']),
   data: z.object({
     size: z.number(),
     type: z.string(),
@@ -48,7 +53,29 @@ export const POST = withZIPAuthorization(async (
         }
       }, { status: 400 })
     }
-    const body = await request.json()
+    // SDKs gzip large submissions and mark them with Content-Encoding
+    let body: unknown
+    if (request.headers.get('content-encoding') === 'gzip') {
+      let inflated: Buffer
+      try {
+        inflated = gunzipSync(Buffer.from(await request.arrayBuffer()), {
+          maxOutputLength: MAX_DECOMPRESSED_BODY_SIZE,
+        })
+      } catch (error) {
+        if ((error as NodeJS.ErrnoException).code === 'ERR_BUFFER_TOO_LARGE') {
+          return NextResponse.json({
+            error: {
+              code: 'PAYLOAD_TOO_LARGE',
+              message: `Decompressed body exceeds ${MAX_DECOMPRESSED_BODY_SIZE / (1024 * 1024)}MB limit`,
+            }
+          }, { status: 413 })
+        }
+        throw error
+      }
+      body = JSON.parse(inflated.toString('utf8'))
+    } else {
+      body = await request.json()
+    }
     
     // Validate request
     const validation = submitEventsSchema.safeParse(body)
@@ -99,4 +126,76 @@ export const POST = withZIPAuthorization(async (
 }, {
   resourceType: 'trace-events',
   action: 'create'
-})
\ No newline at end of file
+})
+
+// GET /api/zip/traces/[sessionId]/events - Get trace events, optionally only
+// those at or after `since` (ms timestamp). Events sharing a millisecond are
+// returned in the order they were recorded so pollers can skip ones already seen.
+export const GET = withZIPAuthorization(async (
+  request: NextRequest,
+  context?: { params: any }
+) => {
+  try {
+    const { sessionId } = context?.params || {}
+    if (!sessionId) {
+      return NextResponse.json({
+        error: {
+          code: 'VALIDATION_ERROR',
+          message: 'Missing sessionId parameter',
+        }
+      }, { status: 400 })
+    }
+
+    const sinceParam = request.nextUrl.searchParams.get('since')
+    const since = sinceParam === null ? undefined : Number(sinceParam)
+    if (since !== undefined && !Number.isFinite(since)) {
+      return NextResponse.json({
+        error: {
+          code: 'VALIDATION_ERROR',
+          message: 'since must be a millisecond timestamp',
+        }
+      }, { status: 400 })
+    }
+
+    const session = await FlowTraceDatabase.getSessionById(sessionId)
+    if (!session) {
+      return NextResponse.json({
+        error: {
+          code: 'RESOURCE_NOT_FOUND',
+          message: `Trace session ${sessionId} not found`,
+        }
+      }, { status: 404 })
+    }
+
+    const events = await FlowTraceDatabase.getSessionEvents(
+      sessionId,
+      since === undefined ? undefined : new Date(since)
+    )
+
+    return NextResponse.json({
+      status: session.status,
+      events: events.map((event: any) => ({
+        id: event.id,
+        timestamp: new Date(event.timestamp).getTime(),
+        nodeId: event.nodeId,
+        portId: event.portId ?? undefined,
+        eventType: event.eventType,
+        data: event.data,
+        duration: event.duration ?? undefined,
+        metadata: event.metadata,
+      })),
+    })
+  } catch (error) {
+    console.error('Error getting trace events:', error)
+    return NextResponse.json({
+      error: {
+        code: 'INTERNAL_ERROR',
+        message: 'Failed to get trace events',
+        traceId: `trace_${Date.now()}`,
+      }
+    }, { status: 500 })
+  }
+}, {
+  resourceType: 'trace-events',
+  action: 'read'
+})
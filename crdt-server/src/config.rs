@@ -1,11 +1,60 @@
+use anyhow::{bail, Context};
+use axum::http::HeaderValue;
+use std::path::PathBuf;
+
 #[derive(Clone, Debug)]
 pub struct ServerConfig {
     pub port: u16,
     pub max_clients_per_room: usize,
+    /// Rooms a single client may be in at once; 0 removes the limit
+    pub max_rooms_per_client: usize,
     pub client_timeout_minutes: u64,
-    pub cors_origin: String,
+    /// Seconds between sweeps for clients idle past `client_timeout_minutes`;
+    /// 0 disables the sweeper
+    pub client_sweep_interval_secs: u64,
+    pub cors_origins: Vec<HeaderValue>,
     pub redis_url: String,
     pub enable_redis_persistence: bool,
+    pub enable_room_backups: bool,
+    pub room_backup_count: usize,
+    pub room_backup_ttl_secs: u64,
+    pub websocket_only: bool,
+    pub reconnect_grace_secs: u64,
+    pub shutdown_summary_path: Option<PathBuf>,
+    pub trust_proxy: bool,
+    /// Bearer token for the `/admin` endpoints; they are disabled when unset
+    pub admin_token: Option<String>,
+    /// Window in which repeated QUERY_AWARENESS messages from a client are
+    /// answered from the previous snapshot; 0 disables caching
+    pub awareness_query_cooldown_ms: u64,
+    /// Relay room broadcasts through Redis pub/sub so clients connected to
+    /// other server instances receive them; needs Redis persistence enabled
+    pub enable_redis_fanout: bool,
+    /// Encoded size above which a room's document is compacted before its
+    /// snapshot is saved; 0 disables compaction
+    pub snapshot_compaction_threshold_bytes: usize,
+}
+
+/// Origins allowed by default: the Next.js dev server on its usual ports
+pub const DEFAULT_CORS_ORIGINS: &str =
+    "http://localhost:3000,http://127.0.0.1:3000,http://localhost:3001,http://127.0.0.1:3001";
+
+/// Parse a comma-separated list of CORS origins, failing on any entry that
+/// is not a valid header value. Blank entries are ignored.
+pub fn parse_cors_origins(origins: &str) -> anyhow::Result<Vec<HeaderValue>> {
+    let mut parsed = Vec::new();
+    for origin in origins.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+        if origin == "*" {
+            bail!("wildcard CORS origin is not allowed with credentials");
+        }
+        let value = HeaderValue::from_str(origin)
+            .with_context(|| format!("invalid CORS origin: {:?}", origin))?;
+        parsed.push(value);
+    }
+    if parsed.is_empty() {
+        bail!("at least one CORS origin is required");
+    }
+    Ok(parsed)
 }
 
 impl Default for ServerConfig {
@@ -13,10 +62,49 @@ impl Default for ServerConfig {
         Self {
             port: 8080,
             max_clients_per_room: 100,
+            max_rooms_per_client: 50,
             client_timeout_minutes: 30,
-            cors_origin: "http://localhost:3000".to_string(),
+            client_sweep_interval_secs: 60,
+            cors_origins: parse_cors_origins(DEFAULT_CORS_ORIGINS)
+                .expect("default CORS origins are valid"),
             redis_url: "redis://redis:6379".to_string(),
             enable_redis_persistence: true,
+            enable_room_backups: false,
+            room_backup_count: 5,
+            room_backup_ttl_secs: 3 * 24 * 3600,
+            websocket_only: false,
+            reconnect_grace_secs: 30,
+            shutdown_summary_path: None,
+            trust_proxy: false,
+            admin_token: None,
+            awareness_query_cooldown_ms: 1000,
+            enable_redis_fanout: false,
+            snapshot_compaction_threshold_bytes: 1024 * 1024,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cors_origins() {
+        let origins = parse_cors_origins(
+            "https://app.zeal.dev, https://staging.zeal.dev,,http://localhost:3000",
+        )
+        .unwrap();
+        assert_eq!(
+            origins,
+            [
+                "https://app.zeal.dev",
+                "https://staging.zeal.dev",
+                "http://localhost:3000"
+            ]
+        );
+
+        assert!(parse_cors_origins("https://ok.dev,bad\norigin").is_err());
+        assert!(parse_cors_origins("*").is_err());
+        assert!(parse_cors_origins(" , ").is_err());
+    }
+}
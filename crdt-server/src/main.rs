@@ -5,6 +5,7 @@
  * JavaScript client while using Rust for better performance.
  */
 use clap::Parser;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::{info, Level};
 
@@ -34,12 +35,20 @@ struct Args {
     #[arg(long, default_value = "100")]
     max_clients_per_room: usize,
 
+    /// Maximum rooms a single client can join (0 for no limit)
+    #[arg(long, default_value = "50")]
+    max_rooms_per_client: usize,
+
     /// Client timeout in minutes
     #[arg(long, default_value = "30")]
     client_timeout_minutes: u64,
 
-    /// CORS origin (for Next.js dev server)
-    #[arg(long, default_value = "http://localhost:3000")]
+    /// Seconds between sweeps for timed-out clients (0 disables the sweeper)
+    #[arg(long, default_value = "60")]
+    client_sweep_interval_secs: u64,
+
+    /// Comma-separated list of allowed CORS origins
+    #[arg(long, default_value = config::DEFAULT_CORS_ORIGINS)]
     cors_origin: String,
 
     /// Redis URL for persistence
@@ -49,6 +58,50 @@ struct Args {
     /// Disable Redis persistence
     #[arg(long)]
     disable_redis_persistence: bool,
+
+    /// Write a timestamped backup snapshot on every room save
+    #[arg(long)]
+    enable_room_backups: bool,
+
+    /// Number of backup snapshots to keep per room
+    #[arg(long, default_value = "5")]
+    room_backup_count: usize,
+
+    /// Backup snapshot TTL in hours
+    #[arg(long, default_value = "72")]
+    room_backup_ttl_hours: u64,
+
+    /// Only accept WebSocket transport (disable long-polling)
+    #[arg(long)]
+    websocket_only: bool,
+
+    /// Seconds a disconnected client is kept in its rooms to allow reconnection
+    #[arg(long, default_value = "30")]
+    reconnect_grace_secs: u64,
+
+    /// Write a JSON shutdown summary to this file
+    #[arg(long)]
+    shutdown_summary_path: Option<PathBuf>,
+
+    /// Take client IPs from X-Forwarded-For / X-Real-IP (only behind a trusted proxy)
+    #[arg(long)]
+    trust_proxy: bool,
+
+    /// Bearer token required by the /admin endpoints (disabled when unset)
+    #[arg(long)]
+    admin_token: Option<String>,
+
+    /// Milliseconds during which repeated awareness queries from a client reuse the last snapshot
+    #[arg(long, default_value = "1000")]
+    awareness_query_cooldown_ms: u64,
+
+    /// Relay room broadcasts between server instances through Redis pub/sub
+    #[arg(long)]
+    enable_redis_fanout: bool,
+
+    /// Compact room snapshots whose encoded size exceeds this many bytes (0 disables)
+    #[arg(long, default_value = "1048576")]
+    snapshot_compaction_threshold_bytes: usize,
 }
 
 #[tokio::main]
@@ -62,6 +115,21 @@ async fn main() -> anyhow::Result<()> {
     if let Ok(disable) = std::env::var("DISABLE_REDIS_PERSISTENCE") {
         args.disable_redis_persistence = disable.to_lowercase() == "true" || disable == "1";
     }
+    if let Ok(enable) = std::env::var("ENABLE_ROOM_BACKUPS") {
+        args.enable_room_backups = enable.to_lowercase() == "true" || enable == "1";
+    }
+    if let Ok(enable) = std::env::var("ENABLE_REDIS_FANOUT") {
+        args.enable_redis_fanout = enable.to_lowercase() == "true" || enable == "1";
+    }
+    if let Ok(enable) = std::env::var("WEBSOCKET_ONLY") {
+        args.websocket_only = enable.to_lowercase() == "true" || enable == "1";
+    }
+    if let Ok(enable) = std::env::var("TRUST_PROXY") {
+        args.trust_proxy = enable.to_lowercase() == "true" || enable == "1";
+    }
+    if let Ok(token) = std::env::var("ADMIN_TOKEN") {
+        args.admin_token = Some(token).filter(|token| !token.is_empty());
+    }
 
     // Initialize tracing
     let level = if args.verbose {
@@ -77,8 +145,11 @@ async fn main() -> anyhow::Result<()> {
     info!("🦀 Starting Zeal CRDT Server");
     info!("📡 Port: {}", args.port);
     info!("🔧 Max clients per room: {}", args.max_clients_per_room);
+    info!("🔧 Max rooms per client: {}", args.max_rooms_per_client);
     info!("⏰ Client timeout: {} minutes", args.client_timeout_minutes);
-    info!("🌐 CORS origin: {}", args.cors_origin);
+    info!("🔁 Reconnect grace period: {}s", args.reconnect_grace_secs);
+    let cors_origins = config::parse_cors_origins(&args.cors_origin)?;
+    info!("🌐 CORS origins: {}", args.cors_origin);
     info!(
         "🗄️  Redis persistence: {}",
         if args.disable_redis_persistence {
@@ -87,15 +158,51 @@ async fn main() -> anyhow::Result<()> {
             "enabled"
         }
     );
+    info!(
+        "🔌 Transports: {}",
+        if args.websocket_only {
+            "websocket only"
+        } else {
+            "polling + websocket"
+        }
+    );
+    if args.trust_proxy {
+        info!("🛡️  Trusting X-Forwarded-For / X-Real-IP for client IPs");
+    }
+    if args.admin_token.is_some() {
+        info!("🔑 Admin endpoints enabled");
+    }
+    if args.enable_redis_fanout {
+        info!("📡 Relaying room broadcasts between instances via Redis pub/sub");
+    }
+    if args.enable_room_backups {
+        info!(
+            "💾 Room backups: keeping {} per room for {} hours",
+            args.room_backup_count, args.room_backup_ttl_hours
+        );
+    }
 
     // Create server config
     let config = ServerConfig {
         port: args.port,
         max_clients_per_room: args.max_clients_per_room,
+        max_rooms_per_client: args.max_rooms_per_client,
         client_timeout_minutes: args.client_timeout_minutes,
-        cors_origin: args.cors_origin,
+        client_sweep_interval_secs: args.client_sweep_interval_secs,
+        cors_origins,
         redis_url: args.redis_url,
         enable_redis_persistence: !args.disable_redis_persistence,
+        enable_room_backups: args.enable_room_backups,
+        room_backup_count: args.room_backup_count,
+        room_backup_ttl_secs: args.room_backup_ttl_hours * 3600,
+        websocket_only: args.websocket_only,
+        reconnect_grace_secs: args.reconnect_grace_secs,
+        shutdown_summary_path: args.shutdown_summary_path,
+        trust_proxy: args.trust_proxy,
+        admin_token: args.admin_token,
+        awareness_query_cooldown_ms: args.awareness_query_cooldown_ms,
+        enable_redis_fanout: args.enable_redis_fanout,
+        snapshot_compaction_threshold_bytes: args.snapshot_compaction_threshold_bytes,
     };
 
     // Create and start the server
@@ -1,8 +1,41 @@
 use anyhow::Result;
+use futures_util::{Stream, StreamExt};
 use redis::{aio::ConnectionManager, Client};
 use std::sync::Arc;
 use tracing::{error, info};
 
+/// Rooms whose name starts with this prefix hold workflow state and are
+/// persisted without expiry
+const WORKFLOW_ROOM_PREFIX: &str = "wf_";
+
+/// Expiry for the stored state of every other (temporary) room, refreshed
+/// whenever the room is accessed
+const TEMPORARY_ROOM_TTL_SECS: u64 = 86400;
+
+/// TTL for a room's stored state, or `None` if it should never expire
+pub fn room_state_ttl(room_id: &str) -> Option<u64> {
+    if room_id.starts_with(WORKFLOW_ROOM_PREFIX) {
+        None
+    } else {
+        Some(TEMPORARY_ROOM_TTL_SECS)
+    }
+}
+
+/// Key of a room backup written at `timestamp` (milliseconds)
+fn room_backup_key(room_id: &str, timestamp: i64) -> String {
+    format!("room:{}:backup:{}", room_id, timestamp)
+}
+
+/// Sorted set of a room's backup keys, scored by timestamp
+fn room_backup_index_key(room_id: &str) -> String {
+    format!("room:{}:backups", room_id)
+}
+
+/// `ZRANGE` stop index selecting every backup but the newest `keep`
+fn stale_backups_stop(keep: usize) -> i64 {
+    -(keep as i64) - 1
+}
+
 #[derive(Clone)]
 pub struct RedisManager {
     client: Arc<Client>,
@@ -87,26 +120,97 @@ impl RedisManager {
         let mut conn = self.get_connection().await?;
         let key = format!("room:{}:state", room_id);
 
-        // Check if this is a workflow room (starts with "wf_")
-        if room_id.starts_with("wf_") {
-            // No TTL for workflow rooms - they persist forever
-            redis::cmd("SET")
-                .arg(&key)
-                .arg(state)
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(&key).arg(state);
+        match room_state_ttl(room_id) {
+            Some(ttl) => {
+                cmd.arg("EX").arg(ttl);
+            }
+            None => info!("Saved persistent workflow room: {}", room_id),
+        }
+        cmd.query_async::<_, ()>(&mut conn).await?;
+
+        Ok(())
+    }
+
+    /// Write a timestamped backup snapshot of a room's state and trim the
+    /// backup index to the newest `keep` entries
+    pub async fn save_room_backup(
+        &self,
+        room_id: &str,
+        state: &[u8],
+        keep: usize,
+        ttl_seconds: u64,
+    ) -> Result<()> {
+        if !self.enabled || keep == 0 {
+            return Ok(());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let key = room_backup_key(room_id, timestamp);
+        let index_key = room_backup_index_key(room_id);
+
+        redis::cmd("SET")
+            .arg(&key)
+            .arg(state)
+            .arg("EX")
+            .arg(ttl_seconds)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        redis::cmd("ZADD")
+            .arg(&index_key)
+            .arg(timestamp)
+            .arg(&key)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        // Drop everything but the newest `keep` backups
+        let stale: Vec<String> = redis::cmd("ZRANGE")
+            .arg(&index_key)
+            .arg(0)
+            .arg(stale_backups_stop(keep))
+            .query_async(&mut conn)
+            .await?;
+        if !stale.is_empty() {
+            redis::cmd("DEL")
+                .arg(&stale)
                 .query_async::<_, ()>(&mut conn)
                 .await?;
-            info!("Saved persistent workflow room: {}", room_id);
-        } else {
-            // Temporary rooms get 24 hour TTL
-            redis::cmd("SET")
-                .arg(&key)
-                .arg(state)
-                .arg("EX")
-                .arg(86400) // 24 hours TTL
+            redis::cmd("ZREM")
+                .arg(&index_key)
+                .arg(&stale)
                 .query_async::<_, ()>(&mut conn)
                 .await?;
         }
 
+        redis::cmd("EXPIRE")
+            .arg(&index_key)
+            .arg(ttl_seconds)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store state that failed validation on load under a separate key, kept for 7 days
+    pub async fn backup_corrupt_room_state(&self, room_id: &str, state: &[u8]) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let mut conn = self.get_connection().await?;
+        let key = format!("room:{}:state:corrupt", room_id);
+
+        redis::cmd("SET")
+            .arg(&key)
+            .arg(state)
+            .arg("EX")
+            .arg(604800) // 7 days TTL
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
         Ok(())
     }
 
@@ -221,26 +325,67 @@ impl RedisManager {
         Ok(())
     }
 
+    /// Push back the expiry of a temporary room's stored state. Workflow
+    /// rooms never expire, so this is a no-op for them.
     pub async fn refresh_room_ttl(&self, room_id: &str) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
+        let Some(ttl) = room_state_ttl(room_id) else {
+            return Ok(());
+        };
 
         let mut conn = self.get_connection().await?;
         let key = format!("room:{}:state", room_id);
 
-        // Only refresh TTL for non-workflow rooms
-        if !room_id.starts_with("wf_") {
-            redis::cmd("EXPIRE")
-                .arg(&key)
-                .arg(86400) // Reset to 24 hours
-                .query_async::<_, ()>(&mut conn)
-                .await?;
+        redis::cmd("EXPIRE")
+            .arg(&key)
+            .arg(ttl)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Publish a room broadcast on `room:{room_id}:bcast` for the other
+    /// server instances
+    pub async fn publish_room_broadcast(&self, room_id: &str, payload: &[u8]) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
         }
 
+        let mut conn = self.get_connection().await?;
+        redis::cmd("PUBLISH")
+            .arg(format!("room:{}:bcast", room_id))
+            .arg(payload)
+            .query_async::<_, ()>(&mut conn)
+            .await?;
+
         Ok(())
     }
 
+    /// Subscribe to every room's broadcast channel, yielding the room name
+    /// and payload of each message. Pub/sub needs a dedicated connection, so
+    /// this opens one instead of using the shared connection manager.
+    pub async fn subscribe_room_broadcasts(&self) -> Result<impl Stream<Item = (String, Vec<u8>)>> {
+        if !self.enabled {
+            return Err(anyhow::anyhow!("Redis persistence is disabled"));
+        }
+
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub.psubscribe("room:*:bcast").await?;
+        info!("Subscribed to room broadcasts from other instances");
+
+        Ok(pubsub.into_on_message().filter_map(|message| async move {
+            let room_id = message
+                .get_channel_name()
+                .strip_prefix("room:")?
+                .strip_suffix(":bcast")?
+                .to_string();
+            Some((room_id, message.get_payload_bytes().to_vec()))
+        }))
+    }
+
     pub async fn health_check(&self) -> Result<bool> {
         if !self.enabled {
             return Ok(true);
@@ -255,3 +400,74 @@ impl RedisManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workflow_rooms_never_expire() {
+        assert_eq!(room_state_ttl("wf_123"), None);
+        assert_eq!(room_state_ttl("wf_"), None);
+    }
+
+    #[test]
+    fn test_temporary_rooms_expire() {
+        assert_eq!(room_state_ttl("scratch"), Some(TEMPORARY_ROOM_TTL_SECS));
+        assert_eq!(
+            room_state_ttl("workflow_123"),
+            Some(TEMPORARY_ROOM_TTL_SECS)
+        );
+        assert_eq!(room_state_ttl("my_wf_room"), Some(TEMPORARY_ROOM_TTL_SECS));
+    }
+
+    #[test]
+    fn test_room_backup_keys() {
+        assert_eq!(
+            room_backup_key("wf_123", 1700000000000),
+            "room:wf_123:backup:1700000000000"
+        );
+        assert_eq!(room_backup_index_key("wf_123"), "room:wf_123:backups");
+    }
+
+    #[test]
+    fn test_stale_backups_leave_newest_keep() {
+        // Number of entries `ZRANGE key 0 stop` returns from a set of `len`
+        fn zrange_len(len: usize, stop: i64) -> usize {
+            let stop = if stop < 0 { len as i64 + stop } else { stop };
+            if stop < 0 {
+                0
+            } else {
+                (stop as usize + 1).min(len)
+            }
+        }
+
+        for keep in 1..4 {
+            for len in 0..8 {
+                assert_eq!(
+                    zrange_len(len, stale_backups_stop(keep)),
+                    len.saturating_sub(keep),
+                    "keep {} of {}",
+                    keep,
+                    len
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_save_room_backup_without_redis_is_noop() {
+        let redis = RedisManager::new("redis://localhost".to_string(), false).unwrap();
+        redis
+            .save_room_backup("wf_123", &[1, 2, 3], 5, 3600)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_room_ttl_without_redis_is_noop() {
+        let redis = RedisManager::new("redis://localhost".to_string(), false).unwrap();
+        redis.refresh_room_ttl("scratch").await.unwrap();
+        redis.refresh_room_ttl("wf_123").await.unwrap();
+    }
+}
@@ -4,12 +4,14 @@ use crate::sync_protocol::SyncProtocol;
 use anyhow::Result;
 use dashmap::DashMap;
 use lib0::decoding::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
+use yrs::types::ToJson;
 use yrs::updates::decoder::Decode;
-use yrs::{Doc, ReadTxn, Transact, Update};
+use yrs::{Any, Array, Doc, Map, ReadTxn, StateVector, Transact, Update, Value};
 
 #[derive(Clone)]
 pub struct CRDTRoom {
@@ -19,6 +21,7 @@ pub struct CRDTRoom {
     pub awareness_states: Arc<DashMap<String, Vec<u8>>>, // Store latest awareness state for each client
     pub last_activity: Arc<RwLock<Instant>>,
     pub marked_for_removal: Arc<RwLock<Option<Instant>>>, // Track when room was marked for removal
+    pub compacted_len: Arc<AtomicUsize>, // Encoded size after the last compaction attempt, 0 if none
     pub config: ServerConfig,
     pub redis: Option<Arc<RedisManager>>,
 }
@@ -34,6 +37,7 @@ impl CRDTRoom {
             awareness_states: Arc::new(DashMap::new()),
             last_activity: Arc::new(RwLock::new(Instant::now())),
             marked_for_removal: Arc::new(RwLock::new(None)),
+            compacted_len: Arc::new(AtomicUsize::new(0)),
             config,
             redis: None,
         }
@@ -49,6 +53,7 @@ impl CRDTRoom {
             awareness_states: Arc::new(DashMap::new()),
             last_activity: Arc::new(RwLock::new(Instant::now())),
             marked_for_removal: Arc::new(RwLock::new(None)),
+            compacted_len: Arc::new(AtomicUsize::new(0)),
             config,
             redis: Some(redis),
         }
@@ -63,23 +68,299 @@ impl CRDTRoom {
                     state.len()
                 );
 
-                // Apply the stored state to the document
-                let doc = self.doc.write().await;
-                if let Ok(update) = Update::decode_v1(&state) {
-                    doc.transact_mut().apply_update(update);
-                    return Ok(true);
+                // Decode into a scratch document first so corrupt state never reaches clients
+                match Self::decode_stored_state(&state) {
+                    Ok(loaded) => {
+                        // An unexpected layout is only reported: replacing the
+                        // state would lose whatever the room does hold
+                        let problems = Self::workflow_layout_problems(&loaded);
+                        if !problems.is_empty() {
+                            warn!(
+                                "Stored state for room {} is not laid out as a workflow, loading it anyway: {}",
+                                self.name,
+                                problems.join("; ")
+                            );
+                        }
+                        *self.doc.write().await = loaded;
+                        return Ok(true);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Stored state for room {} is corrupt, starting with a fresh document: {}",
+                            self.name, e
+                        );
+                        // Keep the corrupt bytes around for inspection before they get overwritten
+                        if let Err(e) = redis.backup_corrupt_room_state(&self.name, &state).await {
+                            warn!(
+                                "Failed to back up corrupt state for room {}: {}",
+                                self.name, e
+                            );
+                        }
+                    }
                 }
             }
         }
         Ok(false)
     }
 
-    pub async fn save_to_redis(&self) -> Result<()> {
+    /// Decode a stored update into a new document, failing if the bytes don't
+    /// decode or the update depends on operations it doesn't contain. The
+    /// workflow layout is checked separately by
+    /// [`workflow_layout_problems`](Self::workflow_layout_problems).
+    pub fn decode_stored_state(state: &[u8]) -> Result<Doc> {
+        let update = Update::decode_v1(state)
+            .map_err(|e| anyhow::anyhow!("Failed to decode stored update: {}", e))?;
+
+        let doc = Doc::new();
+        {
+            let mut txn = doc.transact_mut();
+            txn.apply_update(update);
+            if txn.store().pending_update().is_some() {
+                return Err(anyhow::anyhow!("Stored update has missing dependencies"));
+            }
+        }
+
+        Ok(doc)
+    }
+
+    /// Check a document against the layout the web client writes: non-empty
+    /// `metadata` and `graphs` root maps, and `nodes-{id}` and
+    /// `connections-{id}` maps for every graph listed in `graphs`. Returns
+    /// one description per problem; a document nothing was written to has
+    /// none.
+    pub fn workflow_layout_problems(doc: &Doc) -> Vec<String> {
+        let txn = doc.transact();
+        if txn.state_vector().is_empty() {
+            return Vec::new();
+        }
+
+        let mut problems = Vec::new();
+        if txn
+            .get_map("metadata")
+            .filter(|m| m.len(&txn) > 0)
+            .is_none()
+        {
+            problems.push("no 'metadata' map".to_string());
+        }
+        let Some(graphs) = txn.get_map("graphs").filter(|m| m.len(&txn) > 0) else {
+            problems.push("no 'graphs' map".to_string());
+            return problems;
+        };
+        for (graph_id, info) in graphs.iter(&txn) {
+            if !matches!(info, Value::Any(Any::Map(_))) {
+                problems.push(format!("graph '{}' info is not an object", graph_id));
+            }
+            for part in ["nodes", "connections"] {
+                if txn.get_map(format!("{}-{}", part, graph_id)).is_none() {
+                    problems.push(format!(
+                        "graph '{}' has no '{}-{}' map",
+                        graph_id, part, graph_id
+                    ));
+                }
+            }
+        }
+        problems
+    }
+
+    /// Re-encode a full state update through a fresh document. Deleted items
+    /// lose their content and adjacent structs are merged, but item IDs are
+    /// kept, so updates made against the original document still apply to
+    /// the compacted one.
+    pub fn compact_state(state: &[u8]) -> Result<(Doc, Vec<u8>)> {
+        let update = Update::decode_v1(state)
+            .map_err(|e| anyhow::anyhow!("Failed to decode state for compaction: {}", e))?;
+
+        let doc = Doc::new();
+        {
+            let mut txn = doc.transact_mut();
+            txn.apply_update(update);
+            if txn.store().pending_update().is_some() {
+                return Err(anyhow::anyhow!("State has missing dependencies"));
+            }
+        }
+        let compacted = doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+        Ok((doc, compacted))
+    }
+
+    /// Encode the room's full state for a snapshot. Past
+    /// `snapshot_compaction_threshold_bytes`, the in-memory document is
+    /// swapped for a compacted copy first, so it stops carrying tombstones
+    /// too. Once compacted, a room is only compacted again after its encoded
+    /// size has doubled, since rebuilding a large document on every save
+    /// would stall the room.
+    async fn encode_snapshot(&self) -> Vec<u8> {
+        let threshold = self.config.snapshot_compaction_threshold_bytes;
+        let update = self
+            .doc
+            .read()
+            .await
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+        if threshold == 0 || update.len() <= threshold {
+            return update;
+        }
+        if !self.has_grown_since_compaction(&update) {
+            return update;
+        }
+
+        // Re-encode under the write lock so no update lands between encoding
+        // and swapping in the compacted document
+        let mut doc = self.doc.write().await;
+        let update = doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+        let snapshot = match Self::compact_state(&update) {
+            Ok((compacted_doc, compacted)) if compacted.len() < update.len() => {
+                info!(
+                    "Compacted room {} snapshot from {} to {} bytes",
+                    self.name,
+                    update.len(),
+                    compacted.len()
+                );
+                *doc = compacted_doc;
+                compacted
+            }
+            Ok(_) => update,
+            Err(e) => {
+                warn!("Failed to compact room {} snapshot: {}", self.name, e);
+                update
+            }
+        };
+        self.compacted_len.store(snapshot.len(), Ordering::Relaxed);
+        snapshot
+    }
+
+    /// Whether `update` is at least twice the size left by the last
+    /// compaction attempt, or no attempt has been made yet
+    fn has_grown_since_compaction(&self, update: &[u8]) -> bool {
+        let compacted_len = self.compacted_len.load(Ordering::Relaxed);
+        compacted_len == 0 || update.len() >= compacted_len.saturating_mul(2)
+    }
+
+    /// Decode an initial state for `seed`. It must be non-empty and pass the
+    /// same checks as stored state, so a seeded room survives a reload.
+    pub fn decode_seed(update: &[u8]) -> Result<Doc> {
+        let doc = Self::decode_stored_state(update)?;
+        if doc.transact().state_vector().is_empty() {
+            return Err(anyhow::anyhow!("Seed update is empty"));
+        }
+        Ok(doc)
+    }
+
+    /// Apply `initial` as the room's starting state unless the document
+    /// already has content. Returns whether it was applied.
+    pub async fn seed(&self, initial: &Doc) -> Result<bool> {
+        let doc = self.doc.write().await;
+        if !doc.transact().state_vector().is_empty() {
+            return Ok(false);
+        }
+
+        let update = initial
+            .transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default());
+        let update = Update::decode_v1(&update)
+            .map_err(|e| anyhow::anyhow!("Failed to decode seed update: {}", e))?;
+        doc.transact_mut().apply_update(update);
+        drop(doc);
+
+        self.update_activity().await;
+        Ok(true)
+    }
+
+    /// Read-only JSON view of the document for inspection
+    pub async fn to_json(&self) -> serde_json::Value {
+        Self::doc_to_json(&*self.doc.read().await)
+    }
+
+    /// Convert a document into JSON following the client's workflow layout:
+    /// a `metadata` map, a `graphs` map of graph info keyed by graph id, and
+    /// `nodes-<id>`, `connections-<id>` and `groups-<id>` maps per graph.
+    /// Anything else ends up under `other`, and departures from the layout
+    /// are listed in `warnings` rather than failing the conversion.
+    fn doc_to_json(doc: &Doc) -> serde_json::Value {
+        const GRAPH_PARTS: [&str; 3] = ["nodes", "connections", "groups"];
+
+        let txn = doc.transact();
+        let mut warnings = Vec::new();
+        let mut other = serde_json::Map::new();
+        let mut graphs = serde_json::Map::new();
+
+        let graph_info = match root_to_json(&txn, "graphs") {
+            Some(serde_json::Value::Object(info)) => info,
+            Some(_) => {
+                warnings.push("'graphs' is not a map".to_string());
+                serde_json::Map::new()
+            }
+            None => {
+                if !txn.state_vector().is_empty() {
+                    warnings.push("Document has no 'graphs' map".to_string());
+                }
+                serde_json::Map::new()
+            }
+        };
+        for (graph_id, info) in graph_info {
+            if !info.is_object() {
+                warnings.push(format!("Graph '{}' info is not an object", graph_id));
+            }
+            graphs.insert(graph_id, serde_json::json!({ "info": info }));
+        }
+
+        let mut root_names: Vec<String> =
+            txn.root_refs().map(|(name, _)| name.to_string()).collect();
+        root_names.sort();
+        for name in root_names {
+            if name == "graphs" || name == "metadata" {
+                continue;
+            }
+            let Some(value) = root_to_json(&txn, &name) else {
+                continue;
+            };
+            let graph_part = GRAPH_PARTS.iter().find_map(|part| {
+                name.strip_prefix(part)
+                    .and_then(|rest| rest.strip_prefix('-'))
+                    .map(|graph_id| (*part, graph_id))
+            });
+            match graph_part {
+                Some((part, graph_id)) => {
+                    let graph = graphs.entry(graph_id.to_string()).or_insert_with(|| {
+                        warnings.push(format!(
+                            "Graph '{}' has data but no entry in 'graphs'",
+                            graph_id
+                        ));
+                        serde_json::json!({ "info": null })
+                    });
+                    graph[part] = value;
+                }
+                None => {
+                    other.insert(name, value);
+                }
+            }
+        }
+
+        for graph in graphs.values_mut() {
+            for part in GRAPH_PARTS {
+                if graph.get(part).is_none() {
+                    graph[part] = serde_json::json!({});
+                }
+            }
+        }
+
+        serde_json::json!({
+            "metadata": root_to_json(&txn, "metadata").unwrap_or_else(|| serde_json::json!({})),
+            "graphs": graphs,
+            "other": other,
+            "warnings": warnings,
+        })
+    }
+
+    /// Persist the room state, returning the number of bytes written (0 when
+    /// persistence is disabled)
+    pub async fn save_to_redis(&self) -> Result<usize> {
         if let Some(redis) = &self.redis {
             if redis.is_enabled() {
-                let doc = self.doc.read().await;
-                let state = doc.transact().state_vector();
-                let update = doc.transact().encode_state_as_update_v1(&state);
+                let update = self.encode_snapshot().await;
 
                 redis.save_room_state(&self.name, &update).await?;
                 debug!(
@@ -87,6 +368,21 @@ impl CRDTRoom {
                     self.name,
                     update.len()
                 );
+
+                if self.config.enable_room_backups {
+                    if let Err(e) = redis
+                        .save_room_backup(
+                            &self.name,
+                            &update,
+                            self.config.room_backup_count,
+                            self.config.room_backup_ttl_secs,
+                        )
+                        .await
+                    {
+                        warn!("Failed to write backup for room {}: {}", self.name, e);
+                    }
+                }
+                return Ok(update.len());
             } else {
                 // Redis is disabled, return Ok to prevent room removal
                 debug!("Redis disabled, keeping room {} in memory", self.name);
@@ -95,7 +391,7 @@ impl CRDTRoom {
             // No Redis configured, keep room in memory
             debug!("No Redis configured, keeping room {} in memory", self.name);
         }
-        Ok(())
+        Ok(0)
     }
 
     pub async fn add_client(&self, client_id: String) -> Result<()> {
@@ -149,7 +445,7 @@ impl CRDTRoom {
         }
 
         // Parse and handle different message types
-        if data.len() > 0 {
+        if !data.is_empty() {
             let message_type = data[0];
 
             match message_type {
@@ -288,6 +584,8 @@ impl CRDTRoom {
 
     // Broadcast is now handled by Socket.IO in the server
 
+    /// Remove clients (and their awareness states) not seen for longer than
+    /// `timeout_minutes`, returning how many were removed
     pub async fn cleanup_inactive_clients(&self, timeout_minutes: u64) -> usize {
         let timeout_duration = std::time::Duration::from_secs(timeout_minutes * 60);
         let now = Instant::now();
@@ -305,6 +603,7 @@ impl CRDTRoom {
 
         for client_id in to_remove {
             self.clients.remove(&client_id);
+            self.awareness_states.remove(&client_id);
             removed_count += 1;
         }
 
@@ -447,3 +746,340 @@ impl CRDTRoom {
         messages
     }
 }
+
+/// JSON for a root-level shared type. Roots integrated from remote updates
+/// carry no type information on the server, so a root with keyed entries is
+/// read as a map and one with sequence content as an array.
+fn root_to_json<T: ReadTxn>(txn: &T, name: &str) -> Option<serde_json::Value> {
+    if let Some(map) = txn.get_map(name).filter(|map| map.len(txn) > 0) {
+        return Some(any_to_json(&map.to_json(txn)));
+    }
+    txn.get_array(name)
+        .filter(|array| array.len(txn) > 0)
+        .map(|array| any_to_json(&array.to_json(txn)))
+}
+
+fn any_to_json(any: &Any) -> serde_json::Value {
+    match any {
+        Any::Null | Any::Undefined => serde_json::Value::Null,
+        Any::Bool(b) => serde_json::Value::Bool(*b),
+        Any::Number(n) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Any::BigInt(n) => serde_json::Value::from(*n),
+        Any::String(s) => serde_json::Value::String(s.to_string()),
+        Any::Buffer(bytes) => serde_json::Value::from(bytes.to_vec()),
+        Any::Array(items) => items.iter().map(any_to_json).collect(),
+        Any::Map(entries) => entries
+            .iter()
+            .map(|(key, value)| (key.clone(), any_to_json(value)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yrs::{MapPrelim, Text};
+
+    fn encode(doc: &Doc) -> Vec<u8> {
+        doc.transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default())
+    }
+
+    /// A document laid out the way the web client writes it
+    fn workflow_doc() -> Doc {
+        let doc = Doc::new();
+        let metadata = doc.get_or_insert_map("metadata");
+        let graphs = doc.get_or_insert_map("graphs");
+        let nodes = doc.get_or_insert_map("nodes-main");
+        let connections = doc.get_or_insert_map("connections-main");
+        doc.get_or_insert_map("groups-main");
+        {
+            let mut txn = doc.transact_mut();
+            metadata.insert(&mut txn, "name", "Demo");
+            graphs.insert(
+                &mut txn,
+                "main",
+                Any::from(std::collections::HashMap::from([
+                    ("id".to_string(), Any::from("main")),
+                    ("isMain".to_string(), Any::from(true)),
+                ])),
+            );
+            let node = nodes.insert(&mut txn, "node-1", MapPrelim::<String>::new());
+            node.insert(&mut txn, "type", "http-request");
+            connections.insert(&mut txn, "conn-1", "node-1->node-2");
+        }
+        doc
+    }
+
+    #[test]
+    fn test_decode_valid_workflow_state() {
+        let state = encode(&workflow_doc());
+        let doc = CRDTRoom::decode_stored_state(&state).unwrap();
+        let txn = doc.transact();
+        assert!(matches!(
+            txn.get_map("graphs").unwrap().get(&txn, "main"),
+            Some(Value::Any(Any::Map(_)))
+        ));
+        let nodes = txn.get_map("nodes-main").unwrap();
+        assert!(matches!(nodes.get(&txn, "node-1"), Some(Value::YMap(_))));
+        assert!(txn
+            .get_map("connections-main")
+            .unwrap()
+            .contains_key(&txn, "conn-1"));
+    }
+
+    #[tokio::test]
+    async fn test_to_json() {
+        // Laid out the way the web client writes it
+        let source = Doc::new();
+        {
+            let metadata = source.get_or_insert_map("metadata");
+            let graphs = source.get_or_insert_map("graphs");
+            let nodes = source.get_or_insert_map("nodes-main");
+            let connections = source.get_or_insert_map("connections-main");
+            let orphan = source.get_or_insert_map("nodes-ghost");
+            let notes = source.get_or_insert_text("notes");
+            let mut txn = source.transact_mut();
+            metadata.insert(&mut txn, "name", "Demo");
+            graphs.insert(
+                &mut txn,
+                "main",
+                Any::from(std::collections::HashMap::from([
+                    ("id".to_string(), Any::from("main")),
+                    ("isMain".to_string(), Any::from(true)),
+                ])),
+            );
+            let node = nodes.insert(&mut txn, "node-1", MapPrelim::<String>::new());
+            node.insert(&mut txn, "type", "http-request");
+            node.insert(&mut txn, "x", 10.5);
+            connections.insert(&mut txn, "conn-1", "node-1->node-2");
+            orphan.insert(&mut txn, "node-9", "stray");
+            notes.insert(&mut txn, 0, "todo");
+        }
+
+        // Applied as a remote update, so the server knows no root types
+        let room = CRDTRoom::new("demo".to_string(), ServerConfig::default());
+        {
+            let doc = room.doc.write().await;
+            let mut txn = doc.transact_mut();
+            txn.apply_update(Update::decode_v1(&encode(&source)).unwrap());
+        }
+
+        let json = room.to_json().await;
+        assert_eq!(json["metadata"], serde_json::json!({ "name": "Demo" }));
+        assert_eq!(
+            json["graphs"]["main"],
+            serde_json::json!({
+                "info": { "id": "main", "isMain": true },
+                "nodes": { "node-1": { "type": "http-request", "x": 10.5 } },
+                "connections": { "conn-1": "node-1->node-2" },
+                "groups": {}
+            })
+        );
+        assert_eq!(json["graphs"]["ghost"]["info"], serde_json::Value::Null);
+        assert_eq!(json["graphs"]["ghost"]["nodes"]["node-9"], "stray");
+        assert!(json["other"].get("notes").is_some());
+        assert_eq!(
+            json["warnings"],
+            serde_json::json!(["Graph 'ghost' has data but no entry in 'graphs'"])
+        );
+
+        let empty = CRDTRoom::new("empty".to_string(), ServerConfig::default());
+        assert_eq!(
+            empty.to_json().await,
+            serde_json::json!({ "metadata": {}, "graphs": {}, "other": {}, "warnings": [] })
+        );
+
+        // A document that isn't a workflow is still converted
+        let doc = Doc::new();
+        let text = doc.get_or_insert_text("notes");
+        text.insert(&mut doc.transact_mut(), 0, "not a workflow");
+        let json = CRDTRoom::doc_to_json(&doc);
+        assert_eq!(
+            json["warnings"],
+            serde_json::json!(["Document has no 'graphs' map"])
+        );
+    }
+
+    #[test]
+    fn test_decode_empty_state() {
+        let state = encode(&Doc::new());
+        assert!(CRDTRoom::decode_stored_state(&state).is_ok());
+    }
+
+    #[test]
+    fn test_decode_rejects_corrupted_update() {
+        let mut state = encode(&workflow_doc());
+        state.truncate(state.len() / 2);
+        assert!(CRDTRoom::decode_stored_state(&state).is_err());
+
+        assert!(CRDTRoom::decode_stored_state(&[0xff, 0xff, 0xff, 0x01, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_workflow_layout_problems() {
+        let valid = CRDTRoom::decode_stored_state(&encode(&workflow_doc())).unwrap();
+        assert!(CRDTRoom::workflow_layout_problems(&valid).is_empty());
+        assert!(CRDTRoom::workflow_layout_problems(&Doc::new()).is_empty());
+
+        // Not a workflow at all: still decodes, but every problem is reported
+        let doc = Doc::new();
+        let text = doc.get_or_insert_text("notes");
+        text.insert(&mut doc.transact_mut(), 0, "not a workflow");
+        let loaded = CRDTRoom::decode_stored_state(&encode(&doc)).unwrap();
+        assert_eq!(
+            CRDTRoom::workflow_layout_problems(&loaded),
+            vec!["no 'metadata' map", "no 'graphs' map"]
+        );
+
+        // A graph listed without its nodes and connections
+        let doc = workflow_doc();
+        let graphs = doc.get_or_insert_map("graphs");
+        graphs.insert(
+            &mut doc.transact_mut(),
+            "sub",
+            Any::from(std::collections::HashMap::from([(
+                "id".to_string(),
+                Any::from("sub"),
+            )])),
+        );
+        let loaded = CRDTRoom::decode_stored_state(&encode(&doc)).unwrap();
+        assert_eq!(
+            CRDTRoom::workflow_layout_problems(&loaded),
+            vec![
+                "graph 'sub' has no 'nodes-sub' map",
+                "graph 'sub' has no 'connections-sub' map"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_compaction_keeps_late_updates_mergeable() {
+        let config = ServerConfig {
+            snapshot_compaction_threshold_bytes: 1,
+            ..ServerConfig::default()
+        };
+        let room = CRDTRoom::new("wf_compact".to_string(), config);
+        // Keep deleted content around, as a document that was never garbage
+        // collected would, so compaction has something to drop
+        *room.doc.write().await = Doc::with_options(yrs::Options {
+            skip_gc: true,
+            ..Default::default()
+        });
+
+        // A client fills the document and then deletes most of it, syncing
+        // every edit to the room as it goes
+        let client = workflow_doc();
+        let notes = client.get_or_insert_map("notes");
+        let sync = |client: &Doc| {
+            let room = room.clone();
+            let client = client.clone();
+            async move {
+                let doc = room.doc.write().await;
+                let since = doc.transact().state_vector();
+                let update = client.transact().encode_state_as_update_v1(&since);
+                doc.transact_mut()
+                    .apply_update(Update::decode_v1(&update).unwrap());
+            }
+        };
+        for i in 0..200 {
+            notes.insert(
+                &mut client.transact_mut(),
+                format!("note-{}", i),
+                "x".repeat(100),
+            );
+            sync(&client).await;
+        }
+        for i in 0..190 {
+            notes.remove(&mut client.transact_mut(), &format!("note-{}", i));
+            sync(&client).await;
+        }
+        let uncompacted = encode(&*room.doc.read().await);
+        let synced = client.transact().state_vector();
+        let before = room.doc.read().await.client_id();
+
+        let snapshot = room.encode_snapshot().await;
+        assert!(snapshot.len() < uncompacted.len());
+        assert_ne!(room.doc.read().await.client_id(), before);
+        let loaded = CRDTRoom::decode_stored_state(&snapshot).unwrap();
+
+        // An edit made before the client saw the compaction still merges into
+        // both the live document and one loaded from the snapshot
+        notes.insert(&mut client.transact_mut(), "late", "arrival");
+        let late = client.transact().encode_state_as_update_v1(&synced);
+        for doc in [&*room.doc.read().await, &loaded] {
+            {
+                let mut txn = doc.transact_mut();
+                txn.apply_update(Update::decode_v1(&late).unwrap());
+                assert!(txn.store().pending_update().is_none());
+            }
+            let txn = doc.transact();
+            let notes = txn.get_map("notes").unwrap();
+            assert_eq!(notes.len(&txn), 11);
+            assert!(notes.contains_key(&txn, "late"));
+            assert!(!notes.contains_key(&txn, "note-0"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_recompacted_only_after_doubling() {
+        let config = ServerConfig {
+            snapshot_compaction_threshold_bytes: 1,
+            ..ServerConfig::default()
+        };
+        let room = CRDTRoom::new("wf_grow".to_string(), config);
+        let client = workflow_doc();
+        let notes = client.get_or_insert_map("notes");
+        let sync = |client: &Doc| {
+            let update = encode(client);
+            let room = room.clone();
+            async move {
+                let doc = room.doc.write().await;
+                let mut txn = doc.transact_mut();
+                txn.apply_update(Update::decode_v1(&update).unwrap());
+            }
+        };
+        sync(&client).await;
+
+        let compacted = room.encode_snapshot().await.len();
+        assert_eq!(room.compacted_len.load(Ordering::Relaxed), compacted);
+
+        // A small edit stays under twice the compacted size, so no attempt is
+        // made to compact again
+        notes.insert(&mut client.transact_mut(), "small", "edit");
+        sync(&client).await;
+        assert!(room.encode_snapshot().await.len() > compacted);
+        assert_eq!(room.compacted_len.load(Ordering::Relaxed), compacted);
+
+        // Doubling the document triggers another attempt
+        for i in 0..10 {
+            notes.insert(
+                &mut client.transact_mut(),
+                format!("note-{}", i),
+                "x".repeat(compacted),
+            );
+        }
+        sync(&client).await;
+        let grown = room.encode_snapshot().await.len();
+        assert!(grown >= compacted * 2);
+        assert_eq!(room.compacted_len.load(Ordering::Relaxed), grown);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_below_threshold_is_not_compacted() {
+        let room = CRDTRoom::new("small".to_string(), ServerConfig::default());
+        {
+            let doc = room.doc.write().await;
+            let mut txn = doc.transact_mut();
+            txn.apply_update(Update::decode_v1(&encode(&workflow_doc())).unwrap());
+        }
+        let before = room.doc.read().await.client_id();
+
+        let snapshot = room.encode_snapshot().await;
+        assert!(snapshot.len() < ServerConfig::default().snapshot_compaction_threshold_bytes);
+        assert_eq!(room.doc.read().await.client_id(), before);
+        assert!(CRDTRoom::decode_stored_state(&snapshot).is_ok());
+    }
+}
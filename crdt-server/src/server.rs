@@ -8,23 +8,351 @@ use crate::config::ServerConfig;
 use crate::redis_manager::RedisManager;
 use crate::room::CRDTRoom;
 use anyhow::Result;
+use bytes::Bytes;
 use chrono;
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
+use futures_util::StreamExt;
+use serde::Serialize;
 use serde_json::json;
 use socketioxide::{
-    extract::{Data, SocketRef},
-    SocketIo,
+    extract::{Bin, Data, SocketRef},
+    SocketIo, TransportType,
 };
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 use tower_http::timeout::TimeoutLayer;
 use tracing::{debug, error, info, warn};
 
+/// How long a polling client has to upgrade to WebSocket before we warn
+const TRANSPORT_UPGRADE_CHECK: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Handshake header a client can use to supply its own correlation id
+const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+/// Handshake query parameter a client can use to supply its own correlation id
+const CORRELATION_ID_PARAM: &str = "correlationId";
+
+/// How long the Redis fan-out listener waits before resubscribing after its
+/// subscription fails or ends
+const FANOUT_RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Client id under which broadcasts relayed from other instances are
+/// applied to the local room
+const FANOUT_CLIENT_ID: &str = "redis-fanout";
+
+/// Suffix of the Socket.IO room holding the members of a CRDT room that
+/// negotiated binary `crdt:message` frames. CRDT rooms can't use names
+/// ending in it, or they would share a Socket.IO room with another room's
+/// binary members.
+const BINARY_ROOM_SUFFIX: &str = "#binary";
+
+/// Correlation id attached to every log line for a socket: the one supplied
+/// by the client in the handshake, or the socket id
+fn correlation_id(socket: &SocketRef) -> String {
+    correlation_id_from_handshake(socket.req_parts()).unwrap_or_else(|| socket.id.to_string())
+}
+
+/// Client IP for a connection. With `trust_proxy` the right-most address in
+/// `X-Forwarded-For` (the one appended by the proxy in front of us; anything
+/// left of it is client-supplied), then `X-Real-IP`, is used when present and
+/// valid; otherwise the TCP peer address. Never enable `trust_proxy` unless
+/// every request comes through a proxy that sets these headers, since clients
+/// can spoof them.
+fn client_ip(parts: &axum::http::request::Parts, trust_proxy: bool) -> Option<std::net::IpAddr> {
+    if trust_proxy {
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let forwarded = header("x-forwarded-for")
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|ip| ip.trim().parse().ok());
+        let real_ip = || header("x-real-ip").and_then(|ip| ip.trim().parse().ok());
+        if let Some(ip) = forwarded.or_else(real_ip) {
+            return Some(ip);
+        }
+    }
+
+    parts
+        .extensions
+        .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        .map(|info| info.0.ip())
+}
+
+/// Read a client-supplied correlation id from the handshake header or query.
+/// Ids that are empty, longer than 128 characters or contain anything other
+/// than ASCII alphanumerics, `-`, `_` and `.` are ignored.
+fn correlation_id_from_handshake(parts: &axum::http::request::Parts) -> Option<String> {
+    let from_header = parts
+        .headers
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let from_query = parts.uri.query().and_then(|query| {
+        query.split('&').find_map(|pair| {
+            pair.strip_prefix(CORRELATION_ID_PARAM)
+                .and_then(|rest| rest.strip_prefix('='))
+        })
+    });
+
+    from_header
+        .or(from_query)
+        .filter(|id| {
+            !id.is_empty()
+                && id.len() <= 128
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .map(str::to_string)
+}
+
+/// What happened to the server's state during shutdown
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShutdownSummary {
+    pub rooms_flushed: usize,
+    pub clients_disconnected: usize,
+    pub bytes_persisted: usize,
+    pub failed_rooms: Vec<FailedRoom>,
+}
+
+/// What a periodic inactive-client sweep removed
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SweepSummary {
+    pub clients_removed: usize,
+    pub rooms_removed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedRoom {
+    pub room: String,
+    pub error: String,
+}
+
+/// Body of `GET /stats`. Fields are declared in alphabetical order so the
+/// output matches the sorted keys of the `json!` objects it replaced.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    pub malformed_frames: u64,
+    pub room_details: Vec<RoomStats>,
+    pub rooms: usize,
+    pub status: &'static str,
+    pub total_clients: usize,
+    pub transports: TransportStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoomStats {
+    pub clients: usize,
+    pub name: String,
+}
+
+/// Connections per transport
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TransportStats {
+    pub polling: usize,
+    pub websocket: usize,
+}
+
+/// Body of `GET /health`
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub checks: HealthChecks,
+    pub service: &'static str,
+    /// "healthy", or "degraded" when Redis is unreachable
+    pub status: &'static str,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthChecks {
+    /// "healthy", "unhealthy" or "disabled"
+    pub redis: &'static str,
+    pub server: &'static str,
+}
+
+/// Body of `POST /admin/broadcast`
+#[derive(Debug, serde::Deserialize)]
+pub struct AnnouncementRequest {
+    pub message: String,
+    /// Severity shown by clients, e.g. "info" or "warning" (default "info")
+    #[serde(default)]
+    pub level: Option<String>,
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Resolve when the process receives Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 pub struct CRDTServer {
     config: ServerConfig,
     rooms: Arc<DashMap<String, CRDTRoom>>,
     redis: Arc<RedisManager>,
+    io: OnceLock<SocketIo>,
+    /// Last QUERY_AWARENESS answer per (socket, room), reused within the cooldown
+    awareness_snapshots: DashMap<(String, String), AwarenessSnapshot>,
+    /// `crdt:message` frames rejected as malformed since startup
+    malformed_frames: std::sync::atomic::AtomicU64,
+    /// (socket, room) pairs that negotiated binary frames on join
+    binary_members: DashSet<(String, String)>,
+    /// Identifies this instance's messages on the Redis fan-out channels
+    instance_id: uuid::Uuid,
+}
+
+/// Awareness states gathered for a client and when they were gathered
+type AwarenessSnapshot = (std::time::Instant, Arc<Vec<Vec<u8>>>);
+
+/// Count connections per transport
+fn transport_breakdown(transports: impl IntoIterator<Item = TransportType>) -> TransportStats {
+    let mut stats = TransportStats::default();
+    for transport in transports {
+        match transport {
+            TransportType::Polling => stats.polling += 1,
+            TransportType::Websocket => stats.websocket += 1,
+        }
+    }
+    stats
+}
+
+/// Rooms listed in a client's Redis session
+fn session_rooms(session: &serde_json::Value) -> Vec<String> {
+    session
+        .get("rooms")
+        .and_then(|r| r.as_array())
+        .map(|rooms| {
+            rooms
+                .iter()
+                .filter_map(|r| r.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether joining `room_name` would put a client already in `joined` over
+/// `max_rooms`. Rejoining a room it is already in never counts.
+fn exceeds_room_limit(joined: &[String], room_name: &str, max_rooms: usize) -> bool {
+    max_rooms > 0 && joined.len() >= max_rooms && !joined.iter().any(|r| r == room_name)
+}
+
+/// Socket.IO room for the binary-frame members of `room_name`
+fn binary_room(room_name: &str) -> String {
+    format!("{}{}", room_name, BINARY_ROOM_SUFFIX)
+}
+
+/// Split a `crdt:join` payload into the room name and whether the client
+/// asked for binary frames. Legacy clients send just the room name; newer
+/// ones send `[roomName, { "binary": true }]`.
+fn parse_join_frame(frame: &serde_json::Value) -> std::result::Result<(&str, bool), &'static str> {
+    let (room_name, binary) = match frame {
+        serde_json::Value::String(room_name) => (room_name.as_str(), false),
+        serde_json::Value::Array(parts) => {
+            let Some(room_name) = parts.first().and_then(|room| room.as_str()) else {
+                return Err("crdt:join room name must be a string");
+            };
+            let binary = parts
+                .get(1)
+                .and_then(|options| options.get("binary"))
+                .and_then(|binary| binary.as_bool())
+                .unwrap_or(false);
+            (room_name, binary)
+        }
+        _ => return Err("crdt:join must be a room name or [roomName, options]"),
+    };
+    if room_name.ends_with(BINARY_ROOM_SUFFIX) {
+        return Err("crdt:join room name must not end with #binary");
+    }
+    Ok((room_name, binary))
+}
+
+/// Split a `crdt:message` payload into the room name and message bytes, or
+/// describe why it is malformed. Binary frames carry the room name as their
+/// only JSON argument and the bytes as an attachment; legacy frames are
+/// `[roomName, [byte, ...]]`.
+fn parse_message_frame<'a>(
+    frame: &'a serde_json::Value,
+    bin: &[Bytes],
+) -> std::result::Result<(&'a str, Vec<u8>), &'static str> {
+    if let Some(data) = bin.first() {
+        let room_name = frame
+            .as_str()
+            .or_else(|| frame.as_array()?.first()?.as_str())
+            .ok_or("crdt:message room name must be a string")?;
+        return Ok((room_name, data.to_vec()));
+    }
+
+    let Some(parts) = frame.as_array() else {
+        return Err("crdt:message must be an array of [roomName, data]");
+    };
+    let [room, data, ..] = parts.as_slice() else {
+        return Err("crdt:message must have a room name and a data array");
+    };
+    let (Some(room_name), Some(data)) = (room.as_str(), data.as_array()) else {
+        return Err("crdt:message room name must be a string and data an array");
+    };
+    let bytes = data
+        .iter()
+        .map(|value| value.as_u64().and_then(|n| u8::try_from(n).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("crdt:message data must only contain bytes (0-255)")?;
+    Ok((room_name, bytes))
+}
+
+/// Redis fan-out payload: the publishing instance's id followed by the
+/// message bytes
+fn encode_fanout(origin: &uuid::Uuid, data: &[u8]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(16 + data.len());
+    payload.extend_from_slice(origin.as_bytes());
+    payload.extend_from_slice(data);
+    payload
+}
+
+/// Split a Redis fan-out payload into the publishing instance's id and the
+/// message bytes
+fn decode_fanout(payload: &[u8]) -> Option<(uuid::Uuid, &[u8])> {
+    if payload.len() <= 16 {
+        return None;
+    }
+    let (origin, data) = payload.split_at(16);
+    Some((uuid::Uuid::from_slice(origin).ok()?, data))
+}
+
+/// Legacy `crdt:message` payload: `[roomName, [byte, ...]]`
+fn json_message_frame(room_name: &str, data: &[u8]) -> serde_json::Value {
+    json!([room_name, data])
 }
 
 impl CRDTServer {
@@ -36,24 +364,199 @@ impl CRDTServer {
             config,
             rooms: Arc::new(DashMap::new()),
             redis: Arc::new(redis),
+            io: OnceLock::new(),
+            awareness_snapshots: DashMap::new(),
+            malformed_frames: std::sync::atomic::AtomicU64::new(0),
+            binary_members: DashSet::new(),
+            instance_id: uuid::Uuid::new_v4(),
         }
     }
 
-    pub async fn start(self: Arc<Self>) -> Result<()> {
-        // Connect to Redis if enabled
-        if let Err(e) = self.redis.connect().await {
-            warn!(
-                "Failed to connect to Redis: {}, continuing without persistence",
-                e
-            );
+    /// Awareness states to answer a QUERY_AWARENESS with. Repeated queries from
+    /// the same client within the configured cooldown get the cached snapshot
+    /// instead of re-gathering every state in the room.
+    fn awareness_snapshot(
+        &self,
+        room: &CRDTRoom,
+        room_name: &str,
+        client_id: &str,
+    ) -> Arc<Vec<Vec<u8>>> {
+        let cooldown = std::time::Duration::from_millis(self.config.awareness_query_cooldown_ms);
+        let key = (client_id.to_string(), room_name.to_string());
+        if let Some(entry) = self.awareness_snapshots.get(&key) {
+            let (taken_at, snapshot) = entry.value();
+            if taken_at.elapsed() < cooldown {
+                debug!(
+                    "Reusing awareness snapshot for client {} in room {}",
+                    client_id, room_name
+                );
+                return snapshot.clone();
+            }
+        }
+
+        let snapshot = Arc::new(room.get_awareness_states_for_client(client_id));
+        if !cooldown.is_zero() {
+            self.awareness_snapshots
+                .insert(key, (std::time::Instant::now(), snapshot.clone()));
+        }
+        snapshot
+    }
+
+    /// Count a malformed frame and tell the sender why it was dropped
+    fn reject_malformed_frame(&self, socket: &SocketRef, reason: &str) {
+        self.malformed_frames
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        warn!("Dropping malformed frame from {}: {}", socket.id, reason);
+        socket
+            .emit(
+                "crdt:error",
+                json!({
+                    "error": reason,
+                    "code": "malformed_frame"
+                }),
+            )
+            .ok();
+    }
+
+    /// Whether `socket` negotiated binary frames when joining `room_name`
+    fn is_binary_member(&self, socket: &SocketRef, room_name: &str) -> bool {
+        self.binary_members
+            .contains(&(socket.id.to_string(), room_name.to_string()))
+    }
+
+    /// Send a `crdt:message` to one client in the encoding it negotiated
+    fn emit_crdt_message(&self, socket: &SocketRef, room_name: &str, data: &[u8]) {
+        if self.is_binary_member(socket, room_name) {
+            socket
+                .bin(vec![Bytes::copy_from_slice(data)])
+                .emit("crdt:message", room_name)
+                .ok();
+        } else {
+            socket
+                .emit("crdt:message", json_message_frame(room_name, data))
+                .ok();
+        }
+    }
+
+    /// Send a `crdt:message` to every other client in the room, as a binary
+    /// frame to those that negotiated it and a JSON array to the rest
+    fn broadcast_crdt_message(&self, socket: &SocketRef, room_name: &str, data: &[u8]) {
+        socket
+            .to(binary_room(room_name))
+            .bin(vec![Bytes::copy_from_slice(data)])
+            .emit("crdt:message", room_name)
+            .ok();
+        socket
+            .to(room_name.to_string())
+            .except(binary_room(room_name))
+            .emit("crdt:message", json_message_frame(room_name, data))
+            .ok();
+    }
+
+    /// Publish a room broadcast for the other instances, if fan-out is enabled
+    async fn publish_fanout(&self, room_name: &str, data: &[u8]) {
+        let Some(payload) = self.fanout_payload(data) else {
+            return;
+        };
+        if let Err(e) = self.redis.publish_room_broadcast(room_name, &payload).await {
+            warn!("Failed to publish broadcast for room {}: {}", room_name, e);
+        }
+    }
+
+    /// Payload this instance publishes for a room broadcast, or `None` when
+    /// fan-out is disabled
+    fn fanout_payload(&self, data: &[u8]) -> Option<Vec<u8>> {
+        self.config
+            .enable_redis_fanout
+            .then(|| encode_fanout(&self.instance_id, data))
+    }
+
+    /// Relay broadcasts published by other instances to the local sockets,
+    /// resubscribing whenever the Redis subscription is lost
+    fn spawn_fanout_listener(self: &Arc<Self>) {
+        if !self.config.enable_redis_fanout {
+            return;
+        }
+        if !self.redis.is_enabled() {
+            warn!("Redis fan-out needs Redis persistence enabled; broadcasts stay local");
+            return;
+        }
+
+        let server = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match server.redis.subscribe_room_broadcasts().await {
+                    Ok(messages) => {
+                        futures_util::pin_mut!(messages);
+                        while let Some((room_name, payload)) = messages.next().await {
+                            server.relay_fanout(&room_name, &payload).await;
+                        }
+                        warn!("Redis fan-out subscription ended, resubscribing");
+                    }
+                    Err(e) => warn!("Failed to subscribe to Redis fan-out: {}", e),
+                }
+                tokio::time::sleep(FANOUT_RESUBSCRIBE_DELAY).await;
+            }
+        });
+    }
+
+    /// Apply a broadcast from another instance to the local room and emit it
+    /// to the local sockets in that room
+    async fn relay_fanout(&self, room_name: &str, payload: &[u8]) {
+        let Some((origin, data)) = decode_fanout(payload) else {
+            warn!("Dropping malformed fan-out message for room {}", room_name);
+            return;
+        };
+        if origin == self.instance_id {
+            return;
+        }
+        let Some(room) = self.rooms.get(room_name).map(|room| room.value().clone()) else {
+            // No local sockets; the room loads the merged state from Redis on join
+            return;
+        };
+
+        // Keep the local document current for clients that sync from it later.
+        // Awareness is only relayed; see "Running multiple instances" in the README.
+        if data[0] == 0 {
+            if let Err(e) = room.handle_message(FANOUT_CLIENT_ID, data).await {
+                warn!(
+                    "Failed to apply fan-out update to room {}: {}",
+                    room_name, e
+                );
+            }
         }
 
+        let Some(io) = self.io.get() else {
+            return;
+        };
+        debug!(
+            "Relaying {} byte broadcast from instance {} to room {}",
+            data.len(),
+            origin,
+            room_name
+        );
+        io.to(binary_room(room_name))
+            .bin(vec![Bytes::copy_from_slice(data)])
+            .emit("crdt:message", room_name)
+            .ok();
+        io.to(room_name.to_string())
+            .except(binary_room(room_name))
+            .emit("crdt:message", json_message_frame(room_name, data))
+            .ok();
+    }
+
+    /// Build the HTTP app: Socket.IO handlers plus the plain HTTP routes
+    pub fn router(self: &Arc<Self>) -> Result<axum::Router> {
         // Create Socket.IO layer with configuration
-        let (layer, io) = SocketIo::builder()
+        let mut builder = SocketIo::builder()
             .ping_interval(std::time::Duration::from_secs(25))
-            .ping_timeout(std::time::Duration::from_secs(60))
-            // .ack_timeout(std::time::Duration::from_secs(300))
-            .build_layer();
+            .ping_timeout(std::time::Duration::from_secs(60));
+        // .ack_timeout(std::time::Duration::from_secs(300))
+        if self.config.websocket_only {
+            builder = builder.transports([TransportType::Websocket]);
+        }
+        let (layer, io) = builder.build_layer();
+        let _ = self.io.set(io.clone());
 
         // Set up Socket.IO event handlers
         io.ns("/", {
@@ -61,7 +564,32 @@ impl CRDTServer {
             move |socket: SocketRef| {
                 let server = server.clone();
                 async move {
-                    info!("Client connected: {}", socket.id);
+                    let ip = client_ip(socket.req_parts(), server.config.trust_proxy)
+                        .map(|ip| ip.to_string())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    info!(
+                        correlation_id = %correlation_id(&socket),
+                        client_ip = %ip,
+                        "Client connected: {} (transport: {:?})",
+                        socket.id,
+                        socket.transport_type()
+                    );
+
+                    // Clients stuck on long-polling never upgrade and see high latency
+                    if socket.transport_type() == TransportType::Polling {
+                        let socket = socket.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(TRANSPORT_UPGRADE_CHECK).await;
+                            if socket.connected()
+                                && socket.transport_type() == TransportType::Polling
+                            {
+                                warn!(
+                                    "Client {} still on polling transport after {:?}, WebSocket upgrade may be failing",
+                                    socket.id, TRANSPORT_UPGRADE_CHECK
+                                );
+                            }
+                        });
+                    }
                     
                     // Store session in Redis
                     if let Err(e) = server.redis.save_client_session(&socket.id.to_string(), &json!({
@@ -71,60 +599,65 @@ impl CRDTServer {
                         warn!("Failed to save client session: {}", e);
                     }
 
-                    // Handle joining a room
+                    // Handle joining a room, negotiating binary frames if asked
                     socket.on("crdt:join", {
                         let server = server.clone();
-                        move |socket: SocketRef, Data::<String>(room_name)| {
+                        move |socket: SocketRef, Data::<serde_json::Value>(frame)| {
                             let server = server.clone();
                             async move {
-                                if let Err(e) = server.handle_join(&socket, &room_name).await {
+                                let (room_name, binary) = match parse_join_frame(&frame) {
+                                    Ok(join) => join,
+                                    Err(reason) => {
+                                        server.reject_malformed_frame(&socket, reason);
+                                        return;
+                                    }
+                                };
+                                if let Err(e) = server.handle_join(&socket, room_name, binary).await {
                                     error!("Error handling join: {}", e);
                                 }
                             }
                         }
                     });
 
-                    // Handle CRDT messages - using array of numbers as workaround
+                    // Handle CRDT messages, either binary frames or legacy byte arrays
                     socket.on("crdt:message", {
                         let server = server.clone();
-                        move |socket: SocketRef, data: Data<serde_json::Value>| {
+                        move |socket: SocketRef, data: Data<serde_json::Value>, Bin(bin): Bin| {
                             let server = server.clone();
                             async move {
-                                debug!("Raw crdt:message data: {:?}", data.0);
-                                
-                                // Try to extract room name and data array
-                                match data.0 {
-                                    serde_json::Value::Array(arr) if arr.len() >= 2 => {
-                                        if let (Some(room_name), Some(data_arr)) = (arr[0].as_str(), arr[1].as_array()) {
-                                            // Convert JSON array to Vec<u8>
-                                            let mut bytes = Vec::new();
-                                            for val in data_arr {
-                                                if let Some(num) = val.as_u64() {
-                                                    bytes.push(num as u8);
-                                                }
-                                            }
-                                            
-                                            let message_type = if bytes.len() > 0 { bytes[0] } else { 255 };
-                                            let message_type_name = match message_type {
-                                                0 => "SYNC",
-                                                1 => "AWARENESS",
-                                                2 => "AUTH",
-                                                3 => "QUERY_AWARENESS",
-                                                _ => "UNKNOWN",
-                                            };
-                                            info!("Parsed crdt:message from {} for room: {}, type: {} ({}), size: {} bytes", 
-                                                socket.id, room_name, message_type, message_type_name, bytes.len());
-                                            
-                                            if let Err(e) = server.handle_message(&socket, room_name, &bytes).await {
-                                                error!("Error handling message: {}", e);
-                                            }
-                                        } else {
-                                            error!("Invalid message format in array");
-                                        }
-                                    }
-                                    _ => {
-                                        error!("Unexpected crdt:message format: {:?}", data.0);
+                                debug!(
+                                    "Raw crdt:message data: {:?} ({} attachments)",
+                                    data.0,
+                                    bin.len()
+                                );
+
+                                let (room_name, bytes) = match parse_message_frame(&data.0, &bin) {
+                                    Ok(frame) => frame,
+                                    Err(reason) => {
+                                        server.reject_malformed_frame(&socket, reason);
+                                        return;
                                     }
+                                };
+
+                                let message_type = bytes.first().copied().unwrap_or(255);
+                                let message_type_name = match message_type {
+                                    0 => "SYNC",
+                                    1 => "AWARENESS",
+                                    2 => "AUTH",
+                                    3 => "QUERY_AWARENESS",
+                                    _ => "UNKNOWN",
+                                };
+                                info!(
+                                    "Parsed crdt:message from {} for room: {}, type: {} ({}), size: {} bytes",
+                                    socket.id,
+                                    room_name,
+                                    message_type,
+                                    message_type_name,
+                                    bytes.len()
+                                );
+
+                                if let Err(e) = server.handle_message(&socket, room_name, &bytes).await {
+                                    error!("Error handling message: {}", e);
                                 }
                             }
                         }
@@ -157,12 +690,7 @@ impl CRDTServer {
 
         // Create the app with CORS - Socket.IO compatible
         let cors = CorsLayer::new()
-            .allow_origin([
-                "http://localhost:3000".parse::<axum::http::HeaderValue>()?,
-                "http://127.0.0.1:3000".parse::<axum::http::HeaderValue>()?,
-                "http://localhost:3001".parse::<axum::http::HeaderValue>()?,
-                "http://127.0.0.1:3001".parse::<axum::http::HeaderValue>()?,
-            ])
+            .allow_origin(self.config.cors_origins.clone())
             .allow_methods([
                 axum::http::Method::GET,
                 axum::http::Method::POST,
@@ -217,6 +745,43 @@ impl CRDTServer {
                     }
                 }),
             )
+            .route(
+                "/admin/broadcast",
+                axum::routing::post({
+                    let server = self.clone();
+                    move |headers: axum::http::HeaderMap,
+                          body: std::result::Result<
+                        axum::Json<AnnouncementRequest>,
+                        axum::extract::rejection::JsonRejection,
+                    >| {
+                        let server = server.clone();
+                        async move { server.handle_broadcast(&headers, body).await }
+                    }
+                }),
+            )
+            .route(
+                "/rooms/:name/snapshot",
+                axum::routing::get({
+                    let server = self.clone();
+                    move |headers: axum::http::HeaderMap,
+                          axum::extract::Path(name): axum::extract::Path<String>| {
+                        let server = server.clone();
+                        async move { server.handle_room_snapshot(&headers, &name).await }
+                    }
+                }),
+            )
+            .route(
+                "/rooms/:name/seed",
+                axum::routing::post({
+                    let server = self.clone();
+                    move |headers: axum::http::HeaderMap,
+                          axum::extract::Path(name): axum::extract::Path<String>,
+                          body: axum::body::Bytes| {
+                        let server = server.clone();
+                        async move { server.handle_room_seed(&headers, &name, &body).await }
+                    }
+                }),
+            )
             .layer(
                 ServiceBuilder::new()
                     .layer(TimeoutLayer::new(std::time::Duration::from_secs(5))) // Add 5s timeout for HTTP requests
@@ -224,6 +789,22 @@ impl CRDTServer {
                     .layer(layer),
             );
 
+        Ok(app)
+    }
+
+    pub async fn start(self: Arc<Self>) -> Result<()> {
+        // Connect to Redis if enabled
+        if let Err(e) = self.redis.connect().await {
+            warn!(
+                "Failed to connect to Redis: {}, continuing without persistence",
+                e
+            );
+        }
+
+        let app = self.router()?;
+        self.spawn_client_sweeper();
+        self.spawn_fanout_listener();
+
         // Start the server with connection limit
         let listener =
             tokio::net::TcpListener::bind(format!("0.0.0.0:{}", self.config.port)).await?;
@@ -237,17 +818,115 @@ impl CRDTServer {
         );
 
         // Use axum's serve with a configured server
+        let server = self.clone();
         axum::serve(
             listener,
             app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
         )
+        .with_graceful_shutdown(async move {
+            shutdown_signal().await;
+            info!("🛑 Shutdown signal received");
+            server.shutdown().await;
+        })
         .await?;
 
         Ok(())
     }
 
-    async fn handle_join(&self, socket: &SocketRef, room_name: &str) -> Result<()> {
-        info!("Client {} joining room: {}", socket.id, room_name);
+    /// Periodically drop clients that have been inactive for longer than
+    /// `client_timeout_minutes`
+    fn spawn_client_sweeper(self: &Arc<Self>) {
+        if self.config.client_sweep_interval_secs == 0 {
+            return;
+        }
+
+        let server = self.clone();
+        let period = std::time::Duration::from_secs(self.config.client_sweep_interval_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                server.sweep_inactive_clients().await;
+            }
+        });
+    }
+
+    /// Run `cleanup_inactive_clients` on every room, then drop the rooms left
+    /// empty once their state is saved to Redis
+    async fn sweep_inactive_clients(&self) -> SweepSummary {
+        let rooms: Vec<(String, CRDTRoom)> = self
+            .rooms
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        let mut summary = SweepSummary::default();
+        for (room_name, room) in &rooms {
+            summary.clients_removed += room
+                .cleanup_inactive_clients(self.config.client_timeout_minutes)
+                .await;
+
+            if room.client_count() > 0 {
+                continue;
+            }
+            // Keep the room in memory if its state can't be saved
+            if let Err(e) = room.save_to_redis().await {
+                warn!(
+                    "Failed to save empty room {} to Redis: {}. Keeping room in memory.",
+                    room_name, e
+                );
+                continue;
+            }
+            // A client may have joined while the state was being saved
+            if self
+                .rooms
+                .remove_if(room_name, |_, room| room.client_count() == 0)
+                .is_some()
+            {
+                self.awareness_snapshots
+                    .retain(|(_, snapshot_room), _| snapshot_room != room_name);
+                summary.rooms_removed += 1;
+            }
+        }
+
+        if summary.clients_removed > 0 || summary.rooms_removed > 0 {
+            info!(
+                "🧹 Swept {} inactive clients and {} empty rooms across {} rooms",
+                summary.clients_removed,
+                summary.rooms_removed,
+                rooms.len()
+            );
+        } else {
+            debug!("Client sweep found no inactive clients");
+        }
+        summary
+    }
+
+    /// Create a room, restoring its state from Redis if there is any
+    async fn load_room(&self, room_name: &str) -> (CRDTRoom, Result<bool>) {
+        let room = CRDTRoom::with_redis(
+            room_name.to_string(),
+            self.config.clone(),
+            self.redis.clone(),
+        );
+        let loaded = room.load_from_redis().await;
+        (room, loaded)
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(correlation_id = %correlation_id(socket), socket_id = %socket.id, room = room_name)
+    )]
+    async fn handle_join(&self, socket: &SocketRef, room_name: &str, binary: bool) -> Result<()> {
+        info!(
+            "Client {} joining room: {} ({} frames)",
+            socket.id,
+            room_name,
+            if binary { "binary" } else { "JSON" }
+        );
 
         // Check room capacity
         if let Some(room) = self.rooms.get(room_name) {
@@ -264,18 +943,35 @@ impl CRDTServer {
             }
         }
 
+        // Check how many rooms this client is already in
+        let joined = self.client_rooms(&socket.id.to_string()).await;
+        if exceeds_room_limit(&joined, room_name, self.config.max_rooms_per_client) {
+            warn!(
+                "Client {} is already in {} rooms, rejecting join of {}",
+                socket.id,
+                joined.len(),
+                room_name
+            );
+            socket
+                .emit(
+                    "crdt:error",
+                    json!({
+                        "error": "Room limit reached",
+                        "code": "room_limit",
+                        "maxRooms": self.config.max_rooms_per_client
+                    }),
+                )
+                .ok();
+            return Ok(());
+        }
+
         // Get or create room
         let room = if let Some(existing_room) = self.rooms.get(room_name) {
             existing_room.value().clone()
         } else {
-            let new_room = CRDTRoom::with_redis(
-                room_name.to_string(),
-                self.config.clone(),
-                self.redis.clone(),
-            );
-
             // Always try to load existing state from Redis
-            match new_room.load_from_redis().await {
+            let (new_room, loaded) = self.load_room(room_name).await;
+            match loaded {
                 Ok(loaded) => {
                     if loaded {
                         info!("Restored room {} from Redis persistence", room_name);
@@ -295,8 +991,21 @@ impl CRDTServer {
             new_room
         };
 
-        // Join the socket.io room
+        // Keep a temporary room's stored state alive while it's in use
+        if let Err(e) = self.redis.refresh_room_ttl(room_name).await {
+            warn!("Failed to refresh TTL for room {}: {}", room_name, e);
+        }
+
+        // Join the socket.io room, plus its binary sub-room if negotiated
         socket.join(room_name.to_string()).ok();
+        let member = (socket.id.to_string(), room_name.to_string());
+        if binary {
+            socket.join(binary_room(room_name)).ok();
+            self.binary_members.insert(member);
+        } else {
+            socket.leave(binary_room(room_name)).ok();
+            self.binary_members.remove(&member);
+        }
 
         // Add client to CRDT room
         room.add_client(socket.id.to_string()).await?;
@@ -307,7 +1016,8 @@ impl CRDTServer {
                 "crdt:joined",
                 json!({
                     "roomName": room_name,
-                    "clientId": socket.id.to_string()
+                    "clientId": socket.id.to_string(),
+                    "binary": binary
                 }),
             )
             .ok();
@@ -354,6 +1064,10 @@ impl CRDTServer {
         Ok(())
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(correlation_id = %correlation_id(socket), socket_id = %socket.id, room = room_name)
+    )]
     async fn handle_message(&self, socket: &SocketRef, room_name: &str, data: &[u8]) -> Result<()> {
         if data.is_empty() {
             warn!("Received empty message from client {}", socket.id);
@@ -379,9 +1093,11 @@ impl CRDTServer {
                     socket.id, room_name
                 );
 
+                room.update_client_activity(&socket.id.to_string()).await;
+
                 // Get all awareness states for this client
                 let awareness_messages =
-                    room.get_awareness_states_for_client(&socket.id.to_string());
+                    self.awareness_snapshot(&room, room_name, &socket.id.to_string());
 
                 info!(
                     "Found {} awareness states to send to client {}",
@@ -391,20 +1107,7 @@ impl CRDTServer {
 
                 // Send each awareness state back to the requesting client
                 for (i, awareness_data) in awareness_messages.iter().enumerate() {
-                    let data_array = serde_json::Value::Array(
-                        awareness_data
-                            .iter()
-                            .map(|&b| serde_json::Value::Number(b.into()))
-                            .collect(),
-                    );
-
-                    // Wrap in the same format as regular messages: [roomName, dataArray]
-                    let message_payload = serde_json::Value::Array(vec![
-                        serde_json::Value::String(room_name.to_string()),
-                        data_array,
-                    ]);
-
-                    socket.emit("crdt:message", message_payload).ok();
+                    self.emit_crdt_message(socket, room_name, awareness_data);
                     info!("Sent awareness state {} to client {}", i + 1, socket.id);
                 }
 
@@ -426,19 +1129,7 @@ impl CRDTServer {
                     response.len()
                 );
 
-                let response_array = serde_json::Value::Array(
-                    response
-                        .iter()
-                        .map(|&b| serde_json::Value::Number(b.into()))
-                        .collect(),
-                );
-
-                let response_payload = serde_json::Value::Array(vec![
-                    serde_json::Value::String(room_name.to_string()),
-                    response_array,
-                ]);
-
-                socket.emit("crdt:message", response_payload).ok();
+                self.emit_crdt_message(socket, room_name, &response);
             }
 
             // Check if this is a SYNC message type
@@ -447,7 +1138,6 @@ impl CRDTServer {
             // Only broadcast SYNC Update messages (type 0) and AWARENESS messages (type 1)
             // Don't broadcast AUTH (2), QUERY_AWARENESS (3), or other message types
             if message_type == 0 || message_type == 1 {
-                let data_vec: Vec<u8> = data.to_vec();
                 info!(
                     "Broadcasting {} message to room {} (excluding sender {}), data size: {} bytes",
                     if message_type == 0 {
@@ -457,27 +1147,11 @@ impl CRDTServer {
                     },
                     room_name,
                     socket.id,
-                    data_vec.len()
-                );
-
-                // Convert Vec<u8> to a JSON array for socketioxide
-                let data_array = serde_json::Value::Array(
-                    data_vec
-                        .iter()
-                        .map(|&b| serde_json::Value::Number(b.into()))
-                        .collect(),
+                    data.len()
                 );
 
-                // Use consistent format: [roomName, dataArray] for all messages
-                let message_payload = serde_json::Value::Array(vec![
-                    serde_json::Value::String(room_name.to_string()),
-                    data_array,
-                ]);
-
-                socket
-                    .to(room_name.to_string())
-                    .emit("crdt:message", message_payload)
-                    .ok();
+                self.broadcast_crdt_message(socket, room_name, data);
+                self.publish_fanout(room_name, data).await;
             } else {
                 debug!(
                     "Not broadcasting message type {} to room {}",
@@ -494,11 +1168,21 @@ impl CRDTServer {
         Ok(())
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(correlation_id = %correlation_id(socket), socket_id = %socket.id, room = room_name)
+    )]
     async fn handle_leave(&self, socket: &SocketRef, room_name: &str) {
         info!("Client {} leaving room: {}", socket.id, room_name);
 
         // First leave the socket.io room to prevent further events
         socket.leave(room_name.to_string()).ok();
+        socket.leave(binary_room(room_name)).ok();
+        self.awareness_snapshots
+            .remove(&(socket.id.to_string(), room_name.to_string()));
+        self.binary_members
+            .remove(&(socket.id.to_string(), room_name.to_string()));
+        self.forget_session_room(&socket.id.to_string(), room_name).await;
 
         if let Some(room) = self.rooms.get(room_name) {
             // Only remove if client is actually in the room
@@ -524,9 +1208,60 @@ impl CRDTServer {
         }
     }
 
+    /// Rooms a client is in, from its Redis session. Without a session (Redis
+    /// disabled or unreachable) the in-memory rooms are checked instead.
+    async fn client_rooms(&self, client_id: &str) -> Vec<String> {
+        if let Ok(Some(session_str)) = self.redis.get_client_session(client_id).await {
+            if let Ok(session) = serde_json::from_str::<serde_json::Value>(&session_str) {
+                return session_rooms(&session);
+            }
+        }
+
+        let rooms: Vec<(String, CRDTRoom)> = self
+            .rooms
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        let mut joined = Vec::new();
+        for (room_name, room) in rooms {
+            if room.has_client(client_id).await {
+                joined.push(room_name);
+            }
+        }
+        joined
+    }
+
+    /// Drop a room the client left from its Redis session, so it no longer
+    /// counts toward `max_rooms_per_client`
+    async fn forget_session_room(&self, client_id: &str, room_name: &str) {
+        let Ok(Some(session_str)) = self.redis.get_client_session(client_id).await else {
+            return;
+        };
+        let Ok(mut session) = serde_json::from_str::<serde_json::Value>(&session_str) else {
+            return;
+        };
+        if let Some(rooms) = session.get_mut("rooms").and_then(|r| r.as_array_mut()) {
+            rooms.retain(|r| r.as_str() != Some(room_name));
+        }
+        if let Ok(updated_session) = serde_json::to_string(&session) {
+            let _ = self
+                .redis
+                .save_client_session(client_id, &updated_session)
+                .await;
+        }
+    }
+
+    #[tracing::instrument(
+        skip_all,
+        fields(correlation_id = %correlation_id(socket), socket_id = %socket.id)
+    )]
     async fn handle_disconnect(&self, socket: &SocketRef) {
         info!("Client disconnected: {}", socket.id);
         let socket_id = socket.id.to_string();
+        self.awareness_snapshots
+            .retain(|(client_id, _), _| *client_id != socket_id);
+        self.binary_members
+            .retain(|(client_id, _)| *client_id != socket_id);
 
         // Get client's rooms from Redis session
         let mut client_rooms = Vec::new();
@@ -545,11 +1280,15 @@ impl CRDTServer {
                     }
                 }
 
-                // Keep session alive for 30 seconds to allow reconnection
+                // Keep session alive for the grace period to allow reconnection
                 if let Ok(updated_session) = serde_json::to_string(&session) {
                     let _ = self
                         .redis
-                        .save_client_session_with_ttl(&socket_id, &updated_session, 30)
+                        .save_client_session_with_ttl(
+                            &socket_id,
+                            &updated_session,
+                            self.config.reconnect_grace_secs,
+                        )
                         .await;
                 }
             }
@@ -564,8 +1303,8 @@ impl CRDTServer {
         }
 
         info!(
-            "Client {} disconnected but keeping in rooms for 30s grace period",
-            socket_id
+            "Client {} disconnected but keeping in rooms for {}s grace period",
+            socket_id, self.config.reconnect_grace_secs
         );
     }
 
@@ -633,30 +1372,286 @@ impl CRDTServer {
         }
     }
 
-    pub async fn get_stats(&self) -> axum::Json<serde_json::Value> {
+    /// Flush every room to Redis, disconnect clients and report what happened.
+    /// The summary is logged and, if configured, written to a JSON file.
+    pub async fn shutdown(&self) -> ShutdownSummary {
+        let rooms: Vec<CRDTRoom> = self
+            .rooms
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        let mut clients = std::collections::HashSet::new();
+        let mut summary = ShutdownSummary::default();
+
+        for room in rooms {
+            clients.extend(room.clients.iter().map(|entry| entry.key().clone()));
+            match room.save_to_redis().await {
+                Ok(bytes) => {
+                    summary.rooms_flushed += 1;
+                    summary.bytes_persisted += bytes;
+                }
+                Err(e) => {
+                    error!("Failed to save room {} during shutdown: {}", room.name, e);
+                    summary.failed_rooms.push(FailedRoom {
+                        room: room.name.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        let sockets = self
+            .io
+            .get()
+            .and_then(|io| io.sockets().ok())
+            .unwrap_or_default();
+        for socket in sockets {
+            clients.insert(socket.id.to_string());
+            let _ = socket.disconnect();
+        }
+        summary.clients_disconnected = clients.len();
+
+        info!(
+            rooms_flushed = summary.rooms_flushed,
+            clients_disconnected = summary.clients_disconnected,
+            bytes_persisted = summary.bytes_persisted,
+            failed_rooms = %json!(summary.failed_rooms),
+            "Shutdown summary"
+        );
+
+        if let Some(path) = &self.config.shutdown_summary_path {
+            let written = serde_json::to_vec_pretty(&summary)
+                .map_err(anyhow::Error::from)
+                .and_then(|bytes| std::fs::write(path, bytes).map_err(anyhow::Error::from));
+            if let Err(e) = written {
+                error!(
+                    "Failed to write shutdown summary to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+
+        summary
+    }
+
+    pub async fn get_stats(&self) -> axum::Json<ServerStats> {
         let total_clients: usize = self
             .rooms
             .iter()
             .map(|entry| entry.value().client_count())
             .sum();
 
-        axum::Json(json!({
-            "status": "running",
-            "rooms": self.rooms.len(),
-            "totalClients": total_clients,
-            "roomDetails": self.rooms.iter()
+        let transports = self
+            .io
+            .get()
+            .and_then(|io| io.sockets().ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|socket| socket.transport_type());
+
+        axum::Json(ServerStats {
+            malformed_frames: self
+                .malformed_frames
+                .load(std::sync::atomic::Ordering::Relaxed),
+            room_details: self
+                .rooms
+                .iter()
                 .map(|entry| {
                     let (name, room) = entry.pair();
-                    json!({
-                        "name": name,
-                        "clients": room.client_count()
-                    })
+                    RoomStats {
+                        clients: room.client_count(),
+                        name: name.clone(),
+                    }
                 })
-                .collect::<Vec<_>>()
-        }))
+                .collect(),
+            rooms: self.rooms.len(),
+            status: "running",
+            total_clients,
+            transports: transport_breakdown(transports),
+        })
+    }
+
+    /// Check `Authorization: Bearer <admin token>` for the admin-only
+    /// endpoints; they answer 404 when no admin token is configured
+    fn authorize_admin(
+        &self,
+        headers: &axum::http::HeaderMap,
+    ) -> std::result::Result<(), (axum::http::StatusCode, axum::Json<serde_json::Value>)> {
+        use axum::http::StatusCode;
+
+        let Some(admin_token) = self.config.admin_token.as_deref() else {
+            return Err((
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "error": "admin endpoints are disabled" })),
+            ));
+        };
+        let authorized = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), admin_token.as_bytes()));
+        if !authorized {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                axum::Json(json!({ "error": "invalid admin token" })),
+            ));
+        }
+        Ok(())
+    }
+
+    /// JSON view of a loaded room's document. Admin-only, since it exposes
+    /// the full workflow contents.
+    async fn handle_room_snapshot(
+        &self,
+        headers: &axum::http::HeaderMap,
+        room_name: &str,
+    ) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+        use axum::http::StatusCode;
+
+        if let Err(rejection) = self.authorize_admin(headers) {
+            return rejection;
+        }
+        let Some(room) = self.rooms.get(room_name).map(|room| room.value().clone()) else {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(json!({ "error": "room not found" })),
+            );
+        };
+        (StatusCode::OK, axum::Json(room.to_json().await))
     }
 
-    pub async fn health_check(&self) -> axum::Json<serde_json::Value> {
+    /// Create a room if needed and give it the request body, a Yjs v1
+    /// update, as its initial state. Admin-only and idempotent: a room that
+    /// already has state, in memory or in Redis, is left untouched.
+    async fn handle_room_seed(
+        &self,
+        headers: &axum::http::HeaderMap,
+        room_name: &str,
+        body: &[u8],
+    ) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+        use axum::http::StatusCode;
+
+        if let Err(rejection) = self.authorize_admin(headers) {
+            return rejection;
+        }
+
+        let initial = match CRDTRoom::decode_seed(body) {
+            Ok(doc) => doc,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    axum::Json(json!({ "error": e.to_string() })),
+                )
+            }
+        };
+
+        let room = match self.rooms.get(room_name).map(|room| room.value().clone()) {
+            Some(room) => room,
+            None => {
+                let (room, loaded) = self.load_room(room_name).await;
+                // Seeding over state that could not be read would clobber it
+                if let Err(e) = loaded {
+                    warn!("Failed to load room {} from Redis: {}", room_name, e);
+                    return (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        axum::Json(json!({ "error": "failed to load room state" })),
+                    );
+                }
+                self.rooms
+                    .entry(room_name.to_string())
+                    .or_insert(room)
+                    .value()
+                    .clone()
+            }
+        };
+
+        let seeded = match room.seed(&initial).await {
+            Ok(seeded) => seeded,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(json!({ "error": e.to_string() })),
+                )
+            }
+        };
+        if seeded {
+            if let Err(e) = room.save_to_redis().await {
+                error!("Failed to persist seeded room {}: {}", room_name, e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    axum::Json(json!({ "error": e.to_string() })),
+                );
+            }
+            info!("🌱 Seeded room {} with {} bytes", room_name, body.len());
+        } else {
+            info!("Room {} already has state, skipped seeding", room_name);
+        }
+
+        (
+            StatusCode::OK,
+            axum::Json(json!({ "room": room_name, "seeded": seeded })),
+        )
+    }
+
+    /// Emit a `crdt:announcement` to every connected client, whatever room
+    /// it is in. Requires `Authorization: Bearer <admin token>`; the endpoint
+    /// answers 404 when no admin token is configured.
+    async fn handle_broadcast(
+        &self,
+        headers: &axum::http::HeaderMap,
+        body: std::result::Result<
+            axum::Json<AnnouncementRequest>,
+            axum::extract::rejection::JsonRejection,
+        >,
+    ) -> (axum::http::StatusCode, axum::Json<serde_json::Value>) {
+        use axum::http::StatusCode;
+
+        if let Err(rejection) = self.authorize_admin(headers) {
+            return rejection;
+        }
+
+        let announcement = match body {
+            Ok(axum::Json(announcement)) => announcement,
+            Err(rejection) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    axum::Json(json!({ "error": rejection.body_text() })),
+                )
+            }
+        };
+        let Some(io) = self.io.get() else {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                axum::Json(json!({ "error": "socket server not started" })),
+            );
+        };
+
+        let payload = json!({
+            "message": announcement.message,
+            "level": announcement.level.as_deref().unwrap_or("info"),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        });
+        let recipients = io
+            .sockets()
+            .map(|sockets| sockets.len())
+            .unwrap_or_default();
+        if let Err(e) = io.emit("crdt:announcement", payload) {
+            error!("Failed to broadcast announcement: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                axum::Json(json!({ "error": e.to_string() })),
+            );
+        }
+
+        info!("📣 Broadcast announcement to {} clients", recipients);
+        (
+            StatusCode::OK,
+            axum::Json(json!({ "recipients": recipients })),
+        )
+    }
+
+    pub async fn health_check(&self) -> axum::Json<HealthReport> {
         let redis_healthy = if self.redis.is_enabled() {
             self.redis.health_check().await.unwrap_or(false)
         } else {
@@ -665,18 +1660,895 @@ impl CRDTServer {
 
         let status = if redis_healthy { "healthy" } else { "degraded" };
 
-        axum::Json(json!({
-            "status": status,
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "service": "zeal-crdt-server",
-            "checks": {
-                "server": "healthy",
-                "redis": if self.redis.is_enabled() {
-                    if redis_healthy { "healthy" } else { "unhealthy" }
-                } else {
-                    "disabled"
+        let redis = if !self.redis.is_enabled() {
+            "disabled"
+        } else if redis_healthy {
+            "healthy"
+        } else {
+            "unhealthy"
+        };
+
+        axum::Json(HealthReport {
+            checks: HealthChecks {
+                redis,
+                server: "healthy",
+            },
+            service: "zeal-crdt-server",
+            status,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_summary() {
+        let summary_path =
+            std::env::temp_dir().join(format!("shutdown-summary-{}.json", uuid::Uuid::new_v4()));
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            shutdown_summary_path: Some(summary_path.clone()),
+            ..ServerConfig::default()
+        };
+        let server = CRDTServer::new(config.clone());
+
+        let saved = CRDTRoom::with_redis("saved".to_string(), config.clone(), server.redis.clone());
+        saved.add_client("client-1".to_string()).await.unwrap();
+        saved.add_client("client-2".to_string()).await.unwrap();
+        server.rooms.insert("saved".to_string(), saved);
+
+        // A fake Redis that rejects every command, so the save fails
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let redis_url = format!("redis://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = stream.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        // One error reply per pipelined command (RESP arrays start with '*')
+                        let commands = String::from_utf8_lossy(&buf[..n])
+                            .split("\r\n")
+                            .filter(|line| line.starts_with('*'))
+                            .count();
+                        let reply = "-ERR unavailable\r\n".repeat(commands);
+                        if stream.write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        let rejecting = Arc::new(RedisManager::new(redis_url, true).unwrap());
+        let failing = CRDTRoom::with_redis("failing".to_string(), config, rejecting);
+        failing.add_client("client-2".to_string()).await.unwrap();
+        server.rooms.insert("failing".to_string(), failing);
+
+        let summary = server.shutdown().await;
+        assert_eq!(summary.rooms_flushed, 1);
+        assert_eq!(summary.clients_disconnected, 2);
+        assert_eq!(summary.bytes_persisted, 0);
+        assert_eq!(summary.failed_rooms.len(), 1);
+        assert_eq!(summary.failed_rooms[0].room, "failing");
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&summary_path).unwrap()).unwrap();
+        std::fs::remove_file(&summary_path).unwrap();
+        assert_eq!(written["roomsFlushed"], 1);
+        assert_eq!(written["failedRooms"][0]["room"], "failing");
+    }
+
+    #[tokio::test]
+    async fn test_awareness_query_cooldown() {
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            awareness_query_cooldown_ms: 200,
+            ..ServerConfig::default()
+        };
+        let server = CRDTServer::new(config.clone());
+        let room = CRDTRoom::with_redis("room".to_string(), config, server.redis.clone());
+        room.awareness_states
+            .insert("client-1".to_string(), vec![1, 2, 3]);
+
+        let first = server.awareness_snapshot(&room, "room", "client-1");
+        assert_eq!(first.len(), 1);
+
+        // Rapid repeat queries are answered from the cached snapshot
+        room.awareness_states
+            .insert("client-2".to_string(), vec![4, 5, 6]);
+        for _ in 0..10 {
+            let repeated = server.awareness_snapshot(&room, "room", "client-1");
+            assert!(Arc::ptr_eq(&first, &repeated));
+        }
+
+        // Other clients are not affected by client-1's cooldown
+        assert_eq!(
+            server.awareness_snapshot(&room, "room", "client-2").len(),
+            2
+        );
+
+        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+        assert_eq!(
+            server.awareness_snapshot(&room, "room", "client-1").len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sweep_inactive_clients() {
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            client_timeout_minutes: 1,
+            ..ServerConfig::default()
+        };
+        let server = CRDTServer::new(config.clone());
+        let room =
+            CRDTRoom::with_redis("room".to_string(), config.clone(), server.redis.clone());
+        room.add_client("active".to_string()).await.unwrap();
+        room.add_client("stale".to_string()).await.unwrap();
+        room.awareness_states
+            .insert("stale".to_string(), vec![1, 2, 3]);
+        let two_minutes_ago = std::time::Instant::now()
+            .checked_sub(std::time::Duration::from_secs(120))
+            .unwrap();
+        room.clients.insert("stale".to_string(), two_minutes_ago);
+        server.rooms.insert("room".to_string(), room.clone());
+
+        let abandoned =
+            CRDTRoom::with_redis("abandoned".to_string(), config, server.redis.clone());
+        abandoned.add_client("gone".to_string()).await.unwrap();
+        abandoned.clients.insert("gone".to_string(), two_minutes_ago);
+        server
+            .rooms
+            .insert("abandoned".to_string(), abandoned.clone());
+
+        assert_eq!(
+            server.sweep_inactive_clients().await,
+            SweepSummary {
+                clients_removed: 2,
+                rooms_removed: 1,
+            }
+        );
+        assert!(room.has_client("active").await);
+        assert!(!room.has_client("stale").await);
+        assert!(room.awareness_states.get("stale").is_none());
+        assert!(server.rooms.contains_key("room"));
+        assert!(!server.rooms.contains_key("abandoned"));
+
+        assert_eq!(
+            server.sweep_inactive_clients().await,
+            SweepSummary::default()
+        );
+    }
+
+    #[test]
+    fn test_correlation_id_from_handshake() {
+        let parts = |uri: &str, header: Option<&str>| {
+            let mut request = axum::http::Request::builder().uri(uri);
+            if let Some(value) = header {
+                request = request.header(CORRELATION_ID_HEADER, value);
+            }
+            request.body(()).unwrap().into_parts().0
+        };
+
+        assert_eq!(
+            correlation_id_from_handshake(&parts("/socket.io/?EIO=4&correlationId=req-42", None)),
+            Some("req-42".to_string())
+        );
+        assert_eq!(
+            correlation_id_from_handshake(&parts("/socket.io/?correlationId=q", Some("h.1"))),
+            Some("h.1".to_string())
+        );
+        assert_eq!(
+            correlation_id_from_handshake(&parts("/socket.io/?EIO=4", None)),
+            None
+        );
+        assert_eq!(
+            correlation_id_from_handshake(&parts("/socket.io/?correlationId=a%0Ab", None)),
+            None
+        );
+    }
+
+    fn proxied_request(headers: &[(&str, &str)]) -> axum::http::request::Parts {
+        let mut request = axum::http::Request::builder().uri("/socket.io/");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+        parts
+            .extensions
+            .insert(axum::extract::ConnectInfo(std::net::SocketAddr::from((
+                [10, 0, 0, 1],
+                443,
+            ))));
+        parts
+    }
+
+    #[test]
+    fn test_client_ip_ignores_forwarded_headers_without_trust_proxy() {
+        let parts = proxied_request(&[("x-forwarded-for", "203.0.113.7")]);
+        assert_eq!(client_ip(&parts, false), Some([10, 0, 0, 1].into()));
+    }
+
+    #[test]
+    fn test_client_ip_with_trust_proxy() {
+        let parts = proxied_request(&[
+            ("x-forwarded-for", "203.0.113.7"),
+            ("x-real-ip", "198.51.100.1"),
+        ]);
+        assert_eq!(client_ip(&parts, true), Some([203, 0, 113, 7].into()));
+
+        // A client-supplied prefix is ignored in favour of the address the
+        // proxy appended
+        let parts = proxied_request(&[("x-forwarded-for", "1.2.3.4, 203.0.113.7")]);
+        assert_eq!(client_ip(&parts, true), Some([203, 0, 113, 7].into()));
+
+        let parts = proxied_request(&[
+            ("x-forwarded-for", "garbage"),
+            ("x-real-ip", "198.51.100.1"),
+        ]);
+        assert_eq!(client_ip(&parts, true), Some([198, 51, 100, 1].into()));
+
+        let parts = proxied_request(&[]);
+        assert_eq!(client_ip(&parts, true), Some([10, 0, 0, 1].into()));
+    }
+
+    #[test]
+    fn test_transport_breakdown() {
+        let stats = transport_breakdown([
+            TransportType::Polling,
+            TransportType::Websocket,
+            TransportType::Websocket,
+        ]);
+        assert_eq!(
+            serde_json::to_value(stats).unwrap(),
+            json!({ "polling": 1, "websocket": 2 })
+        );
+
+        assert_eq!(
+            serde_json::to_value(transport_breakdown([])).unwrap(),
+            json!({ "polling": 0, "websocket": 0 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats_and_health_shape() {
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            ..ServerConfig::default()
+        };
+        let server = CRDTServer::new(config.clone());
+        let room = CRDTRoom::with_redis("room-a".to_string(), config, server.redis.clone());
+        room.add_client("client-1".to_string()).await.unwrap();
+        room.add_client("client-2".to_string()).await.unwrap();
+        server.rooms.insert("room-a".to_string(), room);
+
+        // Byte-for-byte what the handler produced before it was typed, plus
+        // the malformed frame counter
+        let axum::Json(stats) = server.get_stats().await;
+        assert_eq!(
+            serde_json::to_string(&stats).unwrap(),
+            json!({
+                "malformedFrames": 0,
+                "status": "running",
+                "rooms": 1,
+                "totalClients": 2,
+                "transports": { "polling": 0, "websocket": 0 },
+                "roomDetails": [{ "name": "room-a", "clients": 2 }]
+            })
+            .to_string()
+        );
+
+        let axum::Json(health) = server.health_check().await;
+        assert_eq!(
+            serde_json::to_string(&health).unwrap(),
+            json!({
+                "status": "healthy",
+                "timestamp": health.timestamp,
+                "service": "zeal-crdt-server",
+                "checks": { "server": "healthy", "redis": "disabled" }
+            })
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_fanout_payload_round_trip() {
+        let origin = uuid::Uuid::new_v4();
+        let payload = encode_fanout(&origin, &[0, 2, 7]);
+        assert_eq!(payload.len(), 19);
+        assert_eq!(decode_fanout(&payload), Some((origin, &[0u8, 2, 7][..])));
+
+        assert_eq!(decode_fanout(origin.as_bytes()), None);
+        assert_eq!(decode_fanout(&[1, 2, 3]), None);
+    }
+
+    #[tokio::test]
+    async fn test_relay_fanout_skips_own_messages() {
+        use crate::sync_protocol::SyncProtocol;
+        use yrs::{Map, ReadTxn, Transact};
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            ..ServerConfig::default()
+        };
+        let server = CRDTServer::new(config.clone());
+        let room = CRDTRoom::with_redis("room".to_string(), config, server.redis.clone());
+        server.rooms.insert("room".to_string(), room.clone());
+
+        let source = yrs::Doc::new();
+        let metadata = source.get_or_insert_map("metadata");
+        metadata.insert(&mut source.transact_mut(), "name", "Demo");
+        let update = source
+            .transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default());
+        let mut message = vec![0];
+        SyncProtocol::write_update(&mut message, &update).unwrap();
+
+        let has_metadata = || async {
+            let doc = room.doc.read().await;
+            let txn = doc.transact();
+            txn.get_map("metadata").is_some()
+        };
+
+        server
+            .relay_fanout("room", &encode_fanout(&server.instance_id, &message))
+            .await;
+        assert!(!has_metadata().await);
+
+        server
+            .relay_fanout("room", &encode_fanout(&uuid::Uuid::new_v4(), &message))
+            .await;
+        assert!(has_metadata().await);
+    }
+
+    #[tokio::test]
+    async fn test_fanout_between_instances() {
+        use crate::sync_protocol::SyncProtocol;
+        use yrs::{Map, ReadTxn, Transact};
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            enable_redis_fanout: true,
+            ..ServerConfig::default()
+        };
+        let publisher = CRDTServer::new(config.clone());
+        let receiver = Arc::new(CRDTServer::new(config.clone()));
+        let app = receiver.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+        let mut ws = join_over_websocket(addr, "room-a").await;
+        next_join_reply(&mut ws).await;
+
+        let source = yrs::Doc::new();
+        let metadata = source.get_or_insert_map("metadata");
+        metadata.insert(&mut source.transact_mut(), "name", "Demo");
+        let update = source
+            .transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default());
+        let mut message = vec![0];
+        SyncProtocol::write_update(&mut message, &update).unwrap();
+
+        // What the publishing instance puts on the channel is relayed by the
+        // other one, into its document and out to its sockets
+        let payload = publisher.fanout_payload(&message).unwrap();
+        assert_eq!(
+            decode_fanout(&payload),
+            Some((publisher.instance_id, &message[..]))
+        );
+        receiver.relay_fanout("room-a", &payload).await;
+
+        let room = receiver.rooms.get("room-a").unwrap().value().clone();
+        {
+            let doc = room.doc.read().await;
+            let txn = doc.transact();
+            assert!(txn.get_map("metadata").is_some());
+        }
+        let expected = json!(["crdt:message", "room-a", message]);
+        loop {
+            let packet = next_text(&mut ws).await;
+            if let Some(event) = packet.strip_prefix("42") {
+                if serde_json::from_str::<serde_json::Value>(event).unwrap() == expected {
+                    break;
+                }
+            }
+        }
+
+        let disabled = CRDTServer::new(ServerConfig {
+            enable_redis_fanout: false,
+            ..config
+        });
+        assert!(disabled.fanout_payload(&message).is_none());
+    }
+
+    #[test]
+    fn test_parse_join_frame() {
+        assert_eq!(parse_join_frame(&json!("room-a")), Ok(("room-a", false)));
+        assert_eq!(parse_join_frame(&json!(["room-a"])), Ok(("room-a", false)));
+        assert_eq!(
+            parse_join_frame(&json!(["room-a", { "binary": true }])),
+            Ok(("room-a", true))
+        );
+        assert!(parse_join_frame(&json!([42, { "binary": true }])).is_err());
+        assert!(parse_join_frame(&json!({ "room": "room-a" })).is_err());
+        // Would share a Socket.IO room with room-a's binary members
+        assert!(parse_join_frame(&json!("room-a#binary")).is_err());
+        assert!(parse_join_frame(&json!(["room-a#binary", { "binary": true }])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_reaches_binary_and_json_members() {
+        use crate::sync_protocol::SyncProtocol;
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+        use yrs::{Map, ReadTxn, Transact};
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            ..ServerConfig::default()
+        };
+        let server = Arc::new(CRDTServer::new(config));
+        let app = server.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        let join = |frame: serde_json::Value| async move {
+            let mut ws = connect_over_websocket(addr).await;
+            let join = json!(["crdt:join", frame]).to_string();
+            ws.send(Message::text(format!("42{}", join))).await.unwrap();
+            let reply = next_join_reply(&mut ws).await;
+            (ws, reply)
+        };
+        let (mut sender, _) = join(json!("room-a")).await;
+        let (mut binary, _) = join(json!(["room-a", { "binary": true }])).await;
+        let (mut plain, _) = join(json!("room-a")).await;
+        let (mut squatter, reply) = join(json!("room-a#binary")).await;
+        assert_eq!(reply[1]["code"], "malformed_frame");
+
+        let source = yrs::Doc::new();
+        let metadata = source.get_or_insert_map("metadata");
+        metadata.insert(&mut source.transact_mut(), "name", "Demo");
+        let update = source
+            .transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default());
+        let mut message = vec![0];
+        SyncProtocol::write_update(&mut message, &update).unwrap();
+        let frame = json!(["crdt:message", json_message_frame("room-a", &message)]);
+        sender
+            .send(Message::text(format!("42{}", frame)))
+            .await
+            .unwrap();
+
+        // The binary member gets the bytes as an attachment
+        let received = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                if let Some(Ok(Message::Binary(bytes))) = binary.next().await {
+                    if bytes[..] == message[..] {
+                        break;
+                    }
+                }
+            }
+        })
+        .await;
+        assert!(received.is_ok(), "binary member never got the update");
+
+        // The JSON member gets the legacy byte array
+        let expected = json!(["crdt:message", "room-a", message]);
+        loop {
+            let packet = next_text(&mut plain).await;
+            if let Some(event) = packet.strip_prefix("42") {
+                if serde_json::from_str::<serde_json::Value>(event).unwrap() == expected {
+                    break;
+                }
+            }
+        }
+
+        // A rejected `room-a#binary` join never sees room-a's traffic
+        let leaked = tokio::time::timeout(std::time::Duration::from_millis(300), async {
+            while let Some(Ok(message)) = squatter.next().await {
+                if matches!(message, Message::Binary(_)) {
+                    return;
+                }
+            }
+        })
+        .await;
+        assert!(leaked.is_err());
+    }
+
+    #[test]
+    fn test_parse_message_frame() {
+        let frame = json!(["room-a", [0, 1, 255]]);
+        assert_eq!(
+            parse_message_frame(&frame, &[]),
+            Ok(("room-a", vec![0u8, 1, 255]))
+        );
+
+        let bin = [Bytes::from_static(&[0, 1, 255])];
+        for frame in [json!("room-a"), json!(["room-a"])] {
+            assert_eq!(
+                parse_message_frame(&frame, &bin),
+                Ok(("room-a", vec![0u8, 1, 255]))
+            );
+        }
+        assert!(parse_message_frame(&json!(42), &bin).is_err());
+
+        for malformed in [
+            json!("not-an-array"),
+            json!(["room-a"]),
+            json!([42, [0, 1]]),
+            json!(["room-a", "0,1"]),
+            json!(["room-a", [0, 256]]),
+        ] {
+            assert!(
+                parse_message_frame(&malformed, &[]).is_err(),
+                "{}",
+                malformed
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_message_frames_are_reported() {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            ..ServerConfig::default()
+        };
+        let server = Arc::new(CRDTServer::new(config));
+        let app = server.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        let mut ws = join_over_websocket(addr, "room-a").await;
+        for payload in [json!("not-an-array"), json!(["room-a"])] {
+            let frame = json!(["crdt:message", payload]).to_string();
+            ws.send(Message::text(format!("42{}", frame)))
+                .await
+                .unwrap();
+
+            let error = loop {
+                let packet = next_text(&mut ws).await;
+                if packet.starts_with(r#"42["crdt:error""#) {
+                    break packet;
                 }
+            };
+            let error: serde_json::Value = serde_json::from_str(&error[2..]).unwrap();
+            assert_eq!(error[1]["code"], "malformed_frame");
+            assert!(error[1]["error"].as_str().unwrap().contains("crdt:message"));
+        }
+
+        let axum::Json(stats) = server.get_stats().await;
+        assert_eq!(stats.malformed_frames, 2);
+    }
+
+    #[test]
+    fn test_exceeds_room_limit() {
+        let session = json!({ "connected_at": "now", "rooms": ["room-a", "room-b", 7] });
+        let joined = session_rooms(&session);
+        assert_eq!(joined, ["room-a", "room-b"]);
+        assert!(session_rooms(&json!({})).is_empty());
+
+        assert!(exceeds_room_limit(&joined, "room-c", 2));
+        assert!(!exceeds_room_limit(&joined, "room-c", 3));
+        // Rejoining is always allowed, and 0 means no limit
+        assert!(!exceeds_room_limit(&joined, "room-a", 2));
+        assert!(!exceeds_room_limit(&joined, "room-c", 0));
+    }
+
+    #[tokio::test]
+    async fn test_join_beyond_room_limit_is_rejected() {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            max_rooms_per_client: 2,
+            ..ServerConfig::default()
+        };
+        let server = Arc::new(CRDTServer::new(config));
+        let app = server.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        let mut ws = join_over_websocket(addr, "room-a").await;
+        let mut replies = vec![next_join_reply(&mut ws).await];
+        for room in ["room-b", "room-c", "room-a"] {
+            let join = json!(["crdt:join", room]).to_string();
+            ws.send(Message::text(format!("42{}", join))).await.unwrap();
+            replies.push(next_join_reply(&mut ws).await);
+        }
+
+        assert_eq!(replies[0][1]["roomName"], "room-a");
+        assert_eq!(replies[1][1]["roomName"], "room-b");
+        assert_eq!(replies[2][0], "crdt:error");
+        assert_eq!(replies[2][1]["code"], "room_limit");
+        assert_eq!(replies[2][1]["maxRooms"], 2);
+        // Already a member, so rejoining is not counted against the limit
+        assert_eq!(replies[3][0], "crdt:joined");
+        assert_eq!(replies[3][1]["roomName"], "room-a");
+        assert!(server.rooms.get("room-c").is_none());
+    }
+
+    /// Wait for the `crdt:joined` or `crdt:error` answering a join
+    async fn next_join_reply<S>(ws: &mut tokio_tungstenite::WebSocketStream<S>) -> serde_json::Value
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        loop {
+            let packet = next_text(ws).await;
+            if packet.starts_with(r#"42["crdt:joined""#) || packet.starts_with(r#"42["crdt:error""#)
+            {
+                return serde_json::from_str(&packet[2..]).unwrap();
             }
-        }))
+        }
+    }
+
+    /// Open a raw engine.io v4 WebSocket and connect to the default namespace
+    async fn connect_over_websocket(
+        addr: std::net::SocketAddr,
+    ) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>
+    {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let url = format!("ws://{}/socket.io/?EIO=4&transport=websocket", addr);
+        let (mut ws, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+        assert!(next_text(&mut ws).await.starts_with('0'));
+        ws.send(Message::text("40")).await.unwrap();
+        assert!(next_text(&mut ws).await.starts_with("40"));
+        ws
+    }
+
+    /// Open a raw engine.io v4 WebSocket, connect to the default namespace
+    /// and join `room`
+    async fn join_over_websocket(
+        addr: std::net::SocketAddr,
+        room: &str,
+    ) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>
+    {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let mut ws = connect_over_websocket(addr).await;
+        let join = json!(["crdt:join", room]).to_string();
+        ws.send(Message::text(format!("42{}", join))).await.unwrap();
+        ws
+    }
+
+    async fn next_text<S>(ws: &mut tokio_tungstenite::WebSocketStream<S>) -> String
+    where
+        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    {
+        use futures_util::StreamExt;
+
+        loop {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+                .await
+                .expect("timed out waiting for a packet")
+                .unwrap()
+                .unwrap();
+            if let Ok(text) = message.into_text() {
+                return text.to_string();
+            }
+        }
+    }
+
+    /// POST `body` with an admin token and return the status code and body
+    async fn admin_post(
+        addr: std::net::SocketAddr,
+        path: &str,
+        token: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> (u16, String) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            path,
+            addr,
+            token,
+            content_type,
+            body.len()
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(head.as_bytes()).await.unwrap();
+        stream.write_all(body).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    /// POST an announcement to `/admin/broadcast` and return the status code
+    async fn post_broadcast(addr: std::net::SocketAddr, token: &str) -> u16 {
+        let body = r#"{"message":"Maintenance in 5 minutes","level":"warning"}"#;
+        admin_post(
+            addr,
+            "/admin/broadcast",
+            token,
+            "application/json",
+            body.as_bytes(),
+        )
+        .await
+        .0
+    }
+
+    #[tokio::test]
+    async fn test_admin_broadcast_reaches_every_room() {
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            admin_token: Some("secret".to_string()),
+            ..ServerConfig::default()
+        };
+        let server = Arc::new(CRDTServer::new(config));
+        let app = server.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        let mut clients = Vec::new();
+        for room in ["room-a", "room-b"] {
+            clients.push(join_over_websocket(addr, room).await);
+        }
+        // Wait until both joins have been handled
+        for _ in 0..50 {
+            if server.rooms.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(server.rooms.len(), 2);
+
+        assert_eq!(post_broadcast(addr, "wrong").await, 401);
+        assert_eq!(post_broadcast(addr, "secret").await, 200);
+
+        for ws in &mut clients {
+            let announcement = loop {
+                let packet = next_text(ws).await;
+                if packet.starts_with(r#"42["crdt:announcement""#) {
+                    break packet;
+                }
+            };
+            let event: serde_json::Value = serde_json::from_str(&announcement[2..]).unwrap();
+            assert_eq!(event[1]["message"], "Maintenance in 5 minutes");
+            assert_eq!(event[1]["level"], "warning");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed_room_is_idempotent() {
+        use yrs::{Doc, Map, MapPrelim, ReadTxn, Transact};
+
+        let config = ServerConfig {
+            enable_redis_persistence: false,
+            admin_token: Some("secret".to_string()),
+            ..ServerConfig::default()
+        };
+        let server = Arc::new(CRDTServer::new(config));
+        let app = server.router().unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+
+        // Laid out the way the web client writes a workflow
+        let seed = |node: &str| {
+            let doc = Doc::new();
+            let graphs = doc.get_or_insert_map("graphs");
+            let nodes = doc.get_or_insert_map("nodes-main");
+            doc.get_or_insert_map("connections-main");
+            let mut txn = doc.transact_mut();
+            graphs.insert(
+                &mut txn,
+                "main",
+                yrs::Any::from(std::collections::HashMap::from([
+                    ("id".to_string(), yrs::Any::from("main")),
+                    ("isMain".to_string(), yrs::Any::from(true)),
+                ])),
+            );
+            let node_map = nodes.insert(&mut txn, node, MapPrelim::<String>::new());
+            node_map.insert(&mut txn, "type", "template-node");
+            txn.encode_state_as_update_v1(&yrs::StateVector::default())
+        };
+        let post_seed = |room: &'static str, token: &'static str, body: Vec<u8>| async move {
+            let (status, body) = admin_post(
+                addr,
+                &format!("/rooms/{}/seed", room),
+                token,
+                "application/octet-stream",
+                &body,
+            )
+            .await;
+            (
+                status,
+                serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            )
+        };
+
+        assert_eq!(post_seed("wf-1", "wrong", seed("a")).await.0, 401);
+        assert!(server.rooms.get("wf-1").is_none());
+
+        let (status, body) = post_seed("wf-1", "secret", seed("a")).await;
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "room": "wf-1", "seeded": true }));
+        let room = server.rooms.get("wf-1").unwrap().value().clone();
+        assert_eq!(
+            room.to_json().await["graphs"]["main"]["nodes"]["a"]["type"],
+            "template-node"
+        );
+
+        // Seeding again leaves the existing state alone
+        let (status, body) = post_seed("wf-1", "secret", seed("b")).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["seeded"], false);
+        let snapshot = room.to_json().await;
+        assert!(snapshot["graphs"]["main"]["nodes"].get("b").is_none());
+
+        // Invalid updates are rejected before a room is created
+        let (status, _) = post_seed("wf-2", "secret", vec![0xff, 0xff, 0x01]).await;
+        assert_eq!(status, 400);
+        let empty = Doc::new()
+            .transact()
+            .encode_state_as_update_v1(&yrs::StateVector::default());
+        assert_eq!(post_seed("wf-2", "secret", empty).await.0, 400);
+        assert!(server.rooms.get("wf-2").is_none());
     }
 }
@@ -0,0 +1,10 @@
+-- Migration: Allow 'warning' trace events
+-- Nodes that complete with warnings are traced with their own event type
+-- Run this against the TimescaleDB trace database (timescaledb-init.sql)
+
+ALTER TABLE flow_trace_events
+DROP CONSTRAINT IF EXISTS flow_trace_events_event_type_check;
+
+ALTER TABLE flow_trace_events
+ADD CONSTRAINT flow_trace_events_event_type_check
+CHECK (event_type IN ('input', 'output', 'error', 'log', 'warning', 'start', 'complete'));
@@ -0,0 +1,77 @@
+//! Compare `serde_json` and `simd-json` on large workflow state payloads.
+//!
+//! Run with `cargo bench --bench json_parsing --features simd-json` to include
+//! the simd-json path.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zeal_sdk::orchestrator::WorkflowState;
+
+/// A workflow state with `nodes` nodes chained by connections
+fn workflow_state(nodes: usize) -> Vec<u8> {
+    let node = |i: usize| {
+        serde_json::json!({
+            "id": format!("node-{}", i),
+            "type": "http-request",
+            "position": { "x": i as f64 * 120.0, "y": (i % 10) as f64 * 80.0 },
+            "properties": {
+                "url": format!("https://api.example.com/items/{}", i),
+                "method": "GET",
+                "retries": 3
+            },
+            "metadata": {
+                "title": format!("Fetch item {}", i),
+                "ports": [
+                    { "id": "in", "label": "In", "type": "input", "position": "left" },
+                    { "id": "out", "label": "Out", "type": "output", "position": "right" }
+                ]
+            }
+        })
+    };
+    let connection = |i: usize| {
+        serde_json::json!({
+            "id": format!("conn-{}", i),
+            "source": { "nodeId": format!("node-{}", i), "portId": "out" },
+            "target": { "nodeId": format!("node-{}", i + 1), "portId": "in" },
+            "state": "success"
+        })
+    };
+
+    serde_json::to_vec(&serde_json::json!({
+        "workflowId": "wf-bench",
+        "graphId": "main",
+        "name": "Benchmark workflow",
+        "description": "Large generated graph",
+        "version": 1,
+        "state": {
+            "nodes": (0..nodes).map(node).collect::<Vec<_>>(),
+            "connections": (0..nodes.saturating_sub(1)).map(connection).collect::<Vec<_>>(),
+            "groups": []
+        },
+        "metadata": {}
+    }))
+    .unwrap()
+}
+
+fn bench_workflow_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("workflow_state");
+    for nodes in [100, 1_000, 10_000] {
+        let payload = workflow_state(nodes);
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("serde_json", nodes), &payload, |b, p| {
+            b.iter(|| serde_json::from_slice::<WorkflowState>(black_box(p)).unwrap())
+        });
+
+        #[cfg(feature = "simd-json")]
+        group.bench_with_input(BenchmarkId::new("simd_json", nodes), &payload, |b, p| {
+            b.iter(|| {
+                let mut buffer = black_box(p).clone();
+                simd_json::serde::from_slice::<WorkflowState>(&mut buffer).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_workflow_state);
+criterion_main!(benches);
@@ -54,7 +54,7 @@ async fn register_example_templates(_client: &ZealClient) -> Result<()> {
     // Note: In a real implementation, these would use the actual TemplatesAPI
     // For this basic example, we'll just show the structure
 
-    let templates = vec![
+    let templates = [
         NodeTemplate {
             id: "data-processor".to_string(),
             type_name: "processor".to_string(),
@@ -114,6 +114,7 @@ async fn register_example_templates(_client: &ZealClient) -> Result<()> {
                 required_env_vars: Some(vec!["PROCESSOR_CONFIG".to_string()]),
                 capabilities: Some(vec!["data-processing".to_string()]),
             }),
+            display: None,
         },
         NodeTemplate {
             id: "data-validator".to_string(),
@@ -183,6 +184,7 @@ async fn register_example_templates(_client: &ZealClient) -> Result<()> {
                 required_env_vars: None,
                 capabilities: Some(vec!["data-validation".to_string()]),
             }),
+            display: None,
         },
     ];
 
@@ -201,7 +203,7 @@ async fn create_example_workflow(client: &ZealClient) -> Result<CreateWorkflowRe
     let workflow_response = CreateWorkflowResponse {
         workflow_id: format!("workflow-{}", uuid::Uuid::new_v4()),
         graph_id: "main".to_string(),
-        embed_url: format!("{}/embed/workflow", client.base_url()),
+        embed_url: format!("{}/embed/workflow", client.base_url()).parse()?,
     };
 
     println!("   Created workflow: {}", workflow_response.workflow_id);
@@ -0,0 +1,943 @@
+//! Trait abstractions over the API modules
+//!
+//! `OrchestratorAPI`, `TemplatesAPI`, `TracesAPI` and `WebhooksAPI` implement these
+//! traits so code written against them can be exercised with mock implementations
+//! instead of a live Zeal server.
+
+use crate::errors::Result;
+use crate::orchestrator::{
+    ClearGraphResponse, ConnectionResponse, CreateGroupRequest, CreateGroupResponse,
+    DeleteNodeResponse, DeleteNodesBatch, ListWorkflowsParams, ListWorkflowsResponse,
+    OrchestratorAPI, StateField, UpdateNodeRequest, UpdateNodeResponse, WorkflowState,
+    WorkflowStatesBatch,
+};
+use crate::templates::{
+    DeleteTemplateResponse, ListTemplatesResponse, TemplatesAPI, UpdateTemplateOptions,
+    UpdateTemplateResponse,
+};
+use crate::traces::{
+    BatchTraceRequest, BatchTraceResponse, CompleteSessionRequest, CompleteSessionResponse,
+    NodeHistoryPage, NodeHistoryQuery, SessionEventsResponse, SubmitEventsResponse, TracesAPI,
+};
+use crate::types::{
+    AddNodeRequest, AddNodeResponse, ConnectNodesRequest, CreateTraceSessionRequest,
+    CreateTraceSessionResponse, CreateWorkflowRequest, CreateWorkflowResponse,
+    ListCategoriesResponse, NodeExecutionRecord, NodeTemplate, RegisterCategoriesRequest,
+    RegisterCategoriesResponse, RegisterTemplatesRequest, RegisterTemplatesResponse,
+    RemoveConnectionRequest, RemoveConnectionResponse, RemoveGroupRequest, RemoveGroupResponse,
+    TraceEvent, TraceEventType, UpdateGroupRequest, UpdateGroupResponse, UpdateWorkflowRequest,
+    UpdateWorkflowResponse, UploadBundleRequest, UploadBundleResponse, WebhookConfig,
+    WebhookRegistrationResponse,
+};
+use crate::webhooks::{DeleteWebhooksBatch, TestWebhookResponse, WebhooksAPI};
+use futures_util::Stream;
+use std::collections::HashMap;
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+/// Workflow orchestration operations
+pub trait OrchestratorApi: Send + Sync {
+    /// Create a new workflow
+    fn create_workflow(
+        &self,
+        request: CreateWorkflowRequest,
+    ) -> impl Future<Output = Result<CreateWorkflowResponse>> + Send;
+
+    /// Update a workflow's name, description or metadata
+    fn update_workflow(
+        &self,
+        workflow_id: &str,
+        request: UpdateWorkflowRequest,
+    ) -> impl Future<Output = Result<UpdateWorkflowResponse>> + Send;
+
+    /// List workflows
+    fn list_workflows(
+        &self,
+        params: Option<ListWorkflowsParams>,
+    ) -> impl Future<Output = Result<ListWorkflowsResponse>> + Send;
+
+    /// Iterate over every workflow, fetching `page_size` at a time
+    fn list_all_workflows(
+        &self,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<serde_json::Value>> + Send + '_;
+
+    /// Get workflow state
+    fn get_workflow_state(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<WorkflowState>> + Send;
+
+    /// Get workflow state with only the requested graph sections
+    fn get_workflow_state_fields(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        fields: &[StateField],
+    ) -> impl Future<Output = Result<WorkflowState>> + Send;
+
+    /// Get the state of several workflows, at most `concurrency` at a time
+    fn get_workflow_states(
+        &self,
+        ids: &[&str],
+        concurrency: usize,
+    ) -> impl Future<Output = Result<WorkflowStatesBatch>> + Send;
+
+    /// Write a workflow's state to `writer` as NDJSON records
+    fn export_workflow_ndjson<W: tokio::io::AsyncWrite + Unpin + Send>(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        writer: &mut W,
+    ) -> impl Future<Output = Result<usize>> + Send;
+
+    /// Check whether a workflow exists without fetching its state
+    fn workflow_exists(&self, workflow_id: &str) -> impl Future<Output = Result<bool>> + Send;
+
+    /// Add a node to a workflow
+    fn add_node(
+        &self,
+        request: AddNodeRequest,
+    ) -> impl Future<Output = Result<AddNodeResponse>> + Send;
+
+    /// Update node properties
+    fn update_node(
+        &self,
+        node_id: &str,
+        updates: UpdateNodeRequest,
+    ) -> impl Future<Output = Result<UpdateNodeResponse>> + Send;
+
+    /// Update only the node properties that changed between `current` and `desired`
+    fn update_node_diff(
+        &self,
+        node_id: &str,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        current: &HashMap<String, serde_json::Value>,
+        desired: &HashMap<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<Option<UpdateNodeResponse>>> + Send;
+
+    /// Delete a node
+    fn delete_node(
+        &self,
+        node_id: &str,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<DeleteNodeResponse>> + Send;
+
+    /// Delete several nodes concurrently, collecting per-node results
+    fn delete_nodes(
+        &self,
+        workflow_id: &str,
+        node_ids: &[&str],
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = DeleteNodesBatch> + Send;
+
+    /// Remove every node, connection and group from a graph
+    fn clear_graph(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<ClearGraphResponse>> + Send;
+
+    /// Connect two nodes
+    fn connect_nodes(
+        &self,
+        request: ConnectNodesRequest,
+    ) -> impl Future<Output = Result<ConnectionResponse>> + Send;
+
+    /// Add several nodes, rolling back created nodes on cancellation or failure
+    fn add_nodes(
+        &self,
+        requests: Vec<AddNodeRequest>,
+        cancel: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<AddNodeResponse>>> + Send;
+
+    /// Create several connections, rolling back created connections on
+    /// cancellation or failure
+    fn connect_nodes_batch(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+        cancel: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<ConnectionResponse>>> + Send;
+
+    /// Add several nodes in one request, with a result per node and no
+    /// rollback of the nodes that succeeded
+    fn add_nodes_best_effort(
+        &self,
+        requests: Vec<AddNodeRequest>,
+    ) -> impl Future<Output = Result<Vec<Result<AddNodeResponse>>>> + Send;
+
+    /// Create several connections in one request, with a result per
+    /// connection and no rollback of the connections that succeeded
+    fn connect_nodes_best_effort(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+    ) -> impl Future<Output = Result<Vec<Result<ConnectionResponse>>>> + Send;
+
+    /// Create a node group
+    fn create_group(
+        &self,
+        request: CreateGroupRequest,
+    ) -> impl Future<Output = Result<CreateGroupResponse>> + Send;
+
+    /// Remove a connection between nodes
+    fn remove_connection(
+        &self,
+        request: RemoveConnectionRequest,
+    ) -> impl Future<Output = Result<RemoveConnectionResponse>> + Send;
+
+    /// Update group properties
+    fn update_group(
+        &self,
+        request: UpdateGroupRequest,
+    ) -> impl Future<Output = Result<UpdateGroupResponse>> + Send;
+
+    /// Remove a group
+    fn remove_group(
+        &self,
+        request: RemoveGroupRequest,
+    ) -> impl Future<Output = Result<RemoveGroupResponse>> + Send;
+}
+
+/// Node template and category operations
+pub trait TemplatesApi: Send + Sync {
+    /// Register node templates
+    fn register(
+        &self,
+        request: RegisterTemplatesRequest,
+    ) -> impl Future<Output = Result<RegisterTemplatesResponse>> + Send;
+
+    /// Register node templates in chunks of at most `chunk_size` per request
+    fn register_chunked(
+        &self,
+        request: RegisterTemplatesRequest,
+        chunk_size: usize,
+    ) -> impl Future<Output = Result<RegisterTemplatesResponse>> + Send;
+
+    /// List templates for a namespace
+    fn list(&self, namespace: &str) -> impl Future<Output = Result<ListTemplatesResponse>> + Send;
+
+    /// Update a template
+    fn update(
+        &self,
+        namespace: &str,
+        template_id: &str,
+        updates: NodeTemplate,
+    ) -> impl Future<Output = Result<UpdateTemplateResponse>> + Send;
+
+    /// Update a template, checking it for breaking changes as configured
+    fn update_with_options(
+        &self,
+        namespace: &str,
+        template_id: &str,
+        updates: NodeTemplate,
+        options: UpdateTemplateOptions,
+    ) -> impl Future<Output = Result<UpdateTemplateResponse>> + Send;
+
+    /// Delete a template
+    fn delete(
+        &self,
+        namespace: &str,
+        template_id: &str,
+    ) -> impl Future<Output = Result<DeleteTemplateResponse>> + Send;
+
+    /// List available node template categories
+    fn list_categories(&self) -> impl Future<Output = Result<ListCategoriesResponse>> + Send;
+
+    /// Register new categories and subcategories
+    fn register_categories(
+        &self,
+        request: RegisterCategoriesRequest,
+    ) -> impl Future<Output = Result<RegisterCategoriesResponse>> + Send;
+
+    /// Upload a Web Component bundle for custom node rendering
+    fn upload_bundle(
+        &self,
+        request: UploadBundleRequest,
+    ) -> impl Future<Output = Result<UploadBundleResponse>> + Send;
+
+    /// Get a specific template
+    fn get(
+        &self,
+        namespace: &str,
+        template_id: &str,
+    ) -> impl Future<Output = Result<NodeTemplate>> + Send;
+}
+
+/// Execution trace operations
+pub trait TracesApi: Send + Sync {
+    /// Create a new trace session
+    fn create_session(
+        &mut self,
+        request: CreateTraceSessionRequest,
+    ) -> impl Future<Output = Result<CreateTraceSessionResponse>> + Send;
+
+    /// Submit trace events
+    fn submit_events(
+        &self,
+        session_id: &str,
+        events: Vec<TraceEvent>,
+    ) -> impl Future<Output = Result<SubmitEventsResponse>> + Send;
+
+    /// Submit a single trace event
+    fn submit_event(
+        &self,
+        session_id: &str,
+        event: TraceEvent,
+    ) -> impl Future<Output = Result<SubmitEventsResponse>> + Send;
+
+    /// Complete a trace session
+    fn complete_session(
+        &mut self,
+        session_id: &str,
+        request: CompleteSessionRequest,
+    ) -> impl Future<Output = Result<CompleteSessionResponse>> + Send;
+
+    /// Trace a node execution as a single event
+    fn trace_node_execution(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        event_type: TraceEventType,
+        data: serde_json::Value,
+        duration: Option<std::time::Duration>,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Trace data flowing through a single port
+    fn trace_port_data(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        port_id: &str,
+        direction: TraceEventType,
+        data: serde_json::Value,
+    ) -> impl Future<Output = Result<()>> + Send;
+
+    /// Batch trace submission
+    fn submit_batch(
+        &self,
+        request: BatchTraceRequest,
+    ) -> impl Future<Output = Result<BatchTraceResponse>> + Send;
+
+    /// Get the events of a session, optionally only those after `since`
+    fn get_session_events(
+        &self,
+        session_id: &str,
+        since: Option<i64>,
+    ) -> impl Future<Output = Result<SessionEventsResponse>> + Send;
+
+    /// Get the most recent executions of a node across runs
+    fn node_history(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<NodeExecutionRecord>>> + Send;
+
+    /// Get one page of a node's execution history
+    fn node_history_page(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        query: &NodeHistoryQuery,
+    ) -> impl Future<Output = Result<NodeHistoryPage>> + Send;
+
+    /// Follow a session's trace events until it reaches a terminal status
+    fn tail_session(&self, session_id: &str) -> impl Stream<Item = Result<TraceEvent>> + Send + '_;
+
+    /// Get the current session ID
+    fn current_session_id(&self) -> Option<&str>;
+}
+
+/// Webhook registration operations
+pub trait WebhooksApi: Send + Sync {
+    /// Register a new webhook
+    fn register(
+        &self,
+        config: WebhookConfig,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send;
+
+    /// Register a new webhook, sending `extra_headers` on the registration request only
+    fn register_with_headers(
+        &self,
+        config: WebhookConfig,
+        extra_headers: HashMap<String, String>,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send;
+
+    /// List webhooks for a namespace
+    fn list(
+        &self,
+        namespace: &str,
+    ) -> impl Future<Output = Result<Vec<WebhookRegistrationResponse>>> + Send;
+
+    /// Update a webhook
+    fn update(
+        &self,
+        webhook_id: &str,
+        config: WebhookConfig,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send;
+
+    /// Delete a webhook
+    fn delete(&self, webhook_id: &str) -> impl Future<Output = Result<()>> + Send;
+
+    /// Delete every webhook in a namespace; `confirm_namespace` must repeat it
+    fn delete_all(
+        &self,
+        namespace: &str,
+        confirm_namespace: &str,
+    ) -> impl Future<Output = Result<DeleteWebhooksBatch>> + Send;
+
+    /// Get a specific webhook by ID
+    fn get(
+        &self,
+        webhook_id: &str,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send;
+
+    /// Test a webhook endpoint
+    fn test(&self, webhook_id: &str) -> impl Future<Output = Result<TestWebhookResponse>> + Send;
+
+    /// Test a webhook endpoint and fail if Zeal could not deliver to it
+    fn ensure_reachable(
+        &self,
+        webhook_id: &str,
+        webhook_url: &str,
+    ) -> impl Future<Output = Result<TestWebhookResponse>> + Send;
+}
+
+impl OrchestratorApi for OrchestratorAPI {
+    fn create_workflow(
+        &self,
+        request: CreateWorkflowRequest,
+    ) -> impl Future<Output = Result<CreateWorkflowResponse>> + Send {
+        OrchestratorAPI::create_workflow(self, request)
+    }
+
+    fn update_workflow(
+        &self,
+        workflow_id: &str,
+        request: UpdateWorkflowRequest,
+    ) -> impl Future<Output = Result<UpdateWorkflowResponse>> + Send {
+        OrchestratorAPI::update_workflow(self, workflow_id, request)
+    }
+
+    fn list_workflows(
+        &self,
+        params: Option<ListWorkflowsParams>,
+    ) -> impl Future<Output = Result<ListWorkflowsResponse>> + Send {
+        OrchestratorAPI::list_workflows(self, params)
+    }
+
+    fn list_all_workflows(
+        &self,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<serde_json::Value>> + Send + '_ {
+        OrchestratorAPI::list_all_workflows(self, page_size)
+    }
+
+    fn get_workflow_state(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<WorkflowState>> + Send {
+        OrchestratorAPI::get_workflow_state(self, workflow_id, graph_id)
+    }
+
+    fn get_workflow_state_fields(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        fields: &[StateField],
+    ) -> impl Future<Output = Result<WorkflowState>> + Send {
+        OrchestratorAPI::get_workflow_state_fields(self, workflow_id, graph_id, fields)
+    }
+
+    fn get_workflow_states(
+        &self,
+        ids: &[&str],
+        concurrency: usize,
+    ) -> impl Future<Output = Result<WorkflowStatesBatch>> + Send {
+        OrchestratorAPI::get_workflow_states(self, ids, concurrency)
+    }
+
+    fn export_workflow_ndjson<W: tokio::io::AsyncWrite + Unpin + Send>(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        writer: &mut W,
+    ) -> impl Future<Output = Result<usize>> + Send {
+        OrchestratorAPI::export_workflow_ndjson(self, workflow_id, graph_id, writer)
+    }
+
+    fn workflow_exists(&self, workflow_id: &str) -> impl Future<Output = Result<bool>> + Send {
+        OrchestratorAPI::workflow_exists(self, workflow_id)
+    }
+
+    fn add_node(
+        &self,
+        request: AddNodeRequest,
+    ) -> impl Future<Output = Result<AddNodeResponse>> + Send {
+        OrchestratorAPI::add_node(self, request)
+    }
+
+    fn update_node(
+        &self,
+        node_id: &str,
+        updates: UpdateNodeRequest,
+    ) -> impl Future<Output = Result<UpdateNodeResponse>> + Send {
+        OrchestratorAPI::update_node(self, node_id, updates)
+    }
+
+    fn update_node_diff(
+        &self,
+        node_id: &str,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        current: &HashMap<String, serde_json::Value>,
+        desired: &HashMap<String, serde_json::Value>,
+    ) -> impl Future<Output = Result<Option<UpdateNodeResponse>>> + Send {
+        OrchestratorAPI::update_node_diff(self, node_id, workflow_id, graph_id, current, desired)
+    }
+
+    fn delete_node(
+        &self,
+        node_id: &str,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<DeleteNodeResponse>> + Send {
+        OrchestratorAPI::delete_node(self, node_id, workflow_id, graph_id)
+    }
+
+    fn delete_nodes(
+        &self,
+        workflow_id: &str,
+        node_ids: &[&str],
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = DeleteNodesBatch> + Send {
+        OrchestratorAPI::delete_nodes(self, workflow_id, node_ids, graph_id)
+    }
+
+    fn clear_graph(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> impl Future<Output = Result<ClearGraphResponse>> + Send {
+        OrchestratorAPI::clear_graph(self, workflow_id, graph_id)
+    }
+
+    fn connect_nodes(
+        &self,
+        request: ConnectNodesRequest,
+    ) -> impl Future<Output = Result<ConnectionResponse>> + Send {
+        OrchestratorAPI::connect_nodes(self, request)
+    }
+
+    fn add_nodes(
+        &self,
+        requests: Vec<AddNodeRequest>,
+        cancel: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<AddNodeResponse>>> + Send {
+        OrchestratorAPI::add_nodes(self, requests, cancel)
+    }
+
+    fn connect_nodes_batch(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+        cancel: &CancellationToken,
+    ) -> impl Future<Output = Result<Vec<ConnectionResponse>>> + Send {
+        OrchestratorAPI::connect_nodes_batch(self, requests, cancel)
+    }
+
+    fn add_nodes_best_effort(
+        &self,
+        requests: Vec<AddNodeRequest>,
+    ) -> impl Future<Output = Result<Vec<Result<AddNodeResponse>>>> + Send {
+        OrchestratorAPI::add_nodes_best_effort(self, requests)
+    }
+
+    fn connect_nodes_best_effort(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+    ) -> impl Future<Output = Result<Vec<Result<ConnectionResponse>>>> + Send {
+        OrchestratorAPI::connect_nodes_best_effort(self, requests)
+    }
+
+    fn create_group(
+        &self,
+        request: CreateGroupRequest,
+    ) -> impl Future<Output = Result<CreateGroupResponse>> + Send {
+        OrchestratorAPI::create_group(self, request)
+    }
+
+    fn remove_connection(
+        &self,
+        request: RemoveConnectionRequest,
+    ) -> impl Future<Output = Result<RemoveConnectionResponse>> + Send {
+        OrchestratorAPI::remove_connection(self, request)
+    }
+
+    fn update_group(
+        &self,
+        request: UpdateGroupRequest,
+    ) -> impl Future<Output = Result<UpdateGroupResponse>> + Send {
+        OrchestratorAPI::update_group(self, request)
+    }
+
+    fn remove_group(
+        &self,
+        request: RemoveGroupRequest,
+    ) -> impl Future<Output = Result<RemoveGroupResponse>> + Send {
+        OrchestratorAPI::remove_group(self, request)
+    }
+}
+
+impl TemplatesApi for TemplatesAPI {
+    fn register(
+        &self,
+        request: RegisterTemplatesRequest,
+    ) -> impl Future<Output = Result<RegisterTemplatesResponse>> + Send {
+        TemplatesAPI::register(self, request)
+    }
+
+    fn register_chunked(
+        &self,
+        request: RegisterTemplatesRequest,
+        chunk_size: usize,
+    ) -> impl Future<Output = Result<RegisterTemplatesResponse>> + Send {
+        TemplatesAPI::register_chunked(self, request, chunk_size)
+    }
+
+    fn list(&self, namespace: &str) -> impl Future<Output = Result<ListTemplatesResponse>> + Send {
+        TemplatesAPI::list(self, namespace)
+    }
+
+    fn update(
+        &self,
+        namespace: &str,
+        template_id: &str,
+        updates: NodeTemplate,
+    ) -> impl Future<Output = Result<UpdateTemplateResponse>> + Send {
+        TemplatesAPI::update(self, namespace, template_id, updates)
+    }
+
+    fn update_with_options(
+        &self,
+        namespace: &str,
+        template_id: &str,
+        updates: NodeTemplate,
+        options: UpdateTemplateOptions,
+    ) -> impl Future<Output = Result<UpdateTemplateResponse>> + Send {
+        TemplatesAPI::update_with_options(self, namespace, template_id, updates, options)
+    }
+
+    fn delete(
+        &self,
+        namespace: &str,
+        template_id: &str,
+    ) -> impl Future<Output = Result<DeleteTemplateResponse>> + Send {
+        TemplatesAPI::delete(self, namespace, template_id)
+    }
+
+    fn list_categories(&self) -> impl Future<Output = Result<ListCategoriesResponse>> + Send {
+        TemplatesAPI::list_categories(self)
+    }
+
+    fn register_categories(
+        &self,
+        request: RegisterCategoriesRequest,
+    ) -> impl Future<Output = Result<RegisterCategoriesResponse>> + Send {
+        TemplatesAPI::register_categories(self, request)
+    }
+
+    fn upload_bundle(
+        &self,
+        request: UploadBundleRequest,
+    ) -> impl Future<Output = Result<UploadBundleResponse>> + Send {
+        TemplatesAPI::upload_bundle(self, request)
+    }
+
+    fn get(
+        &self,
+        namespace: &str,
+        template_id: &str,
+    ) -> impl Future<Output = Result<NodeTemplate>> + Send {
+        TemplatesAPI::get(self, namespace, template_id)
+    }
+}
+
+impl TracesApi for TracesAPI {
+    fn create_session(
+        &mut self,
+        request: CreateTraceSessionRequest,
+    ) -> impl Future<Output = Result<CreateTraceSessionResponse>> + Send {
+        TracesAPI::create_session(self, request)
+    }
+
+    fn submit_events(
+        &self,
+        session_id: &str,
+        events: Vec<TraceEvent>,
+    ) -> impl Future<Output = Result<SubmitEventsResponse>> + Send {
+        TracesAPI::submit_events(self, session_id, events)
+    }
+
+    fn submit_event(
+        &self,
+        session_id: &str,
+        event: TraceEvent,
+    ) -> impl Future<Output = Result<SubmitEventsResponse>> + Send {
+        TracesAPI::submit_event(self, session_id, event)
+    }
+
+    fn complete_session(
+        &mut self,
+        session_id: &str,
+        request: CompleteSessionRequest,
+    ) -> impl Future<Output = Result<CompleteSessionResponse>> + Send {
+        TracesAPI::complete_session(self, session_id, request)
+    }
+
+    fn trace_node_execution(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        event_type: TraceEventType,
+        data: serde_json::Value,
+        duration: Option<std::time::Duration>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        TracesAPI::trace_node_execution(self, session_id, node_id, event_type, data, duration)
+    }
+
+    fn trace_port_data(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        port_id: &str,
+        direction: TraceEventType,
+        data: serde_json::Value,
+    ) -> impl Future<Output = Result<()>> + Send {
+        TracesAPI::trace_port_data(self, session_id, node_id, port_id, direction, data)
+    }
+
+    fn submit_batch(
+        &self,
+        request: BatchTraceRequest,
+    ) -> impl Future<Output = Result<BatchTraceResponse>> + Send {
+        TracesAPI::submit_batch(self, request)
+    }
+
+    fn get_session_events(
+        &self,
+        session_id: &str,
+        since: Option<i64>,
+    ) -> impl Future<Output = Result<SessionEventsResponse>> + Send {
+        TracesAPI::get_session_events(self, session_id, since)
+    }
+
+    fn node_history(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        limit: usize,
+    ) -> impl Future<Output = Result<Vec<NodeExecutionRecord>>> + Send {
+        TracesAPI::node_history(self, workflow_id, node_id, limit)
+    }
+
+    fn node_history_page(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        query: &NodeHistoryQuery,
+    ) -> impl Future<Output = Result<NodeHistoryPage>> + Send {
+        TracesAPI::node_history_page(self, workflow_id, node_id, query)
+    }
+
+    fn tail_session(&self, session_id: &str) -> impl Stream<Item = Result<TraceEvent>> + Send + '_ {
+        TracesAPI::tail_session(self, session_id)
+    }
+
+    fn current_session_id(&self) -> Option<&str> {
+        TracesAPI::current_session_id(self)
+    }
+}
+
+impl WebhooksApi for WebhooksAPI {
+    fn register(
+        &self,
+        config: WebhookConfig,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send {
+        WebhooksAPI::register(self, config)
+    }
+
+    fn register_with_headers(
+        &self,
+        config: WebhookConfig,
+        extra_headers: HashMap<String, String>,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send {
+        WebhooksAPI::register_with_headers(self, config, extra_headers)
+    }
+
+    fn list(
+        &self,
+        namespace: &str,
+    ) -> impl Future<Output = Result<Vec<WebhookRegistrationResponse>>> + Send {
+        WebhooksAPI::list(self, namespace)
+    }
+
+    fn update(
+        &self,
+        webhook_id: &str,
+        config: WebhookConfig,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send {
+        WebhooksAPI::update(self, webhook_id, config)
+    }
+
+    fn delete(&self, webhook_id: &str) -> impl Future<Output = Result<()>> + Send {
+        WebhooksAPI::delete(self, webhook_id)
+    }
+
+    fn delete_all(
+        &self,
+        namespace: &str,
+        confirm_namespace: &str,
+    ) -> impl Future<Output = Result<DeleteWebhooksBatch>> + Send {
+        WebhooksAPI::delete_all(self, namespace, confirm_namespace)
+    }
+
+    fn get(
+        &self,
+        webhook_id: &str,
+    ) -> impl Future<Output = Result<WebhookRegistrationResponse>> + Send {
+        WebhooksAPI::get(self, webhook_id)
+    }
+
+    fn test(&self, webhook_id: &str) -> impl Future<Output = Result<TestWebhookResponse>> + Send {
+        WebhooksAPI::test(self, webhook_id)
+    }
+
+    fn ensure_reachable(
+        &self,
+        webhook_id: &str,
+        webhook_url: &str,
+    ) -> impl Future<Output = Result<TestWebhookResponse>> + Send {
+        WebhooksAPI::ensure_reachable(self, webhook_id, webhook_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ZealError;
+
+    struct MockWebhooks {
+        webhooks: Vec<WebhookRegistrationResponse>,
+    }
+
+    impl WebhooksApi for MockWebhooks {
+        async fn register(&self, _config: WebhookConfig) -> Result<WebhookRegistrationResponse> {
+            Err(ZealError::other("not mocked"))
+        }
+
+        async fn register_with_headers(
+            &self,
+            _config: WebhookConfig,
+            _extra_headers: HashMap<String, String>,
+        ) -> Result<WebhookRegistrationResponse> {
+            Err(ZealError::other("not mocked"))
+        }
+
+        async fn list(&self, namespace: &str) -> Result<Vec<WebhookRegistrationResponse>> {
+            Ok(self
+                .webhooks
+                .iter()
+                .filter(|w| w.namespace == namespace)
+                .cloned()
+                .collect())
+        }
+
+        async fn update(
+            &self,
+            _webhook_id: &str,
+            _config: WebhookConfig,
+        ) -> Result<WebhookRegistrationResponse> {
+            Err(ZealError::other("not mocked"))
+        }
+
+        async fn delete(&self, _webhook_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_all(
+            &self,
+            _namespace: &str,
+            _confirm_namespace: &str,
+        ) -> Result<DeleteWebhooksBatch> {
+            Err(ZealError::other("not mocked"))
+        }
+
+        async fn get(&self, webhook_id: &str) -> Result<WebhookRegistrationResponse> {
+            self.webhooks
+                .iter()
+                .find(|w| w.webhook_id == webhook_id)
+                .cloned()
+                .ok_or_else(|| ZealError::not_found("webhook", webhook_id))
+        }
+
+        async fn test(&self, _webhook_id: &str) -> Result<TestWebhookResponse> {
+            Err(ZealError::other("not mocked"))
+        }
+
+        async fn ensure_reachable(
+            &self,
+            _webhook_id: &str,
+            _webhook_url: &str,
+        ) -> Result<TestWebhookResponse> {
+            Err(ZealError::other("not mocked"))
+        }
+    }
+
+    fn webhook(id: &str, namespace: &str, is_active: bool) -> WebhookRegistrationResponse {
+        WebhookRegistrationResponse {
+            webhook_id: id.to_string(),
+            namespace: namespace.to_string(),
+            url: format!("https://example.com/{}", id),
+            events: vec!["*".to_string()],
+            is_active,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn count_active<W: WebhooksApi>(api: &W, namespace: &str) -> Result<usize> {
+        Ok(api
+            .list(namespace)
+            .await?
+            .iter()
+            .filter(|w| w.is_active)
+            .count())
+    }
+
+    #[tokio::test]
+    async fn test_mock_implementation_drives_generic_code() {
+        let mock = MockWebhooks {
+            webhooks: vec![
+                webhook("wh-1", "runtime", true),
+                webhook("wh-2", "runtime", false),
+                webhook("wh-3", "other", true),
+            ],
+        };
+
+        assert_eq!(count_active(&mock, "runtime").await.unwrap(), 1);
+        assert!(matches!(
+            mock.get("wh-missing").await,
+            Err(ZealError::NotFound { .. })
+        ));
+    }
+
+    fn assert_impls<O: OrchestratorApi, T: TemplatesApi, R: TracesApi, W: WebhooksApi>() {}
+
+    #[test]
+    fn test_concrete_apis_implement_traits() {
+        assert_impls::<OrchestratorAPI, TemplatesAPI, TracesAPI, WebhooksAPI>();
+    }
+}
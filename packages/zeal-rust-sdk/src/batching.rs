@@ -0,0 +1,123 @@
+//! Background loop shared by the batchers
+//!
+//! [`TraceBatcher`](crate::trace_batcher::TraceBatcher) and
+//! [`VisualStateBatcher`](crate::visual_state_batcher::VisualStateBatcher)
+//! both hand items to a background task that collects them and flushes when
+//! the pending batch is full, when its first item has waited for the flush
+//! interval, or when the input channel closes. [`run`] is that task; each
+//! batcher supplies a [`Pending`] deciding how items accumulate and where a
+//! flush sends them.
+
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Items collected by a batching task since its last flush
+pub(crate) trait Pending: Send {
+    type Item: Send;
+
+    /// Add an item to the batch
+    fn push(&mut self, item: Self::Item);
+
+    /// Check whether nothing is waiting to be flushed
+    fn is_empty(&self) -> bool;
+
+    /// Check whether the batch should be flushed without waiting for the
+    /// interval
+    fn is_full(&self) -> bool;
+
+    /// Send the pending items on and start an empty batch
+    fn flush(&mut self) -> impl Future<Output = ()> + Send + '_;
+}
+
+/// Receiving half of a batcher's input channel, bounded or not
+pub(crate) trait Receive<T>: Send {
+    fn recv(&mut self) -> impl Future<Output = Option<T>> + Send + '_;
+}
+
+impl<T: Send> Receive<T> for mpsc::Receiver<T> {
+    fn recv(&mut self) -> impl Future<Output = Option<T>> + Send + '_ {
+        mpsc::Receiver::recv(self)
+    }
+}
+
+impl<T: Send> Receive<T> for mpsc::UnboundedReceiver<T> {
+    fn recv(&mut self) -> impl Future<Output = Option<T>> + Send + '_ {
+        mpsc::UnboundedReceiver::recv(self)
+    }
+}
+
+/// Collect items from `receiver` into `pending`, flushing when it is full,
+/// `interval` after the first item of a batch, and once more when the
+/// channel closes
+pub(crate) async fn run<P, R>(mut receiver: R, mut pending: P, interval: Duration)
+where
+    P: Pending,
+    R: Receive<P::Item>,
+{
+    let mut deadline: Option<Instant> = None;
+
+    loop {
+        let received = match deadline {
+            Some(at) => match tokio::time::timeout_at(at, receiver.recv()).await {
+                Ok(received) => received,
+                Err(_) => {
+                    pending.flush().await;
+                    deadline = None;
+                    continue;
+                }
+            },
+            None => receiver.recv().await,
+        };
+
+        match received {
+            Some(item) => {
+                pending.push(item);
+                if pending.is_full() {
+                    pending.flush().await;
+                }
+            }
+            None => {
+                pending.flush().await;
+                return;
+            }
+        }
+
+        deadline = match (deadline, pending.is_empty()) {
+            (_, true) => None,
+            (Some(at), false) => Some(at),
+            (None, false) => Some(Instant::now() + interval),
+        };
+    }
+}
+
+/// Input channel and task handle of a running batcher
+#[derive(Debug)]
+pub(crate) struct BatcherHandle<S> {
+    sender: Option<S>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl<S> BatcherHandle<S> {
+    pub(crate) fn new(sender: S, task: JoinHandle<()>) -> Self {
+        Self {
+            sender: Some(sender),
+            task: Some(task),
+        }
+    }
+
+    /// The input channel, or `None` once the batcher is closed
+    pub(crate) fn sender(&self) -> Option<&S> {
+        self.sender.as_ref()
+    }
+
+    /// Close the input channel and wait for the task's final flush
+    pub(crate) async fn close(&mut self) {
+        self.sender.take();
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
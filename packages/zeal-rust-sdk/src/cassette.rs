@@ -0,0 +1,312 @@
+//! Record/replay cassettes for HTTP interactions
+//!
+//! When [`ClientConfig::record_replay`](crate::config::ClientConfig::record_replay) is set,
+//! every request made by the SDK is either recorded to a JSON cassette file or served from
+//! one without touching the network. Requests are matched by method, URL and a SHA-256
+//! hash of the request body.
+
+use crate::config::RecordReplayMode;
+use crate::errors::{Result, ZealError};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A set of recorded HTTP interactions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// A single recorded request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(rename = "bodyHash")]
+    pub body_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: String,
+}
+
+impl Cassette {
+    /// Load a cassette from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Write the cassette to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Indices of all interactions matching the given request
+    fn matching(&self, method: &str, url: &str, body_hash: &str) -> Vec<usize> {
+        self.interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.request.method == method
+                    && i.request.url == url
+                    && i.request.body_hash == body_hash
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+}
+
+/// Hex-encoded SHA-256 hash of a request body
+pub fn body_hash(body: &[u8]) -> String {
+    Sha256::digest(body)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Records or replays interactions for an HTTP client
+#[derive(Debug)]
+pub(crate) struct CassetteRecorder {
+    mode: RecordReplayMode,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+    served: Mutex<Vec<bool>>,
+}
+
+impl CassetteRecorder {
+    /// Start a new recording, or load an existing cassette for replay
+    pub(crate) fn new(mode: RecordReplayMode, path: PathBuf) -> Result<Self> {
+        let cassette = match mode {
+            RecordReplayMode::Record => Cassette::default(),
+            RecordReplayMode::Replay => Cassette::load(&path).map_err(|e| {
+                ZealError::configuration_error(format!(
+                    "Failed to load cassette '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?,
+        };
+        let served = vec![false; cassette.interactions.len()];
+
+        Ok(Self {
+            mode,
+            path,
+            cassette: Mutex::new(cassette),
+            served: Mutex::new(served),
+        })
+    }
+
+    /// Execute a request according to the recorder mode
+    pub(crate) async fn execute(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let hash = body_hash(
+            request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or_default(),
+        );
+
+        match self.mode {
+            RecordReplayMode::Replay => self.replay(&method, &url, &hash),
+            RecordReplayMode::Record => {
+                let response = client.execute(request).await?;
+                let recorded = RecordedResponse {
+                    status: response.status().as_u16(),
+                    headers: response
+                        .headers()
+                        .iter()
+                        .filter_map(|(name, value)| {
+                            value
+                                .to_str()
+                                .ok()
+                                .map(|v| (name.to_string(), v.to_string()))
+                        })
+                        .collect(),
+                    body: String::from_utf8_lossy(&response.bytes().await?).into_owned(),
+                };
+
+                {
+                    let mut cassette = self.cassette.lock();
+                    cassette.interactions.push(Interaction {
+                        request: RecordedRequest {
+                            method,
+                            url,
+                            body_hash: hash,
+                        },
+                        response: recorded.clone(),
+                    });
+                    cassette.save(&self.path)?;
+                }
+
+                to_response(&recorded)
+            }
+        }
+    }
+
+    /// Serve a recorded response. Identical requests are answered in recording
+    /// order; once exhausted, the last matching response is repeated.
+    fn replay(&self, method: &str, url: &str, hash: &str) -> Result<reqwest::Response> {
+        let cassette = self.cassette.lock();
+        let matches = cassette.matching(method, url, hash);
+        let Some(&last) = matches.last() else {
+            return Err(ZealError::other(format!(
+                "No recorded interaction for {} {} in cassette '{}'",
+                method,
+                url,
+                self.path.display()
+            )));
+        };
+
+        let mut served = self.served.lock();
+        let index = matches
+            .iter()
+            .copied()
+            .find(|&i| !served[i])
+            .unwrap_or(last);
+        served[index] = true;
+
+        to_response(&cassette.interactions[index].response)
+    }
+}
+
+fn to_response(recorded: &RecordedResponse) -> Result<reqwest::Response> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+
+    let response = builder
+        .body(recorded.body.clone().into_bytes())
+        .map_err(|e| ZealError::other(format!("Invalid recorded response: {}", e)))?;
+    Ok(reqwest::Response::from(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClientConfig, PerformanceConfig, RecordReplayConfig};
+    use crate::types::CreateWorkflowRequest;
+    use crate::ZealClient;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_config(base_url: &str, record_replay: RecordReplayConfig) -> ClientConfig {
+        ClientConfig {
+            base_url: base_url.to_string(),
+            performance: PerformanceConfig {
+                http2_prior_knowledge: false,
+                ..Default::default()
+            },
+            record_replay: Some(record_replay),
+            ..Default::default()
+        }
+    }
+
+    fn create_request(name: &str) -> CreateWorkflowRequest {
+        CreateWorkflowRequest {
+            name: name.to_string(),
+            description: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_body_hash_is_stable() {
+        assert_eq!(body_hash(b"abc"), body_hash(b"abc"));
+        assert_ne!(body_hash(b"abc"), body_hash(b"abd"));
+        assert_eq!(body_hash(b"").len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_without_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let cassette_path = dir.path().join("cassette.json");
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/workflows"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "workflowId": "wf-1",
+                "graphId": "main",
+                "embedUrl": "http://localhost/embed/wf-1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let recording = ZealClient::new(client_config(
+            &server.uri(),
+            RecordReplayConfig::record(&cassette_path),
+        ))
+        .unwrap();
+        let recorded = recording
+            .orchestrator()
+            .create_workflow(create_request("demo"))
+            .await
+            .unwrap();
+        assert_eq!(recorded.workflow_id, "wf-1");
+
+        let base_url = server.uri();
+        drop(server);
+
+        let cassette = Cassette::load(&cassette_path).unwrap();
+        assert_eq!(cassette.interactions.len(), 1);
+        assert_eq!(cassette.interactions[0].request.method, "POST");
+
+        let replaying = ZealClient::new(client_config(
+            &base_url,
+            RecordReplayConfig::replay(&cassette_path),
+        ))
+        .unwrap();
+        let replayed = replaying
+            .orchestrator()
+            .create_workflow(create_request("demo"))
+            .await
+            .unwrap();
+        assert_eq!(replayed.workflow_id, "wf-1");
+        assert_eq!(replayed.embed_url.as_str(), "http://localhost/embed/wf-1");
+
+        // A different body hashes differently and has no recording
+        let err = replaying
+            .orchestrator()
+            .create_workflow(create_request("other"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No recorded interaction"));
+    }
+
+    #[test]
+    fn test_replay_requires_existing_cassette() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = client_config(
+            "http://localhost:3000",
+            RecordReplayConfig::replay(dir.path().join("missing.json")),
+        );
+
+        assert!(matches!(
+            ZealClient::new(config),
+            Err(ZealError::ConfigurationError { .. })
+        ));
+    }
+}
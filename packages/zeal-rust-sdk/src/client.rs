@@ -2,16 +2,25 @@
 
 use crate::config::ClientConfig;
 use crate::errors::{Result, ZealError};
+use crate::http::{self, HttpClient};
+use crate::json;
 use crate::orchestrator::OrchestratorAPI;
+use crate::subscription::{SubscriptionOptions, WebhookSubscription};
 use crate::templates::TemplatesAPI;
 use crate::traces::TracesAPI;
 use crate::types::HealthCheckResponse;
 use crate::webhooks::WebhooksAPI;
 
 /// Main client for interacting with the Zeal Integration Protocol
+///
+/// The API modules it exposes share one HTTP client, so their requests reuse
+/// the same connection pool, retry budget and concurrency limit. APIs built
+/// on their own with `new()` each get a separate client; to share
+/// connections between those, create them with `with_client` and a clone of
+/// the same `reqwest::Client`.
 pub struct ZealClient {
     config: ClientConfig,
-    http_client: reqwest::Client,
+    http_client: HttpClient,
     templates_api: TemplatesAPI,
     orchestrator_api: OrchestratorAPI,
     traces_api: TracesAPI,
@@ -25,13 +34,15 @@ impl ZealClient {
         if config.base_url.is_empty() {
             return Err(ZealError::configuration_error("Base URL cannot be empty"));
         }
+        if config.max_concurrent_requests == Some(0) {
+            return Err(ZealError::configuration_error(
+                "max_concurrent_requests must be greater than zero",
+            ));
+        }
 
         // Build HTTP client with performance optimizations
-        let mut client_builder = reqwest::Client::builder()
+        let mut client_builder = http::pooled_client_builder(&config.performance)
             .timeout(config.default_timeout)
-            .pool_idle_timeout(config.performance.idle_timeout)
-            .pool_max_idle_per_host(config.performance.max_idle_connections)
-            .tcp_keepalive(config.performance.tcp_keepalive)
             .user_agent(&config.user_agent);
 
         // Configure TLS
@@ -50,14 +61,32 @@ impl ZealClient {
             // client_builder = client_builder.gzip(true);
         }
 
-        let http_client = client_builder.build()?;
+        let http_client =
+            HttpClient::with_record_replay(client_builder.build()?, config.record_replay.as_ref())?
+                .with_retry_config(config.retry.clone())
+                .with_token_provider(config.auth.as_ref().map(|auth| auth.provider()))
+                .with_max_concurrent_requests(config.max_concurrent_requests)
+                .with_pool_acquire_timeout(config.performance.pool_acquire_timeout);
 
         // Initialize API modules with shared HTTP client
         let base_url = &config.base_url;
-        let templates_api = TemplatesAPI::with_client(base_url, http_client.clone());
-        let orchestrator_api = OrchestratorAPI::with_client(base_url, http_client.clone());
-        let traces_api = TracesAPI::with_client(base_url, http_client.clone());
-        let webhooks_api = WebhooksAPI::with_client(base_url, http_client.clone());
+        let templates_api = TemplatesAPI::with_http_client(base_url, http_client.clone());
+        let mut orchestrator_api = OrchestratorAPI::with_http_client(base_url, http_client.clone())
+            .with_batch_concurrency(config.performance.max_connections_per_host);
+        if let Some(secrets) = &config.secret_properties {
+            orchestrator_api = orchestrator_api.with_secret_properties(secrets.clone());
+        }
+        let traces_api = TracesAPI::with_http_client(base_url, http_client.clone())
+            .with_max_batch_size(config.performance.trace_batch_size)
+            .with_compression_threshold(
+                config
+                    .performance
+                    .compression
+                    .then_some(config.performance.compression_threshold),
+            )
+            .with_max_inline_data_size(config.performance.max_inline_data_size);
+        let webhooks_api = WebhooksAPI::with_http_client(base_url, http_client.clone())
+            .with_batch_concurrency(config.performance.max_connections_per_host);
 
         Ok(Self {
             config,
@@ -87,7 +116,7 @@ impl ZealClient {
             ));
         }
 
-        let health_response = response.json::<HealthCheckResponse>().await?;
+        let health_response = json::from_response::<HealthCheckResponse>(response).await?;
         Ok(health_response)
     }
 
@@ -98,7 +127,7 @@ impl ZealClient {
 
     /// Get the HTTP client for internal use
     #[allow(dead_code)]
-    pub(crate) fn http_client(&self) -> &reqwest::Client {
+    pub(crate) fn http_client(&self) -> &HttpClient {
         &self.http_client
     }
 
@@ -126,12 +155,20 @@ impl ZealClient {
     pub fn webhooks(&self) -> &WebhooksAPI {
         &self.webhooks_api
     }
+
+    /// Create a webhook subscription backed by this client's HTTP client
+    pub fn subscribe(&self, options: Option<SubscriptionOptions>) -> WebhookSubscription {
+        let webhooks_api = WebhooksAPI::with_http_client(self.base_url(), self.http_client.clone());
+        WebhookSubscription::new(webhooks_api, options)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::ClientConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
 
     #[test]
     fn test_client_creation() {
@@ -165,4 +202,147 @@ mod tests {
         let client = ZealClient::new(config).unwrap();
         assert_eq!(client.base_url(), "http://localhost:3000");
     }
+
+    /// Plain HTTP/1.1 server answering `[]` to every request, counting the
+    /// TCP connections it accepts
+    async fn connection_counting_server() -> (String, Arc<AtomicUsize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = stream.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let requests = String::from_utf8_lossy(&buf[..n])
+                            .matches("\r\n\r\n")
+                            .count();
+                        let reply = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n[]";
+                        if stream
+                            .write_all(reply.repeat(requests).as_bytes())
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (url, connections)
+    }
+
+    #[tokio::test]
+    async fn test_apis_share_one_connection_pool() {
+        let (url, connections) = connection_counting_server().await;
+        let mut config = ClientConfig {
+            base_url: url,
+            ..Default::default()
+        };
+        config.performance.http2_prior_knowledge = false;
+        let client = ZealClient::new(config).unwrap();
+
+        client.webhooks().list("default").await.unwrap();
+        let _ = client.templates().list("default").await;
+        let _ = client.traces().get_session_events("session-1", None).await;
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+
+        // Standalone constructors each get their own client...
+        let (url, connections) = connection_counting_server().await;
+        WebhooksAPI::new(&url).list("default").await.unwrap();
+        let _ = TemplatesAPI::new(&url).list("default").await;
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+        // ...unless they are handed the same one
+        let (url, connections) = connection_counting_server().await;
+        let shared = reqwest::Client::new();
+        WebhooksAPI::with_client(&url, shared.clone())
+            .list("default")
+            .await
+            .unwrap();
+        let _ = TemplatesAPI::with_client(&url, shared)
+            .list("default")
+            .await;
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_uses_client_base_url() {
+        let config = ClientConfig {
+            base_url: "https://zeal.example.com".to_string(),
+            ..Default::default()
+        };
+
+        let client = ZealClient::new(config).unwrap();
+        let subscription = client.subscribe(Some(SubscriptionOptions {
+            namespace: Some("ci".to_string()),
+            ..Default::default()
+        }));
+        assert!(!subscription.is_running());
+        assert_eq!(
+            subscription.webhooks_api().base_url(),
+            "https://zeal.example.com"
+        );
+    }
+
+    /// Hands out `token-1`, `token-2`, ... counting how often it is asked
+    struct CountingProvider(AtomicUsize);
+
+    impl crate::auth::TokenProvider for CountingProvider {
+        async fn token(&self) -> Result<String> {
+            Ok(format!(
+                "token-{}",
+                self.0.fetch_add(1, Ordering::SeqCst) + 1
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_provider_tokens() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        for token in ["Bearer token-1", "Bearer token-2", "Bearer static"] {
+            Mock::given(method("GET"))
+                .and(header("authorization", token))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let mut config = ClientConfig::builder()
+            .base_url(server.uri())
+            .token_provider(CountingProvider(AtomicUsize::new(0)))
+            .build()
+            .unwrap();
+        config.performance.http2_prior_knowledge = false;
+        let client = ZealClient::new(config).unwrap();
+        client.webhooks().list("default").await.unwrap();
+        client.webhooks().list("default").await.unwrap();
+
+        // A plain bearer token is sent as-is
+        let mut config = ClientConfig::builder()
+            .base_url(server.uri())
+            .bearer_token("static")
+            .build()
+            .unwrap();
+        config.performance.http2_prior_knowledge = false;
+        ZealClient::new(config)
+            .unwrap()
+            .webhooks()
+            .list("default")
+            .await
+            .unwrap();
+
+        server.verify().await;
+    }
 }
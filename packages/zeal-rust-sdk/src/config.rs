@@ -1,5 +1,9 @@
 //! Configuration types for the Zeal SDK
 
+use crate::auth::{SharedTokenProvider, StaticTokenProvider, TokenProvider};
+use crate::errors::{Result, ZealError};
+use crate::secrets::SecretProperties;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Main configuration for the Zeal client
@@ -25,6 +29,19 @@ pub struct ClientConfig {
 
     /// Enable TLS certificate verification
     pub verify_tls: bool,
+
+    /// Record HTTP interactions to, or replay them from, a cassette file
+    pub record_replay: Option<RecordReplayConfig>,
+
+    /// Encrypt secret node property values before sending them
+    pub secret_properties: Option<SecretProperties>,
+
+    /// Retry policy for API requests
+    pub retry: RetryConfig,
+
+    /// Maximum number of API requests in flight at once across the client;
+    /// requests beyond the limit wait for one to finish. `None` is unlimited.
+    pub max_concurrent_requests: Option<usize>,
 }
 
 impl Default for ClientConfig {
@@ -37,10 +54,254 @@ impl Default for ClientConfig {
             user_agent: format!("zeal-rust-sdk/{}", crate::VERSION),
             default_timeout: Duration::from_secs(30),
             verify_tls: true,
+            record_replay: None,
+            secret_properties: None,
+            retry: RetryConfig::default(),
+            max_concurrent_requests: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Start building a configuration from the defaults
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+
+    /// Build a configuration from `ZEAL_*` environment variables, keeping the
+    /// defaults for anything unset or empty:
+    ///
+    /// - `ZEAL_BASE_URL`, `ZEAL_WS_PATH`
+    /// - `ZEAL_TOKEN` (sets `auth` to a bearer token)
+    /// - `ZEAL_VERIFY_TLS`, `ZEAL_HTTP2_PRIOR_KNOWLEDGE` (`true`/`false`/`1`/`0`)
+    /// - `ZEAL_TIMEOUT_MS`, `ZEAL_CONNECT_TIMEOUT_MS`, `ZEAL_IDLE_TIMEOUT_MS`
+    /// - `ZEAL_MAX_CONNECTIONS_PER_HOST`, `ZEAL_MAX_IDLE_CONNECTIONS`, `ZEAL_TRACE_BATCH_SIZE`
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let mut config = Self::default();
+
+        if let Some(base_url) = var("ZEAL_BASE_URL") {
+            config.base_url = base_url;
+        }
+        if let Some(path) = var("ZEAL_WS_PATH") {
+            config.websocket_path = Some(path);
+        }
+        if let Some(token) = var("ZEAL_TOKEN") {
+            config.auth = Some(AuthConfig::new(token));
+        }
+        if let Some(value) = var("ZEAL_VERIFY_TLS") {
+            config.verify_tls = parse_env_bool("ZEAL_VERIFY_TLS", &value)?;
+        }
+        if let Some(value) = var("ZEAL_TIMEOUT_MS") {
+            config.default_timeout = parse_env_millis("ZEAL_TIMEOUT_MS", &value)?;
+        }
+
+        let performance = &mut config.performance;
+        if let Some(value) = var("ZEAL_HTTP2_PRIOR_KNOWLEDGE") {
+            performance.http2_prior_knowledge =
+                parse_env_bool("ZEAL_HTTP2_PRIOR_KNOWLEDGE", &value)?;
+        }
+        if let Some(value) = var("ZEAL_CONNECT_TIMEOUT_MS") {
+            performance.connection_timeout = parse_env_millis("ZEAL_CONNECT_TIMEOUT_MS", &value)?;
+        }
+        if let Some(value) = var("ZEAL_IDLE_TIMEOUT_MS") {
+            performance.idle_timeout = parse_env_millis("ZEAL_IDLE_TIMEOUT_MS", &value)?;
+        }
+        if let Some(value) = var("ZEAL_MAX_CONNECTIONS_PER_HOST") {
+            performance.max_connections_per_host =
+                parse_env_number("ZEAL_MAX_CONNECTIONS_PER_HOST", &value)?;
+        }
+        if let Some(value) = var("ZEAL_MAX_IDLE_CONNECTIONS") {
+            performance.max_idle_connections =
+                parse_env_number("ZEAL_MAX_IDLE_CONNECTIONS", &value)?;
+        }
+        if let Some(value) = var("ZEAL_TRACE_BATCH_SIZE") {
+            performance.trace_batch_size = parse_env_number("ZEAL_TRACE_BATCH_SIZE", &value)?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// Chainable builder for [`ClientConfig`]
+#[derive(Debug, Default)]
+pub struct ClientConfigBuilder {
+    config: ClientConfig,
+}
+
+impl ClientConfigBuilder {
+    /// Set the base URL of the Zeal server
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.config.base_url = base_url.into();
+        self
+    }
+
+    /// Set the WebSocket path
+    pub fn websocket_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.config.websocket_path = Some(path.into());
+        self
+    }
+
+    /// Authenticate with a bearer token
+    pub fn bearer_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.config.auth = Some(AuthConfig::new(token.into()));
+        self
+    }
+
+    /// Authenticate with tokens from a provider, asked before each request
+    pub fn token_provider<P: TokenProvider + 'static>(mut self, provider: P) -> Self {
+        self.config.auth = Some(AuthConfig::with_token_provider(provider));
+        self
+    }
+
+    /// Set the user agent string
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    /// Set the default timeout for API requests
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.default_timeout = timeout;
+        self
+    }
+
+    /// Enable or disable TLS certificate verification
+    pub fn verify_tls(mut self, verify: bool) -> Self {
+        self.config.verify_tls = verify;
+        self
+    }
+
+    /// Record or replay HTTP interactions
+    pub fn record_replay(mut self, record_replay: RecordReplayConfig) -> Self {
+        self.config.record_replay = Some(record_replay);
+        self
+    }
+
+    /// Encrypt secret node property values before sending them
+    pub fn secret_properties(mut self, secret_properties: SecretProperties) -> Self {
+        self.config.secret_properties = Some(secret_properties);
+        self
+    }
+
+    /// Set the retry policy
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    /// Limit the number of API requests in flight at once
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.config.max_concurrent_requests = Some(max);
+        self
+    }
+
+    /// Replace the whole performance configuration
+    pub fn performance(mut self, performance: PerformanceConfig) -> Self {
+        self.config.performance = performance;
+        self
+    }
+
+    /// Set the maximum number of connections per host
+    pub fn max_connections_per_host(mut self, max: usize) -> Self {
+        self.config.performance.max_connections_per_host = max;
+        self
+    }
+
+    /// Set the connection timeout
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.config.performance.connection_timeout = timeout;
+        self
+    }
+
+    /// Set how long a request may wait for a free concurrency slot
+    pub fn pool_acquire_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.performance.pool_acquire_timeout = timeout;
+        self
+    }
+
+    /// Set the TCP keepalive duration
+    pub fn tcp_keepalive(mut self, keepalive: Option<Duration>) -> Self {
+        self.config.performance.tcp_keepalive = keepalive;
+        self
+    }
+
+    /// Enable or disable HTTP/2 prior knowledge
+    pub fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.config.performance.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Enable or disable connection pooling
+    pub fn connection_pooling(mut self, enabled: bool) -> Self {
+        self.config.performance.connection_pooling = enabled;
+        self
+    }
+
+    /// Set the maximum number of idle connections
+    pub fn max_idle_connections(mut self, max: usize) -> Self {
+        self.config.performance.max_idle_connections = max;
+        self
+    }
+
+    /// Set the idle connection timeout
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.performance.idle_timeout = timeout;
+        self
+    }
+
+    /// Set the number of trace events sent per request
+    pub fn trace_batch_size(mut self, size: usize) -> Self {
+        self.config.performance.trace_batch_size = size;
+        self
+    }
+
+    /// Build the configuration, checking that the base URL parses and any
+    /// concurrency limit is non-zero
+    pub fn build(self) -> Result<ClientConfig> {
+        url::Url::parse(&self.config.base_url).map_err(|e| {
+            ZealError::configuration_error(format!(
+                "Invalid base URL {:?}: {}",
+                self.config.base_url, e
+            ))
+        })?;
+        if self.config.max_concurrent_requests == Some(0) {
+            return Err(ZealError::configuration_error(
+                "max_concurrent_requests must be greater than zero",
+            ));
         }
+        Ok(self.config)
     }
 }
 
+fn parse_env_bool(name: &str, value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(ZealError::configuration_error(format!(
+            "{} must be true or false, got {:?}",
+            name, value
+        ))),
+    }
+}
+
+fn parse_env_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value.trim().parse().map_err(|_| {
+        ZealError::configuration_error(format!(
+            "{} must be a non-negative integer, got {:?}",
+            name, value
+        ))
+    })
+}
+
+fn parse_env_millis(name: &str, value: &str) -> Result<Duration> {
+    parse_env_number(name, value).map(Duration::from_millis)
+}
+
 /// Performance-related configuration
 #[derive(Debug, Clone)]
 pub struct PerformanceConfig {
@@ -53,6 +314,13 @@ pub struct PerformanceConfig {
     /// Request timeout
     pub request_timeout: Duration,
 
+    /// How long one request attempt may take to get a connection and its
+    /// response headers, including any wait for a slot under
+    /// `max_concurrent_requests`, before failing with a
+    /// [`ZealError::ConnectionError`] that points at pool exhaustion instead
+    /// of stalling. That failure is not retried. `None` waits indefinitely.
+    pub pool_acquire_timeout: Option<Duration>,
+
     /// TCP keepalive duration
     pub tcp_keepalive: Option<Duration>,
 
@@ -68,9 +336,18 @@ pub struct PerformanceConfig {
     /// Idle connection timeout
     pub idle_timeout: Duration,
 
-    /// Enable compression
+    /// Enable compression. Trace submissions larger than
+    /// `compression_threshold` are sent gzip-encoded.
     pub compression: bool,
 
+    /// Serialized size in bytes above which a trace request body is
+    /// compressed
+    pub compression_threshold: usize,
+
+    /// Serialized size in bytes above which a trace event's `full_data` is
+    /// dropped and a truncated copy kept as its preview; `None` never truncates
+    pub max_inline_data_size: Option<usize>,
+
     /// WebSocket ping interval
     pub ws_ping_interval: Duration,
 
@@ -91,6 +368,9 @@ pub struct PerformanceConfig {
 
     /// Batch timeout for trace events
     pub trace_batch_timeout: Duration,
+
+    /// How long visual state changes are coalesced before being flushed
+    pub visual_state_flush_interval: Duration,
 }
 
 impl Default for PerformanceConfig {
@@ -99,12 +379,15 @@ impl Default for PerformanceConfig {
             max_connections_per_host: 50,
             connection_timeout: Duration::from_secs(10),
             request_timeout: Duration::from_secs(30),
+            pool_acquire_timeout: Some(Duration::from_secs(30)),
             tcp_keepalive: Some(Duration::from_secs(60)),
             http2_prior_knowledge: true,
             connection_pooling: true,
             max_idle_connections: 10,
             idle_timeout: Duration::from_secs(90),
             compression: true,
+            compression_threshold: 16 * 1024,
+            max_inline_data_size: Some(1024 * 1024),
             ws_ping_interval: Duration::from_secs(30),
             ws_pong_timeout: Duration::from_secs(10),
             ws_max_message_size: 64 * 1024 * 1024, // 64MB
@@ -112,15 +395,160 @@ impl Default for PerformanceConfig {
             stream_buffer_size: 8192,
             trace_batch_size: 1000,
             trace_batch_timeout: Duration::from_millis(100),
+            visual_state_flush_interval: Duration::from_millis(50),
         }
     }
 }
 
+impl PerformanceConfig {
+    /// Start building a performance configuration from the defaults
+    pub fn builder() -> PerformanceConfigBuilder {
+        PerformanceConfigBuilder::default()
+    }
+}
+
+/// Chainable builder for [`PerformanceConfig`]
+#[derive(Debug, Default)]
+pub struct PerformanceConfigBuilder {
+    config: PerformanceConfig,
+}
+
+impl PerformanceConfigBuilder {
+    /// Set the maximum number of connections per host
+    pub fn with_max_connections_per_host(mut self, max: usize) -> Self {
+        self.config.max_connections_per_host = max;
+        self
+    }
+
+    /// Set the connection timeout
+    pub fn with_connection_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connection_timeout = timeout;
+        self
+    }
+
+    /// Set the request timeout
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout = timeout;
+        self
+    }
+
+    /// Set how long a request attempt may wait for a connection
+    pub fn with_pool_acquire_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.pool_acquire_timeout = timeout;
+        self
+    }
+
+    /// Set the TCP keepalive duration
+    pub fn with_tcp_keepalive(mut self, keepalive: Option<Duration>) -> Self {
+        self.config.tcp_keepalive = keepalive;
+        self
+    }
+
+    /// Enable or disable HTTP/2 prior knowledge
+    pub fn with_http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.config.http2_prior_knowledge = enabled;
+        self
+    }
+
+    /// Enable or disable connection pooling
+    pub fn with_connection_pooling(mut self, enabled: bool) -> Self {
+        self.config.connection_pooling = enabled;
+        self
+    }
+
+    /// Set the maximum number of idle connections
+    pub fn with_max_idle_connections(mut self, max: usize) -> Self {
+        self.config.max_idle_connections = max;
+        self
+    }
+
+    /// Set the idle connection timeout
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.idle_timeout = timeout;
+        self
+    }
+
+    /// Enable or disable compression of large trace submissions
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.config.compression = enabled;
+        self
+    }
+
+    /// Set the body size in bytes above which trace requests are compressed
+    pub fn with_compression_threshold(mut self, size: usize) -> Self {
+        self.config.compression_threshold = size;
+        self
+    }
+
+    /// Set the size in bytes above which trace event data is truncated
+    pub fn with_max_inline_data_size(mut self, size: Option<usize>) -> Self {
+        self.config.max_inline_data_size = size;
+        self
+    }
+
+    /// Set the WebSocket ping interval
+    pub fn with_ws_ping_interval(mut self, interval: Duration) -> Self {
+        self.config.ws_ping_interval = interval;
+        self
+    }
+
+    /// Set the WebSocket pong timeout
+    pub fn with_ws_pong_timeout(mut self, timeout: Duration) -> Self {
+        self.config.ws_pong_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum WebSocket message size
+    pub fn with_ws_max_message_size(mut self, size: usize) -> Self {
+        self.config.ws_max_message_size = size;
+        self
+    }
+
+    /// Set the maximum WebSocket frame size
+    pub fn with_ws_max_frame_size(mut self, size: usize) -> Self {
+        self.config.ws_max_frame_size = size;
+        self
+    }
+
+    /// Set the buffer size for streaming operations
+    pub fn with_stream_buffer_size(mut self, size: usize) -> Self {
+        self.config.stream_buffer_size = size;
+        self
+    }
+
+    /// Set the number of trace events sent per request
+    pub fn with_trace_batch_size(mut self, size: usize) -> Self {
+        self.config.trace_batch_size = size;
+        self
+    }
+
+    /// Set the batch timeout for trace events
+    pub fn with_trace_batch_timeout(mut self, timeout: Duration) -> Self {
+        self.config.trace_batch_timeout = timeout;
+        self
+    }
+
+    /// Set how long visual state changes are coalesced
+    pub fn with_visual_state_flush_interval(mut self, interval: Duration) -> Self {
+        self.config.visual_state_flush_interval = interval;
+        self
+    }
+
+    /// Build the configuration
+    pub fn build(self) -> PerformanceConfig {
+        self.config
+    }
+}
+
 /// Authentication configuration
 #[derive(Debug, Clone)]
 pub struct AuthConfig {
     /// Bearer token for authentication
     pub bearer_token: String,
+
+    /// Provider asked for a token before each request; takes precedence
+    /// over `bearer_token`
+    pub token_provider: Option<SharedTokenProvider>,
 }
 
 impl AuthConfig {
@@ -128,6 +556,7 @@ impl AuthConfig {
     pub fn new(token: String) -> Self {
         Self {
             bearer_token: token,
+            token_provider: None,
         }
     }
 
@@ -135,6 +564,59 @@ impl AuthConfig {
     pub fn with_bearer_token(token: String) -> Self {
         Self::new(token)
     }
+
+    /// Create auth config that gets tokens from a provider
+    pub fn with_token_provider<P: TokenProvider + 'static>(provider: P) -> Self {
+        Self {
+            bearer_token: String::new(),
+            token_provider: Some(SharedTokenProvider::new(provider)),
+        }
+    }
+
+    /// The provider requests are authenticated with; a plain bearer token is
+    /// served by a `StaticTokenProvider`
+    pub fn provider(&self) -> SharedTokenProvider {
+        self.token_provider.clone().unwrap_or_else(|| {
+            SharedTokenProvider::new(StaticTokenProvider::new(self.bearer_token.clone()))
+        })
+    }
+}
+
+/// Record/replay mode for HTTP interactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordReplayMode {
+    /// Perform real requests and write each request/response pair to the cassette
+    Record,
+    /// Serve responses from the cassette without touching the network
+    Replay,
+}
+
+/// Record/replay configuration
+#[derive(Debug, Clone)]
+pub struct RecordReplayConfig {
+    /// Whether to record or replay
+    pub mode: RecordReplayMode,
+
+    /// Path of the JSON cassette file
+    pub cassette_path: PathBuf,
+}
+
+impl RecordReplayConfig {
+    /// Record interactions to the given cassette file
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: RecordReplayMode::Record,
+            cassette_path: path.into(),
+        }
+    }
+
+    /// Replay interactions from the given cassette file
+    pub fn replay(path: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: RecordReplayMode::Replay,
+            cassette_path: path.into(),
+        }
+    }
 }
 
 /// Retry configuration
@@ -157,6 +639,15 @@ pub struct RetryConfig {
 
     /// HTTP status codes that should trigger retries
     pub retryable_status_codes: Vec<u16>,
+
+    /// Retry budget shared by every request from a client; `None` retries
+    /// each request independently
+    pub budget: Option<RetryBudgetConfig>,
+
+    /// Also retry non-idempotent requests (`POST`, `PATCH`). Off by default:
+    /// a retry after a timeout or 5xx can repeat a change the server already
+    /// applied, e.g. creating a workflow or node twice.
+    pub retry_non_idempotent: bool,
 }
 
 impl Default for RetryConfig {
@@ -168,6 +659,32 @@ impl Default for RetryConfig {
             backoff_multiplier: 2.0,
             jitter_factor: 0.1,
             retryable_status_codes: vec![408, 429, 500, 502, 503, 504],
+            budget: None,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+/// Token bucket that throttles retries across all requests, so a widespread
+/// outage doesn't turn into a retry storm. Each request that succeeds (with
+/// a response whose status isn't retried) deposits `retry_ratio` tokens and
+/// each retry spends one; failures deposit nothing. Once the bucket is
+/// empty, failed requests return their error without retrying.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryBudgetConfig {
+    /// Tokens deposited per successful request, e.g. `0.1` allows one retry
+    /// for every ten successes
+    pub retry_ratio: f64,
+
+    /// Tokens available up front, and the most the bucket holds
+    pub burst: usize,
+}
+
+impl Default for RetryBudgetConfig {
+    fn default() -> Self {
+        Self {
+            retry_ratio: 0.1,
+            burst: 10,
         }
     }
 }
@@ -201,6 +718,15 @@ pub struct WebSocketConfig {
 
     /// Enable compression
     pub compression: bool,
+
+    /// Time to wait for a subscription acknowledgement
+    pub subscribe_timeout: Duration,
+
+    /// Times to resend an unacknowledged subscribe request
+    pub subscribe_retries: usize,
+
+    /// Weight of the newest ping round trip in the smoothed latency, in (0, 1]
+    pub latency_ema_alpha: f64,
 }
 
 impl Default for WebSocketConfig {
@@ -215,6 +741,9 @@ impl Default for WebSocketConfig {
             reconnect_delay: Duration::from_millis(500),
             max_reconnect_delay: Duration::from_secs(30),
             compression: true,
+            subscribe_timeout: Duration::from_secs(5),
+            subscribe_retries: 2,
+            latency_ema_alpha: 0.2,
         }
     }
 }
@@ -229,6 +758,7 @@ mod tests {
         assert_eq!(config.base_url, "http://localhost:3000");
         assert_eq!(config.default_timeout, Duration::from_secs(30));
         assert!(config.verify_tls);
+        assert!(config.record_replay.is_none());
     }
 
     #[test]
@@ -239,6 +769,107 @@ mod tests {
         assert!(config.http2_prior_knowledge);
     }
 
+    #[test]
+    fn test_client_config_from_vars() {
+        let vars = |pairs: Vec<(&'static str, &'static str)>| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        let config = ClientConfig::from_vars(vars(vec![
+            ("ZEAL_BASE_URL", "https://zeal.example.com"),
+            ("ZEAL_TOKEN", "secret"),
+            ("ZEAL_WS_PATH", "/ws/custom"),
+            ("ZEAL_VERIFY_TLS", "FALSE"),
+            ("ZEAL_TIMEOUT_MS", "2500"),
+            ("ZEAL_MAX_CONNECTIONS_PER_HOST", "8"),
+            ("ZEAL_HTTP2_PRIOR_KNOWLEDGE", "0"),
+            ("ZEAL_TRACE_BATCH_SIZE", ""),
+        ]))
+        .unwrap();
+        assert_eq!(config.base_url, "https://zeal.example.com");
+        assert_eq!(config.auth.unwrap().bearer_token, "secret");
+        assert_eq!(config.websocket_path.as_deref(), Some("/ws/custom"));
+        assert!(!config.verify_tls);
+        assert_eq!(config.default_timeout, Duration::from_millis(2500));
+        assert_eq!(config.performance.max_connections_per_host, 8);
+        assert!(!config.performance.http2_prior_knowledge);
+        // Empty values fall back to the defaults
+        assert_eq!(config.performance.trace_batch_size, 1000);
+
+        let config = ClientConfig::from_vars(vars(vec![])).unwrap();
+        assert_eq!(config.base_url, "http://localhost:3000");
+        assert!(config.auth.is_none());
+
+        for invalid in [
+            ("ZEAL_VERIFY_TLS", "maybe"),
+            ("ZEAL_TIMEOUT_MS", "soon"),
+            ("ZEAL_MAX_IDLE_CONNECTIONS", "-1"),
+        ] {
+            let err = ClientConfig::from_vars(vars(vec![invalid])).unwrap_err();
+            assert!(
+                matches!(err, ZealError::ConfigurationError { ref message } if message.contains(invalid.0))
+            );
+        }
+    }
+
+    #[test]
+    fn test_client_config_builder() {
+        let config = ClientConfig::builder()
+            .base_url("https://zeal.example.com")
+            .bearer_token("secret")
+            .timeout(Duration::from_secs(5))
+            .verify_tls(false)
+            .max_connections_per_host(4)
+            .http2_prior_knowledge(false)
+            .build()
+            .unwrap();
+        assert_eq!(config.base_url, "https://zeal.example.com");
+        assert_eq!(config.auth.unwrap().bearer_token, "secret");
+        assert_eq!(config.default_timeout, Duration::from_secs(5));
+        assert!(!config.verify_tls);
+        assert_eq!(config.performance.max_connections_per_host, 4);
+        assert!(!config.performance.http2_prior_knowledge);
+        assert_eq!(config.performance.idle_timeout, Duration::from_secs(90));
+
+        let err = ClientConfig::builder()
+            .base_url("not a url")
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ZealError::ConfigurationError { .. }));
+
+        let err = ClientConfig::builder()
+            .max_concurrent_requests(0)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ZealError::ConfigurationError { .. }));
+    }
+
+    #[test]
+    fn test_performance_config_builder() {
+        let performance = PerformanceConfig::builder()
+            .with_max_connections_per_host(4)
+            .with_pool_acquire_timeout(None)
+            .with_compression(false)
+            .with_trace_batch_size(250)
+            .build();
+        assert_eq!(performance.max_connections_per_host, 4);
+        assert_eq!(performance.pool_acquire_timeout, None);
+        assert!(!performance.compression);
+        assert_eq!(performance.trace_batch_size, 250);
+        assert_eq!(performance.idle_timeout, Duration::from_secs(90));
+
+        let config = ClientConfig::builder()
+            .performance(performance)
+            .build()
+            .unwrap();
+        assert_eq!(config.performance.trace_batch_size, 250);
+    }
+
     #[test]
     fn test_auth_config_new() {
         let auth = AuthConfig::new("test-token".to_string());
@@ -85,6 +85,14 @@ pub enum ZealError {
         source: std::io::Error,
     },
 
+    /// Operation was cancelled before completing
+    #[error("Operation cancelled: {operation}")]
+    Cancelled { operation: String },
+
+    /// Webhook endpoint could not be reached by the Zeal server
+    #[error("Webhook endpoint unreachable: {url} ({detail})")]
+    WebhookUnreachable { url: String, detail: String },
+
     /// Generic errors
     #[error("Error: {message}")]
     Other { message: String },
@@ -147,6 +155,13 @@ impl Clone for ZealError {
             Self::IoError { .. } => Self::Other {
                 message: "IO error".to_string(),
             },
+            Self::Cancelled { operation } => Self::Cancelled {
+                operation: operation.clone(),
+            },
+            Self::WebhookUnreachable { url, detail } => Self::WebhookUnreachable {
+                url: url.clone(),
+                detail: detail.clone(),
+            },
             Self::Other { message } => Self::Other {
                 message: message.clone(),
             },
@@ -223,6 +238,18 @@ impl ZealError {
         }
     }
 
+    /// Map a `429 Too Many Requests` response to a rate limit error, taking
+    /// the delay from its `Retry-After` header when present and valid
+    pub fn rate_limited(response: &reqwest::Response) -> Self {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, chrono::Utc::now()));
+
+        Self::rate_limit_error(format!("Rate limited: {}", response.status()), retry_after)
+    }
+
     /// Create a timeout error
     pub fn timeout_error<S: Into<String>>(operation: S) -> Self {
         Self::TimeoutError {
@@ -237,6 +264,21 @@ impl ZealError {
         }
     }
 
+    /// Create a cancellation error
+    pub fn cancelled<S: Into<String>>(operation: S) -> Self {
+        Self::Cancelled {
+            operation: operation.into(),
+        }
+    }
+
+    /// Create a webhook unreachable error
+    pub fn webhook_unreachable<S: Into<String>>(url: S, detail: S) -> Self {
+        Self::WebhookUnreachable {
+            url: url.into(),
+            detail: detail.into(),
+        }
+    }
+
     /// Create a generic error
     pub fn other<S: Into<String>>(message: S) -> Self {
         Self::Other {
@@ -284,6 +326,62 @@ impl ZealError {
     }
 }
 
+/// Parse a `Retry-After` header value, either delay-seconds or an HTTP-date.
+/// Dates in the past yield a zero delay.
+pub fn parse_retry_after(
+    value: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<std::time::Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Structured error body returned by the Zeal API:
+/// `{"error":{"code":"...","message":"..."}}`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ZealApiErrorBody {
+    pub error: ZealApiErrorDetail,
+}
+
+/// The `error` object of a [`ZealApiErrorBody`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ZealApiErrorDetail {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+/// Map a failed response for `operation` (e.g. "create workflow") to an API
+/// error. Structured bodies populate the message and error code; anything
+/// else is kept as the raw text after the status.
+pub fn map_error_response(operation: &str, status: reqwest::StatusCode, body: &str) -> ZealError {
+    let message = match serde_json::from_str::<ZealApiErrorBody>(body) {
+        Ok(ZealApiErrorBody { error }) => {
+            let message = error.message.unwrap_or_else(|| status.to_string());
+            return ZealError::api_error(
+                status.as_u16(),
+                format!("Failed to {}: {}", operation, message),
+                error.code,
+            );
+        }
+        Err(_) if body.trim().is_empty() => format!("Failed to {}: {}", operation, status),
+        Err(_) => format!("Failed to {}: {}: {}", operation, status, body.trim()),
+    };
+    ZealError::api_error(status.as_u16(), message, None)
+}
+
 impl From<reqwest::Error> for ZealError {
     fn from(err: reqwest::Error) -> Self {
         Self::network_error(err)
@@ -308,6 +406,12 @@ impl From<std::io::Error> for ZealError {
     }
 }
 
+impl From<crate::observables::StreamTimeout> for ZealError {
+    fn from(err: crate::observables::StreamTimeout) -> Self {
+        Self::timeout_error(format!("waiting for stream item ({:?})", err.elapsed))
+    }
+}
+
 impl From<tokio_tungstenite::tungstenite::Error> for ZealError {
     fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
         Self::websocket_error(err.to_string())
@@ -395,6 +499,84 @@ mod tests {
 
         let err = ZealError::api_error(400, "Bad request".to_string(), None);
         assert!(!err.is_retryable());
+
+        assert!(ZealError::connection_error("refused").is_retryable());
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        use std::time::Duration;
+        let now = chrono::DateTime::parse_from_rfc3339("2015-10-21T07:28:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_rate_limited_response() {
+        let response = |retry_after: Option<&str>| -> reqwest::Response {
+            let mut builder = http::Response::builder().status(429);
+            if let Some(value) = retry_after {
+                builder = builder.header("Retry-After", value);
+            }
+            builder.body("").unwrap().into()
+        };
+
+        let err = ZealError::rate_limited(&response(Some("7")));
+        assert!(matches!(err, ZealError::RateLimitError { .. }));
+        assert_eq!(err.retry_after(), Some(std::time::Duration::from_secs(7)));
+
+        let in_a_minute = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = ZealError::rate_limited(&response(Some(&in_a_minute)))
+            .retry_after()
+            .unwrap();
+        assert!(delay > std::time::Duration::from_secs(55));
+
+        assert_eq!(ZealError::rate_limited(&response(None)).retry_after(), None);
+    }
+
+    #[test]
+    fn test_map_error_response() {
+        let status = reqwest::StatusCode::UNPROCESSABLE_ENTITY;
+
+        let err = map_error_response(
+            "register webhook",
+            status,
+            r#"{"error":{"code":"VALIDATION_ERROR","message":"Missing namespace"}}"#,
+        );
+        assert!(matches!(
+            &err,
+            ZealError::ApiError { status: 422, message, error_code: Some(code) }
+                if message == "Failed to register webhook: Missing namespace"
+                    && code == "VALIDATION_ERROR"
+        ));
+
+        let err = map_error_response("create workflow", status, "upstream exploded");
+        assert!(matches!(
+            &err,
+            ZealError::ApiError { message, error_code: None, .. }
+                if message == "Failed to create workflow: 422 Unprocessable Entity: upstream exploded"
+        ));
+
+        let err = map_error_response("create workflow", status, "");
+        assert!(matches!(
+            &err,
+            ZealError::ApiError { message, error_code: None, .. }
+                if message == "Failed to create workflow: 422 Unprocessable Entity"
+        ));
     }
 
     #[test]
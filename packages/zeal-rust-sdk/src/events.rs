@@ -134,6 +134,48 @@ pub struct ExecutionStartedEvent {
     pub trigger: Option<ExecutionTrigger>,
 }
 
+impl ExecutionStartedEvent {
+    /// Build the request that starts a trace session for this execution.
+    ///
+    /// The session ID becomes the trace's `executionId` and the trigger type
+    /// its `trigger`; a trigger source, which trace metadata has no field for,
+    /// is kept as a `trigger-source:<source>` tag. `workflowVersionId`,
+    /// `environment` and `tags` are taken from the event metadata when they
+    /// hold strings (`tags` may be a single string or a list of them).
+    pub fn to_trace_session_request(&self) -> crate::types::CreateTraceSessionRequest {
+        let metadata = self.base.metadata.as_ref();
+        let string_field = |key: &str| {
+            metadata
+                .and_then(|m| m.get(key))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+
+        let mut tags: Vec<String> = match metadata.and_then(|m| m.get("tags")) {
+            Some(serde_json::Value::String(tag)) => vec![tag.clone()],
+            Some(serde_json::Value::Array(values)) => values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if let Some(source) = self.trigger.as_ref().and_then(|t| t.source.as_ref()) {
+            tags.push(format!("trigger-source:{}", source));
+        }
+
+        crate::types::CreateTraceSessionRequest {
+            workflow_id: self.base.workflow_id.clone(),
+            workflow_version_id: string_field("workflowVersionId"),
+            execution_id: self.session_id.clone(),
+            metadata: Some(crate::types::TraceMetadata {
+                trigger: self.trigger.as_ref().map(|t| t.trigger_type.clone()),
+                environment: string_field("environment"),
+                tags,
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionCompletedEvent {
     #[serde(flatten)]
@@ -419,6 +461,22 @@ pub struct TraceEventData {
     pub data: serde_json::Value,
 }
 
+impl TraceEventData {
+    /// Convert the event payload into a typed [`TraceEvent`](crate::types::TraceEvent).
+    ///
+    /// `data` holds the trace event fields; the envelope's `nodeId` is used
+    /// when the payload does not carry its own.
+    pub fn to_trace_event(&self) -> crate::errors::Result<crate::types::TraceEvent> {
+        let mut data = self.data.clone();
+        if let Some(fields) = data.as_object_mut() {
+            fields
+                .entry("nodeId")
+                .or_insert_with(|| serde_json::Value::String(self.node_id.clone()));
+        }
+        Ok(serde_json::from_value(data)?)
+    }
+}
+
 /// Stream display events (from Reflow binary streaming infrastructure)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamOpenedEvent {
@@ -504,6 +562,14 @@ impl ZipStreamEvent {
         }
     }
 
+    pub fn node_id(&self) -> &str {
+        match self {
+            Self::StreamOpened(e) => &e.node_id,
+            Self::StreamClosed(e) => &e.node_id,
+            Self::StreamError(e) => &e.node_id,
+        }
+    }
+
     pub fn stream_id(&self) -> u64 {
         match self {
             Self::StreamOpened(e) => e.stream_id,
@@ -578,6 +644,29 @@ pub struct UnsubscribeEvent {
     pub workflow_id: Option<String>,
 }
 
+/// Acknowledgement that a subscription was accepted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscribedEvent {
+    #[serde(rename = "type")]
+    pub event_type: String, // Always "subscribed"
+    #[serde(rename = "workflowId")]
+    pub workflow_id: String,
+    #[serde(rename = "graphId", skip_serializing_if = "Option::is_none")]
+    pub graph_id: Option<String>,
+}
+
+/// Rejection of a subscription (unknown workflow, unauthorized, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscribeErrorEvent {
+    #[serde(rename = "type")]
+    pub event_type: String, // Always "subscribe.error"
+    #[serde(rename = "workflowId", skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<String>,
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PingEvent {
     #[serde(rename = "type")]
@@ -631,7 +720,7 @@ pub struct VisualStateElement {
 }
 
 /// Element type for visual updates
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ElementType {
     Node,
@@ -639,7 +728,7 @@ pub enum ElementType {
 }
 
 /// Element state for visual updates
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ElementState {
     Idle,
@@ -656,6 +745,152 @@ pub struct VisualStateUpdate {
     pub elements: Vec<VisualStateElement>,
 }
 
+impl VisualStateElement {
+    fn node(id: &str, state: ElementState, message: Option<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            element_type: ElementType::Node,
+            state,
+            progress: None,
+            message,
+        }
+    }
+
+    fn connection(id: &str, state: ElementState) -> Self {
+        Self {
+            id: id.to_string(),
+            element_type: ElementType::Connection,
+            state,
+            progress: None,
+            message: None,
+        }
+    }
+}
+
+impl VisualStateUpdate {
+    /// Map a node execution event to the editor's visual state: the node
+    /// itself, followed by the connections the event names.
+    ///
+    /// - `node.executing`: the node and its input connections are running
+    /// - `node.completed`: the node and its output connections succeeded
+    /// - `node.failed`: the node and its output connections are in error,
+    ///   with the error message on the node
+    /// - `node.warning`: the node and its output connections are in warning,
+    ///   with the warning message on the node
+    ///
+    /// Execution events carry no intermediate progress, so only completed
+    /// nodes report one (100 percent). Execution-level events don't target
+    /// an element and produce an empty update.
+    pub fn from_execution_event(event: &ZipExecutionEvent) -> Self {
+        let (node, connections, state) = match event {
+            ZipExecutionEvent::NodeExecuting(e) => (
+                VisualStateElement::node(&e.node_id, ElementState::Running, None),
+                &e.input_connections,
+                ElementState::Running,
+            ),
+            ZipExecutionEvent::NodeCompleted(e) => (
+                VisualStateElement {
+                    progress: Some(100.0),
+                    ..VisualStateElement::node(&e.node_id, ElementState::Success, None)
+                },
+                &e.output_connections,
+                ElementState::Success,
+            ),
+            ZipExecutionEvent::NodeFailed(e) => (
+                VisualStateElement::node(
+                    &e.node_id,
+                    ElementState::Error,
+                    e.error.as_ref().map(|error| error.message.clone()),
+                ),
+                &e.output_connections,
+                ElementState::Error,
+            ),
+            ZipExecutionEvent::NodeWarning(e) => (
+                VisualStateElement::node(
+                    &e.node_id,
+                    ElementState::Warning,
+                    e.warning.as_ref().map(|warning| warning.message.clone()),
+                ),
+                &e.output_connections,
+                ElementState::Warning,
+            ),
+            ZipExecutionEvent::ExecutionStarted(_)
+            | ZipExecutionEvent::ExecutionCompleted(_)
+            | ZipExecutionEvent::ExecutionFailed(_) => {
+                return Self {
+                    elements: Vec::new(),
+                }
+            }
+        };
+
+        let mut elements = Vec::with_capacity(connections.len() + 1);
+        elements.push(node);
+        elements.extend(
+            connections
+                .iter()
+                .map(|id| VisualStateElement::connection(id, state.clone())),
+        );
+        Self { elements }
+    }
+}
+
+/// What failed: a whole execution or a single node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureScope {
+    Execution { session_id: String },
+    Node { node_id: String },
+}
+
+/// Summary of an `execution.failed` or `node.failed` event, for alerting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionFailure {
+    pub workflow_id: String,
+    pub graph_id: Option<String>,
+    pub scope: FailureScope,
+    /// Error message, if the event carried one
+    pub message: Option<String>,
+    pub code: Option<String>,
+}
+
+impl ExecutionFailure {
+    /// Summarize a failure event, or `None` for any other execution event
+    pub fn from_execution_event(event: &ZipExecutionEvent) -> Option<Self> {
+        let (base, scope, message, code) = match event {
+            ZipExecutionEvent::ExecutionFailed(e) => (
+                &e.base,
+                FailureScope::Execution {
+                    session_id: e.session_id.clone(),
+                },
+                e.error.as_ref().map(|error| error.message.clone()),
+                e.error.as_ref().and_then(|error| error.code.clone()),
+            ),
+            ZipExecutionEvent::NodeFailed(e) => (
+                &e.base,
+                FailureScope::Node {
+                    node_id: e.node_id.clone(),
+                },
+                e.error.as_ref().map(|error| error.message.clone()),
+                e.error.as_ref().and_then(|error| error.code.clone()),
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            workflow_id: base.workflow_id.clone(),
+            graph_id: base.graph_id.clone(),
+            scope,
+            message,
+            code,
+        })
+    }
+}
+
+impl From<&ZipExecutionEvent> for VisualStateUpdate {
+    fn from(event: &ZipExecutionEvent) -> Self {
+        Self::from_execution_event(event)
+    }
+}
+
 /// Union types for all event categories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -694,13 +929,50 @@ pub enum ZipCRDTEvent {
     TraceEvent(TraceEventData),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Control messages share a shape (e.g. `subscribed` and `subscribe` both
+/// carry just a workflow ID), so they are deserialized by their `"type"`
+/// field rather than by trying each variant in turn.
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum ZipControlEvent {
     Subscribe(SubscribeEvent),
     Unsubscribe(UnsubscribeEvent),
     Ping(PingEvent),
     Pong(PongEvent),
+    Subscribed(SubscribedEvent),
+    SubscribeError(SubscribeErrorEvent),
+}
+
+impl<'de> Deserialize<'de> for ZipControlEvent {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde_json::from_value;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let event_type = value
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let event = match event_type.as_str() {
+            "subscribe" => from_value(value).map(Self::Subscribe),
+            "unsubscribe" => from_value(value).map(Self::Unsubscribe),
+            "ping" => from_value(value).map(Self::Ping),
+            "pong" => from_value(value).map(Self::Pong),
+            "subscribed" => from_value(value).map(Self::Subscribed),
+            "subscribe.error" => from_value(value).map(Self::SubscribeError),
+            other => {
+                return Err(D::Error::custom(format!(
+                    "unknown control event type \"{}\"",
+                    other
+                )))
+            }
+        };
+        event.map_err(D::Error::custom)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -723,6 +995,75 @@ pub enum ZipWebhookEvent {
     Stream(ZipStreamEvent),
 }
 
+impl ZipWebSocketEvent {
+    /// Deserialize an event by dispatching on its `"type"` field.
+    ///
+    /// Unlike deserializing through the untagged enum, which takes the first
+    /// variant whose required fields happen to be present, this picks the
+    /// struct for the event's declared type and fails with a clear error for
+    /// unknown types or events missing fields their type requires.
+    pub fn from_json(value: serde_json::Value) -> crate::errors::Result<Self> {
+        use serde_json::from_value;
+
+        let Some(event_type) = value.get("type").and_then(|t| t.as_str()) else {
+            return Err(crate::errors::ZealError::validation_error(
+                "type".to_string(),
+                "Event has no \"type\" field".to_string(),
+            ));
+        };
+
+        let event = match event_type {
+            "node.executing" => {
+                Self::Execution(ZipExecutionEvent::NodeExecuting(from_value(value)?))
+            }
+            "node.completed" => {
+                Self::Execution(ZipExecutionEvent::NodeCompleted(from_value(value)?))
+            }
+            "node.failed" => Self::Execution(ZipExecutionEvent::NodeFailed(from_value(value)?)),
+            "node.warning" => Self::Execution(ZipExecutionEvent::NodeWarning(from_value(value)?)),
+            "execution.started" => {
+                Self::Execution(ZipExecutionEvent::ExecutionStarted(from_value(value)?))
+            }
+            "execution.completed" => {
+                Self::Execution(ZipExecutionEvent::ExecutionCompleted(from_value(value)?))
+            }
+            "execution.failed" => {
+                Self::Execution(ZipExecutionEvent::ExecutionFailed(from_value(value)?))
+            }
+            "subscribe" => Self::Control(ZipControlEvent::Subscribe(from_value(value)?)),
+            "unsubscribe" => Self::Control(ZipControlEvent::Unsubscribe(from_value(value)?)),
+            "ping" => Self::Control(ZipControlEvent::Ping(from_value(value)?)),
+            "pong" => Self::Control(ZipControlEvent::Pong(from_value(value)?)),
+            "subscribed" => Self::Control(ZipControlEvent::Subscribed(from_value(value)?)),
+            "subscribe.error" => Self::Control(ZipControlEvent::SubscribeError(from_value(value)?)),
+            "workflow.updated" => Self::WorkflowUpdated(from_value(value)?),
+            "connection.state" => Self::ConnectionState(from_value(value)?),
+            "node.added" => Self::CRDT(ZipCRDTEvent::NodeAdded(from_value(value)?)),
+            "node.updated" => Self::CRDT(ZipCRDTEvent::NodeUpdated(from_value(value)?)),
+            "node.deleted" => Self::CRDT(ZipCRDTEvent::NodeDeleted(from_value(value)?)),
+            "connection.added" => Self::CRDT(ZipCRDTEvent::ConnectionAdded(from_value(value)?)),
+            "connection.deleted" => Self::CRDT(ZipCRDTEvent::ConnectionDeleted(from_value(value)?)),
+            "group.created" => Self::CRDT(ZipCRDTEvent::GroupCreated(from_value(value)?)),
+            "group.updated" => Self::CRDT(ZipCRDTEvent::GroupUpdated(from_value(value)?)),
+            "group.deleted" => Self::CRDT(ZipCRDTEvent::GroupDeleted(from_value(value)?)),
+            "template.registered" => {
+                Self::CRDT(ZipCRDTEvent::TemplateRegistered(from_value(value)?))
+            }
+            "trace.event" => Self::CRDT(ZipCRDTEvent::TraceEvent(from_value(value)?)),
+            "stream.opened" => Self::Stream(ZipStreamEvent::StreamOpened(from_value(value)?)),
+            "stream.closed" => Self::Stream(ZipStreamEvent::StreamClosed(from_value(value)?)),
+            "stream.error" => Self::Stream(ZipStreamEvent::StreamError(from_value(value)?)),
+            other => {
+                return Err(crate::errors::ZealError::validation_error(
+                    "type".to_string(),
+                    format!("Unknown event type '{}'", other),
+                ))
+            }
+        };
+        Ok(event)
+    }
+}
+
 /// Type guards
 impl ZipExecutionEvent {
     pub fn event_type(&self) -> &str {
@@ -749,6 +1090,18 @@ impl ZipExecutionEvent {
         }
     }
 
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Self::NodeExecuting(e) => Some(&e.node_id),
+            Self::NodeCompleted(e) => Some(&e.node_id),
+            Self::NodeFailed(e) => Some(&e.node_id),
+            Self::NodeWarning(e) => Some(&e.node_id),
+            Self::ExecutionStarted(_) | Self::ExecutionCompleted(_) | Self::ExecutionFailed(_) => {
+                None
+            }
+        }
+    }
+
     pub fn is_node_event(&self) -> bool {
         self.event_type().starts_with("node.")
     }
@@ -811,6 +1164,16 @@ impl ZipCRDTEvent {
         }
     }
 
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Self::NodeAdded(e) => Some(&e.node_id),
+            Self::NodeUpdated(e) => Some(&e.node_id),
+            Self::NodeDeleted(e) => Some(&e.node_id),
+            Self::TraceEvent(e) => Some(&e.node_id),
+            _ => None,
+        }
+    }
+
     pub fn is_node_event(&self) -> bool {
         matches!(
             self,
@@ -838,6 +1201,77 @@ impl ZipCRDTEvent {
     }
 }
 
+impl ZipWebhookEvent {
+    /// Deserialize a webhook event by dispatching on its `"type"` field, with
+    /// the same errors for unknown types and missing fields as
+    /// [`ZipWebSocketEvent::from_json`].
+    pub fn from_json(value: serde_json::Value) -> crate::errors::Result<Self> {
+        use serde_json::from_value;
+
+        let event_type = value
+            .get("type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let event = match event_type.as_str() {
+            "workflow.created" => {
+                Self::Workflow(ZipWorkflowEvent::WorkflowCreated(from_value(value)?))
+            }
+            "workflow.updated" => {
+                Self::Workflow(ZipWorkflowEvent::WorkflowUpdated(from_value(value)?))
+            }
+            "workflow.deleted" => {
+                Self::Workflow(ZipWorkflowEvent::WorkflowDeleted(from_value(value)?))
+            }
+            "workflow.published" => {
+                Self::Workflow(ZipWorkflowEvent::WorkflowPublished(from_value(value)?))
+            }
+            "workflow.unpublished" => {
+                Self::Workflow(ZipWorkflowEvent::WorkflowUnpublished(from_value(value)?))
+            }
+            _ => match ZipWebSocketEvent::from_json(value)? {
+                ZipWebSocketEvent::Execution(event) => Self::Execution(event),
+                ZipWebSocketEvent::CRDT(event) => Self::CRDT(event),
+                ZipWebSocketEvent::Stream(event) => Self::Stream(event),
+                _ => {
+                    return Err(crate::errors::ZealError::validation_error(
+                        "type".to_string(),
+                        format!("Unknown event type '{}'", event_type),
+                    ))
+                }
+            },
+        };
+        Ok(event)
+    }
+
+    pub fn event_type(&self) -> &str {
+        match self {
+            Self::Execution(e) => e.event_type(),
+            Self::Workflow(e) => e.event_type(),
+            Self::CRDT(e) => e.event_type(),
+            Self::Stream(e) => e.event_type(),
+        }
+    }
+
+    pub fn workflow_id(&self) -> &str {
+        match self {
+            Self::Execution(e) => e.workflow_id(),
+            Self::Workflow(e) => e.workflow_id(),
+            Self::CRDT(e) => e.workflow_id(),
+            Self::Stream(e) => e.workflow_id(),
+        }
+    }
+
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Self::Execution(e) => e.node_id(),
+            Self::Workflow(_) => None,
+            Self::CRDT(e) => e.node_id(),
+            Self::Stream(e) => Some(e.node_id()),
+        }
+    }
+}
+
 pub fn is_execution_event(event_type: &str) -> bool {
     event_type.starts_with("node.") || event_type.starts_with("execution.")
 }
@@ -847,7 +1281,10 @@ pub fn is_workflow_event(event_type: &str) -> bool {
 }
 
 pub fn is_control_event(event_type: &str) -> bool {
-    matches!(event_type, "subscribe" | "unsubscribe" | "ping" | "pong")
+    matches!(
+        event_type,
+        "subscribe" | "unsubscribe" | "subscribed" | "subscribe.error" | "ping" | "pong"
+    )
 }
 
 pub fn is_node_event(event_type: &str) -> bool {
@@ -952,6 +1389,28 @@ pub fn create_node_failed_event(
     }
 }
 
+pub fn create_node_warning_event(
+    workflow_id: &str,
+    node_id: &str,
+    output_connections: Vec<String>,
+    warning: Option<NodeWarning>,
+    graph_id: Option<String>,
+) -> NodeWarningEvent {
+    NodeWarningEvent {
+        base: ZipEventBase {
+            id: generate_event_id(),
+            timestamp: current_timestamp(),
+            workflow_id: workflow_id.to_string(),
+            graph_id,
+            metadata: None,
+        },
+        event_type: "node.warning".to_string(),
+        node_id: node_id.to_string(),
+        output_connections,
+        warning,
+    }
+}
+
 pub fn create_execution_started_event(
     workflow_id: &str,
     session_id: &str,
@@ -1020,6 +1479,62 @@ pub fn create_execution_failed_event(
     }
 }
 
+/// Create a `workflow.created` event
+pub fn create_workflow_created_event(
+    workflow_id: &str,
+    workflow_name: &str,
+    user_id: Option<String>,
+) -> WorkflowCreatedEvent {
+    WorkflowCreatedEvent {
+        base: ZipEventBase {
+            id: generate_event_id(),
+            timestamp: current_timestamp(),
+            workflow_id: workflow_id.to_string(),
+            graph_id: None,
+            metadata: None,
+        },
+        event_type: "workflow.created".to_string(),
+        workflow_name: workflow_name.to_string(),
+        user_id,
+    }
+}
+
+/// Create a `workflow.updated` event
+pub fn create_workflow_updated_event(
+    workflow_id: &str,
+    data: Option<WorkflowUpdateData>,
+) -> WorkflowUpdatedEvent {
+    WorkflowUpdatedEvent {
+        base: ZipEventBase {
+            id: generate_event_id(),
+            timestamp: current_timestamp(),
+            workflow_id: workflow_id.to_string(),
+            graph_id: None,
+            metadata: None,
+        },
+        event_type: "workflow.updated".to_string(),
+        data,
+    }
+}
+
+/// Create a `workflow.deleted` event
+pub fn create_workflow_deleted_event(
+    workflow_id: &str,
+    workflow_name: Option<String>,
+) -> WorkflowDeletedEvent {
+    WorkflowDeletedEvent {
+        base: ZipEventBase {
+            id: generate_event_id(),
+            timestamp: current_timestamp(),
+            workflow_id: workflow_id.to_string(),
+            graph_id: None,
+            metadata: None,
+        },
+        event_type: "workflow.deleted".to_string(),
+        workflow_name,
+    }
+}
+
 /// CRDT Event creation helpers
 pub fn create_node_added_event(
     workflow_id: &str,
@@ -1340,6 +1855,152 @@ fn current_timestamp() -> String {
 mod tests {
     use super::*;
 
+    fn states(update: &VisualStateUpdate) -> Vec<(&str, &ElementType, &ElementState)> {
+        update
+            .elements
+            .iter()
+            .map(|e| (e.id.as_str(), &e.element_type, &e.state))
+            .collect()
+    }
+
+    #[test]
+    fn test_visual_state_from_node_executing() {
+        let event = ZipExecutionEvent::NodeExecuting(create_node_executing_event(
+            "wf-1",
+            "node-1",
+            vec!["conn-in".to_string()],
+            None,
+        ));
+        let update = VisualStateUpdate::from_execution_event(&event);
+        assert_eq!(
+            states(&update),
+            vec![
+                ("node-1", &ElementType::Node, &ElementState::Running),
+                ("conn-in", &ElementType::Connection, &ElementState::Running),
+            ]
+        );
+        assert_eq!(update.elements[0].progress, None);
+    }
+
+    #[test]
+    fn test_visual_state_from_node_completed() {
+        let event = ZipExecutionEvent::NodeCompleted(create_node_completed_event(
+            "wf-1",
+            "node-1",
+            vec!["conn-a".to_string(), "conn-b".to_string()],
+            None,
+        ));
+        let update = VisualStateUpdate::from(&event);
+        assert_eq!(
+            states(&update),
+            vec![
+                ("node-1", &ElementType::Node, &ElementState::Success),
+                ("conn-a", &ElementType::Connection, &ElementState::Success),
+                ("conn-b", &ElementType::Connection, &ElementState::Success),
+            ]
+        );
+        assert_eq!(update.elements[0].progress, Some(100.0));
+        assert_eq!(update.elements[1].progress, None);
+    }
+
+    #[test]
+    fn test_visual_state_from_node_failed_and_warning() {
+        let failed = ZipExecutionEvent::NodeFailed(create_node_failed_event(
+            "wf-1",
+            "node-1",
+            vec!["conn-out".to_string()],
+            Some(NodeError {
+                message: "boom".to_string(),
+                code: None,
+                stack: None,
+            }),
+            None,
+        ));
+        let update = VisualStateUpdate::from_execution_event(&failed);
+        assert_eq!(
+            states(&update),
+            vec![
+                ("node-1", &ElementType::Node, &ElementState::Error),
+                ("conn-out", &ElementType::Connection, &ElementState::Error),
+            ]
+        );
+        assert_eq!(update.elements[0].message.as_deref(), Some("boom"));
+
+        let warning = ZipExecutionEvent::NodeWarning(create_node_warning_event(
+            "wf-1",
+            "node-2",
+            vec![],
+            Some(NodeWarning {
+                message: "slow".to_string(),
+                code: None,
+            }),
+            None,
+        ));
+        let update = VisualStateUpdate::from_execution_event(&warning);
+        assert_eq!(
+            states(&update),
+            vec![("node-2", &ElementType::Node, &ElementState::Warning)]
+        );
+        assert_eq!(update.elements[0].message.as_deref(), Some("slow"));
+    }
+
+    #[test]
+    fn test_execution_failure_from_failure_events_only() {
+        let node_failed = ZipExecutionEvent::NodeFailed(create_node_failed_event(
+            "wf-1",
+            "node-1",
+            vec![],
+            Some(NodeError {
+                message: "boom".to_string(),
+                code: Some("E_NODE".to_string()),
+                stack: None,
+            }),
+            Some("main".to_string()),
+        ));
+        assert_eq!(
+            ExecutionFailure::from_execution_event(&node_failed),
+            Some(ExecutionFailure {
+                workflow_id: "wf-1".to_string(),
+                graph_id: Some("main".to_string()),
+                scope: FailureScope::Node {
+                    node_id: "node-1".to_string()
+                },
+                message: Some("boom".to_string()),
+                code: Some("E_NODE".to_string()),
+            })
+        );
+
+        let execution_failed = ZipExecutionEvent::ExecutionFailed(create_execution_failed_event(
+            "wf-2", "exec-1", None, None,
+        ));
+        let failure = ExecutionFailure::from_execution_event(&execution_failed).unwrap();
+        assert_eq!(
+            failure.scope,
+            FailureScope::Execution {
+                session_id: "exec-1".to_string()
+            }
+        );
+        assert_eq!(failure.message, None);
+
+        let completed = ZipExecutionEvent::NodeCompleted(create_node_completed_event(
+            "wf-1",
+            "node-1",
+            vec![],
+            None,
+        ));
+        assert_eq!(ExecutionFailure::from_execution_event(&completed), None);
+    }
+
+    #[test]
+    fn test_visual_state_from_execution_level_event_is_empty() {
+        let event = ZipExecutionEvent::ExecutionCompleted(create_execution_completed_event(
+            "wf-1", "exec-1", 10, 3, None,
+        ));
+        assert!(VisualStateUpdate::from_execution_event(&event)
+            .elements
+            .is_empty());
+    }
+
     #[test]
     fn test_node_executing_event() {
         let event = create_node_executing_event(
@@ -1356,6 +2017,129 @@ mod tests {
         assert_eq!(event.base.graph_id, Some("main".to_string()));
     }
 
+    #[test]
+    fn test_execution_started_to_trace_session_request() {
+        let event: ExecutionStartedEvent = serde_json::from_value(serde_json::json!({
+            "id": "evt_1",
+            "timestamp": "2024-05-01T12:00:00.000Z",
+            "workflowId": "wf-1",
+            "graphId": "main",
+            "type": "execution.started",
+            "sessionId": "exec-42",
+            "workflowName": "Nightly import",
+            "trigger": { "type": "webhook", "source": "github" },
+            "metadata": {
+                "workflowVersionId": "v7",
+                "environment": "production",
+                "tags": ["import", 3, "nightly"],
+                "attempt": 2
+            }
+        }))
+        .unwrap();
+
+        let request = event.to_trace_session_request();
+        assert_eq!(request.workflow_id, "wf-1");
+        assert_eq!(request.execution_id, "exec-42");
+        assert_eq!(request.workflow_version_id.as_deref(), Some("v7"));
+        let metadata = request.metadata.unwrap();
+        assert_eq!(metadata.trigger.as_deref(), Some("webhook"));
+        assert_eq!(metadata.environment.as_deref(), Some("production"));
+        assert_eq!(
+            metadata.tags,
+            vec!["import", "nightly", "trigger-source:github"]
+        );
+
+        let bare = create_execution_started_event("wf-2", "exec-1", "Bare", None);
+        let request = bare.to_trace_session_request();
+        assert_eq!(request.execution_id, "exec-1");
+        assert!(request.workflow_version_id.is_none());
+        let metadata = request.metadata.unwrap();
+        assert!(metadata.trigger.is_none() && metadata.environment.is_none());
+        assert!(metadata.tags.is_empty());
+    }
+
+    #[test]
+    fn test_websocket_event_from_json_dispatches_on_type() {
+        let base = |event_type: &str| {
+            serde_json::json!({
+                "id": "evt_1",
+                "timestamp": "2024-05-01T12:00:00.000Z",
+                "workflowId": "wf-1",
+                "type": event_type
+            })
+        };
+
+        // Only has base fields beyond its own, so untagged parsing could
+        // mistake it for a workflow update
+        let mut added = base("node.added");
+        added["nodeId"] = "node-1".into();
+        added["data"] = serde_json::json!({});
+        let event = ZipWebSocketEvent::from_json(added).unwrap();
+        assert!(matches!(
+            event,
+            ZipWebSocketEvent::CRDT(ZipCRDTEvent::NodeAdded(ref e)) if e.node_id == "node-1"
+        ));
+
+        let mut completed = base("node.completed");
+        completed["nodeId"] = "node-2".into();
+        completed["outputConnections"] = serde_json::json!([]);
+        assert!(matches!(
+            ZipWebSocketEvent::from_json(completed).unwrap(),
+            ZipWebSocketEvent::Execution(ZipExecutionEvent::NodeCompleted(_))
+        ));
+
+        assert!(matches!(
+            ZipWebSocketEvent::from_json(base("workflow.updated")).unwrap(),
+            ZipWebSocketEvent::WorkflowUpdated(_)
+        ));
+
+        // Missing a field its declared type requires
+        assert!(ZipWebSocketEvent::from_json(base("node.executing")).is_err());
+
+        let err = ZipWebSocketEvent::from_json(base("node.teleported")).unwrap_err();
+        assert!(err.to_string().contains("node.teleported"));
+        assert!(ZipWebSocketEvent::from_json(serde_json::json!({ "id": "x" })).is_err());
+    }
+
+    #[test]
+    fn test_control_event_deserializes_by_type() {
+        let subscribed: ZipControlEvent = serde_json::from_value(serde_json::json!({
+            "type": "subscribed",
+            "workflowId": "wf-1"
+        }))
+        .unwrap();
+        assert!(matches!(
+            subscribed,
+            ZipControlEvent::Subscribed(ref e) if e.workflow_id == "wf-1"
+        ));
+
+        let rejected: ZipWebSocketEvent = serde_json::from_value(serde_json::json!({
+            "type": "subscribe.error",
+            "workflowId": "wf-1",
+            "error": "Workflow not found"
+        }))
+        .unwrap();
+        assert!(matches!(
+            rejected,
+            ZipWebSocketEvent::Control(ZipControlEvent::SubscribeError(ref e))
+                if e.error == "Workflow not found"
+        ));
+
+        assert!(
+            serde_json::from_value::<ZipControlEvent>(serde_json::json!({
+                "type": "subscribed"
+            }))
+            .is_err()
+        );
+        assert!(
+            serde_json::from_value::<ZipControlEvent>(serde_json::json!({
+                "type": "node.added",
+                "workflowId": "wf-1"
+            }))
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_event_serialization() {
         let event = create_node_completed_event(
@@ -1378,6 +2162,74 @@ mod tests {
         assert_eq!(deserialized.duration, Some(150));
     }
 
+    #[test]
+    fn test_node_warning_event_serialization() {
+        let event = create_node_warning_event(
+            "workflow-123",
+            "node-456",
+            vec!["conn-out".to_string()],
+            Some(NodeWarning {
+                message: "Response truncated".to_string(),
+                code: Some("TRUNCATED".to_string()),
+            }),
+            None,
+        );
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains(r#""type":"node.warning""#));
+
+        let parsed = ZipWebSocketEvent::from_json(serde_json::to_value(&event).unwrap()).unwrap();
+        assert!(matches!(
+            parsed,
+            ZipWebSocketEvent::Execution(ZipExecutionEvent::NodeWarning(ref e))
+                if e.warning.as_ref().unwrap().code.as_deref() == Some("TRUNCATED")
+        ));
+    }
+
+    #[test]
+    fn test_workflow_lifecycle_event_creation() {
+        let created =
+            create_workflow_created_event("workflow-123", "Orders", Some("user-1".to_string()));
+        assert_eq!(created.event_type, "workflow.created");
+        assert_eq!(created.workflow_name, "Orders");
+        let json = serde_json::to_value(&created).unwrap();
+        assert_eq!(json["userId"], "user-1");
+        assert!(
+            create_workflow_created_event("workflow-123", "Orders", None)
+                .user_id
+                .is_none()
+        );
+
+        let updated = create_workflow_updated_event(
+            "workflow-123",
+            Some(WorkflowUpdateData {
+                version: Some(2),
+                graphs: None,
+                changes: Some(WorkflowChanges {
+                    nodes: Some(true),
+                    connections: None,
+                    properties: None,
+                }),
+                metadata: None,
+            }),
+        );
+        let json = serde_json::to_value(&updated).unwrap();
+        assert_eq!(json["type"], "workflow.updated");
+        assert_eq!(json["data"]["changes"]["nodes"], true);
+
+        let deleted = create_workflow_deleted_event("workflow-123", None);
+        let json = serde_json::to_value(&deleted).unwrap();
+        assert_eq!(json["type"], "workflow.deleted");
+        assert!(json.get("workflowName").is_none());
+        assert_eq!(
+            create_workflow_deleted_event("workflow-123", Some("Orders".to_string()))
+                .workflow_name
+                .as_deref(),
+            Some("Orders")
+        );
+        assert_ne!(created.base.id, deleted.base.id);
+    }
+
     #[test]
     fn test_zip_execution_event_methods() {
         let event = ZipExecutionEvent::NodeExecuting(create_node_executing_event(
@@ -0,0 +1,249 @@
+//! Streaming NDJSON export and import of workflow graphs
+//!
+//! An export is one JSON record per line, tagged by its `record` field: a
+//! single `header` line followed by `node`, `connection` and `group` lines.
+//! Records are written and read one at a time, so a large graph never has
+//! to be serialized into (or parsed from) a single buffer. Writing borrows
+//! from the [`WorkflowState`] rather than copying each element, but the
+//! state itself is held in memory: the API returns it as one JSON document.
+
+use crate::errors::{Result, ZealError};
+use crate::orchestrator::{WorkflowConnection, WorkflowGroup, WorkflowNode, WorkflowState};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+/// One line of a workflow NDJSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "lowercase")]
+pub enum WorkflowRecord {
+    /// Workflow-level fields, always the first record
+    Header(WorkflowHeader),
+    Node(WorkflowNode),
+    Connection(WorkflowConnection),
+    Group(WorkflowGroup),
+}
+
+/// Borrowed counterpart of [`WorkflowRecord`] for writing, so elements are
+/// serialized straight from the state instead of being cloned first
+#[derive(Serialize)]
+#[serde(tag = "record", rename_all = "lowercase")]
+enum WorkflowRecordRef<'a> {
+    Header(&'a WorkflowHeader),
+    Node(&'a WorkflowNode),
+    Connection(&'a WorkflowConnection),
+    Group(&'a WorkflowGroup),
+}
+
+/// Workflow fields other than the graph contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowHeader {
+    #[serde(rename = "workflowId")]
+    pub workflow_id: String,
+    #[serde(rename = "graphId")]
+    pub graph_id: String,
+    pub name: String,
+    pub description: String,
+    pub version: u32,
+    pub metadata: serde_json::Value,
+}
+
+impl WorkflowHeader {
+    fn from_state(state: &WorkflowState) -> Self {
+        Self {
+            workflow_id: state.workflow_id.clone(),
+            graph_id: state.graph_id.clone(),
+            name: state.name.clone(),
+            description: state.description.clone(),
+            version: state.version,
+            metadata: state.metadata.clone(),
+        }
+    }
+}
+
+/// Write `state` to `writer` as NDJSON, returning the number of records written
+pub async fn write_workflow_ndjson<W: AsyncWrite + Unpin>(
+    state: &WorkflowState,
+    writer: &mut W,
+) -> Result<usize> {
+    let mut line = Vec::new();
+    let header = WorkflowHeader::from_state(state);
+    write_record(writer, &mut line, &WorkflowRecordRef::Header(&header)).await?;
+    let mut count = 1;
+
+    let graph = &state.state;
+    for node in &graph.nodes {
+        write_record(writer, &mut line, &WorkflowRecordRef::Node(node)).await?;
+        count += 1;
+    }
+    for connection in &graph.connections {
+        write_record(
+            writer,
+            &mut line,
+            &WorkflowRecordRef::Connection(connection),
+        )
+        .await?;
+        count += 1;
+    }
+    for group in &graph.groups {
+        write_record(writer, &mut line, &WorkflowRecordRef::Group(group)).await?;
+        count += 1;
+    }
+
+    writer.flush().await?;
+    Ok(count)
+}
+
+/// Serialize one record into the reused `line` buffer and write it out
+async fn write_record<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    line: &mut Vec<u8>,
+    record: &WorkflowRecordRef<'_>,
+) -> Result<()> {
+    line.clear();
+    serde_json::to_writer(&mut *line, record)?;
+    line.push(b'\n');
+    writer.write_all(line).await?;
+    Ok(())
+}
+
+/// Read workflow records from NDJSON one line at a time. Blank lines are
+/// skipped; a malformed line yields an error naming its line number and the
+/// stream continues with the next one.
+pub fn read_workflow_ndjson<R: AsyncBufRead + Unpin>(
+    reader: R,
+) -> impl Stream<Item = Result<WorkflowRecord>> {
+    futures_util::stream::unfold(
+        (reader.lines(), 0usize),
+        |(mut lines, mut line_number)| async move {
+            loop {
+                line_number += 1;
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => return None,
+                    Err(err) => return Some((Err(err.into()), (lines, line_number))),
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let record = serde_json::from_str::<WorkflowRecord>(&line).map_err(|err| {
+                    ZealError::validation_error(
+                        format!("line {}", line_number),
+                        format!("Invalid workflow record: {}", err),
+                    )
+                });
+                return Some((record, (lines, line_number)));
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orchestrator::WorkflowStateData;
+    use crate::types::{NodePort, Position};
+    use futures_util::StreamExt;
+    use std::collections::HashMap;
+
+    fn large_workflow(nodes: usize) -> WorkflowState {
+        let port = |node: usize, port: &str| NodePort {
+            node_id: format!("node-{}", node),
+            port_id: port.to_string(),
+        };
+
+        WorkflowState {
+            workflow_id: "wf-1".to_string(),
+            graph_id: "main".to_string(),
+            name: "Migration".to_string(),
+            description: "Synthetic".to_string(),
+            version: 3,
+            state: WorkflowStateData {
+                nodes: (0..nodes)
+                    .map(|i| WorkflowNode {
+                        id: format!("node-{}", i),
+                        node_type: "processor".to_string(),
+                        position: Position {
+                            x: i as f64,
+                            y: 0.0,
+                        },
+                        properties: HashMap::from([("index".to_string(), serde_json::json!(i))]),
+                        extra: HashMap::from([(
+                            "metadata".to_string(),
+                            serde_json::json!({ "title": format!("Node {}", i) }),
+                        )]),
+                    })
+                    .collect(),
+                connections: (1..nodes)
+                    .map(|i| WorkflowConnection {
+                        id: format!("conn-{}", i),
+                        source: port(i - 1, "out"),
+                        target: port(i, "in"),
+                        extra: HashMap::new(),
+                    })
+                    .collect(),
+                groups: vec![WorkflowGroup {
+                    id: "group-1".to_string(),
+                    title: "First ten".to_string(),
+                    node_ids: (0..10).map(|i| format!("node-{}", i)).collect(),
+                    extra: HashMap::new(),
+                }],
+            },
+            metadata: serde_json::json!({ "tags": ["big"] }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_round_trip() {
+        let state = large_workflow(2_000);
+        let mut buffer = Vec::new();
+        let written = write_workflow_ndjson(&state, &mut buffer).await.unwrap();
+        assert_eq!(written, 1 + 2_000 + 1_999 + 1);
+        assert_eq!(buffer.iter().filter(|&&b| b == b'\n').count(), written);
+
+        let records: Vec<_> = read_workflow_ndjson(buffer.as_slice())
+            .map(|record| record.unwrap())
+            .collect()
+            .await;
+        assert_eq!(records.len(), written);
+
+        let WorkflowRecord::Header(header) = &records[0] else {
+            panic!("first record is not the header");
+        };
+        assert_eq!(header.workflow_id, "wf-1");
+        assert_eq!(header.version, 3);
+
+        let mut imported = WorkflowStateData {
+            nodes: Vec::new(),
+            connections: Vec::new(),
+            groups: Vec::new(),
+        };
+        for record in records.into_iter().skip(1) {
+            match record {
+                WorkflowRecord::Node(node) => imported.nodes.push(node),
+                WorkflowRecord::Connection(connection) => imported.connections.push(connection),
+                WorkflowRecord::Group(group) => imported.groups.push(group),
+                WorkflowRecord::Header(_) => panic!("unexpected second header"),
+            }
+        }
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&state.state).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_reports_malformed_lines() {
+        let input = b"{\"record\":\"group\",\"id\":\"g\"}\n\nnot json\n{\"record\":\"unknown\"}\n";
+        let results: Vec<_> = read_workflow_ndjson(&input[..]).collect().await;
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], Ok(WorkflowRecord::Group(_))));
+        assert!(matches!(
+            &results[1],
+            Err(ZealError::ValidationError { field, .. }) if field == "line 3"
+        ));
+        assert!(results[2].is_err());
+    }
+}
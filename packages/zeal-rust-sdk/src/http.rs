@@ -0,0 +1,570 @@
+//! HTTP transport shared by the API modules
+
+use crate::auth::SharedTokenProvider;
+use crate::cassette::CassetteRecorder;
+use crate::config::{PerformanceConfig, RecordReplayConfig, RetryConfig};
+use crate::errors::{Result, ZealError};
+use crate::retry::{retry_with_budget_if, should_retry, RetryBudget};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::warn;
+
+/// Client builder with the connection pool settings from `performance` applied
+pub(crate) fn pooled_client_builder(performance: &PerformanceConfig) -> reqwest::ClientBuilder {
+    let max_idle = if performance.connection_pooling {
+        performance.max_idle_connections
+    } else {
+        0
+    };
+    reqwest::Client::builder()
+        .connect_timeout(performance.connection_timeout)
+        .pool_idle_timeout(performance.idle_timeout)
+        .pool_max_idle_per_host(max_idle)
+        .tcp_keepalive(performance.tcp_keepalive)
+}
+
+/// Client with the default pool settings, used by the standalone API
+/// constructors. Each call builds a new client: a pool shared across the
+/// process would hand connections opened on one tokio runtime to another,
+/// and they fail once the runtime that opened them shuts down.
+fn default_client() -> reqwest::Client {
+    pooled_client_builder(&PerformanceConfig::default())
+        .user_agent(format!("zeal-rust-sdk/{}", crate::VERSION))
+        .build()
+        .unwrap_or_else(|e| {
+            warn!(
+                "Failed to build HTTP client with pool settings, using reqwest defaults: {}",
+                e
+            );
+            reqwest::Client::new()
+        })
+}
+
+/// Thin wrapper around `reqwest::Client` that routes every request through a
+/// single execution path (used for record/replay and retries)
+#[derive(Debug, Clone)]
+pub(crate) struct HttpClient {
+    client: reqwest::Client,
+    recorder: Option<Arc<CassetteRecorder>>,
+    retry: RetryConfig,
+    retry_budget: Option<Arc<RetryBudget>>,
+    token_provider: Option<SharedTokenProvider>,
+    concurrency: Option<Arc<Semaphore>>,
+    pool_acquire_timeout: Option<Duration>,
+}
+
+impl HttpClient {
+    pub(crate) fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            recorder: None,
+            retry: RetryConfig::default(),
+            retry_budget: None,
+            token_provider: None,
+            concurrency: None,
+            pool_acquire_timeout: None,
+        }
+    }
+
+    /// Wrap a new client with the default pool settings
+    pub(crate) fn standalone() -> Self {
+        Self::new(default_client())
+            .with_pool_acquire_timeout(PerformanceConfig::default().pool_acquire_timeout)
+    }
+
+    /// Wrap a client, enabling record/replay if configured
+    pub(crate) fn with_record_replay(
+        client: reqwest::Client,
+        record_replay: Option<&RecordReplayConfig>,
+    ) -> Result<Self> {
+        let recorder = match record_replay {
+            Some(config) => Some(Arc::new(CassetteRecorder::new(
+                config.mode,
+                config.cassette_path.clone(),
+            )?)),
+            None => None,
+        };
+
+        Ok(Self {
+            client,
+            recorder,
+            retry: RetryConfig::default(),
+            retry_budget: None,
+            token_provider: None,
+            concurrency: None,
+            pool_acquire_timeout: None,
+        })
+    }
+
+    /// Use the given retry policy for every request. Its retry budget, if
+    /// any, is shared by every clone of this client.
+    pub(crate) fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry_budget = retry
+            .budget
+            .as_ref()
+            .map(|budget| Arc::new(RetryBudget::new(budget)));
+        self.retry = retry;
+        self
+    }
+
+    /// Send a bearer token from the provider with every request
+    pub(crate) fn with_token_provider(mut self, provider: Option<SharedTokenProvider>) -> Self {
+        self.token_provider = provider;
+        self
+    }
+
+    /// Allow at most `max` requests in flight at once across every clone of
+    /// this client; further requests wait for a permit
+    pub(crate) fn with_max_concurrent_requests(mut self, max: Option<usize>) -> Self {
+        self.concurrency = max.map(|max| Arc::new(Semaphore::new(max)));
+        self
+    }
+
+    /// Fail request attempts that take longer than `timeout` to get a
+    /// connection and response headers, including any wait for a permit
+    pub(crate) fn with_pool_acquire_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_acquire_timeout = timeout;
+        self
+    }
+
+    pub(crate) fn get(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.get(url))
+    }
+
+    pub(crate) fn post(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.post(url))
+    }
+
+    pub(crate) fn put(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.put(url))
+    }
+
+    pub(crate) fn patch(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.patch(url))
+    }
+
+    pub(crate) fn delete(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.delete(url))
+    }
+
+    pub(crate) fn head(&self, url: &str) -> RequestBuilder {
+        self.wrap(self.client.head(url))
+    }
+
+    fn wrap(&self, builder: reqwest::RequestBuilder) -> RequestBuilder {
+        RequestBuilder {
+            client: self.clone(),
+            builder,
+        }
+    }
+
+    /// Execute a request, retrying per the retry policy. Responses with a
+    /// retryable status are retried too; the last one is returned as-is so
+    /// callers can report it, except `429` which is always returned as a
+    /// [`ZealError::RateLimitError`]. Requests whose body cannot be cloned,
+    /// and non-idempotent ones unless `retry_non_idempotent` is set, are sent
+    /// once. Retries stop early once the retry budget, if any, runs out, and
+    /// an attempt that hits the pool acquire timeout is never retried.
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        let pool_timed_out = AtomicBool::new(false);
+        let idempotent = !matches!(*request.method(), Method::POST | Method::PATCH);
+        if !(idempotent || self.retry.retry_non_idempotent) || request.try_clone().is_none() {
+            let response = self.execute_once(request, &pool_timed_out).await?;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                return Err(ZealError::rate_limited(&response));
+            }
+            return Ok(response);
+        }
+
+        // A retryable response is handed to the retry loop as an error, so it
+        // neither counts as a success for the budget nor races it for a
+        // token. The last one is kept to return as-is if retries run out.
+        let last_response = Mutex::new(None);
+        let attempt = || async {
+            *last_response.lock().unwrap() = None;
+            let request = request.try_clone().expect("request body is cloneable");
+            let response = self.execute_once(request, &pool_timed_out).await?;
+
+            let status = response.status();
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(ZealError::rate_limited(&response));
+            }
+            if self.retry.retryable_status_codes.contains(&status.as_u16()) {
+                let err = status_error(&response);
+                *last_response.lock().unwrap() = Some(response);
+                return Err(err);
+            }
+            Ok(response)
+        };
+        // Retrying after a pool timeout only adds load to the saturated pool
+        let retryable = |err: &ZealError| {
+            !pool_timed_out.load(Ordering::SeqCst) && should_retry(&self.retry, err)
+        };
+        let result = retry_with_budget_if(
+            &self.retry,
+            self.retry_budget.as_deref(),
+            attempt,
+            retryable,
+        )
+        .await;
+
+        match (result, last_response.into_inner().unwrap()) {
+            (Err(_), Some(response)) => Ok(response),
+            (result, _) => result,
+        }
+    }
+
+    /// Send a single attempt, bounded by the pool acquire timeout so an
+    /// exhausted pool surfaces as an error rather than unexplained latency.
+    /// Sets `pool_timed_out` if the timeout elapses.
+    async fn execute_once(
+        &self,
+        request: reqwest::Request,
+        pool_timed_out: &AtomicBool,
+    ) -> Result<reqwest::Response> {
+        let Some(timeout) = self.pool_acquire_timeout else {
+            return self.send_attempt(request).await;
+        };
+        match tokio::time::timeout(timeout, self.send_attempt(request)).await {
+            Ok(result) => result,
+            Err(_) => {
+                pool_timed_out.store(true, Ordering::SeqCst);
+                Err(ZealError::connection_error(format!(
+                    "No connection became available within {:?}; the connection pool is \
+                     likely exhausted by concurrent requests. Raise max_connections_per_host \
+                     (and max_concurrent_requests, if set), or send fewer requests at once",
+                    timeout
+                )))
+            }
+        }
+    }
+
+    /// Send a single attempt, holding a concurrency permit (if limited) until
+    /// the response headers arrive. Permits aren't held across retry delays.
+    async fn send_attempt(&self, mut request: reqwest::Request) -> Result<reqwest::Response> {
+        let _permit = match &self.concurrency {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire()
+                    .await
+                    .map_err(|_| ZealError::other("Request concurrency limiter was closed"))?,
+            ),
+            None => None,
+        };
+
+        if let Some(provider) = &self.token_provider {
+            let token = provider.token().await?;
+            let value = HeaderValue::from_str(&format!("Bearer {}", token)).map_err(|_| {
+                ZealError::authentication_error("Token contains invalid header characters")
+            })?;
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+
+        match &self.recorder {
+            Some(recorder) => recorder.execute(&self.client, request).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+}
+
+/// Error for a response whose status should be retried
+fn status_error(response: &reqwest::Response) -> ZealError {
+    let status = response.status();
+    ZealError::api_error(status.as_u16(), format!("Request failed: {}", status), None)
+}
+
+/// Request builder mirroring the subset of `reqwest::RequestBuilder` used by the SDK
+pub(crate) struct RequestBuilder {
+    client: HttpClient,
+    builder: reqwest::RequestBuilder,
+}
+
+impl RequestBuilder {
+    pub(crate) fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+
+    pub(crate) fn json<T: Serialize + ?Sized>(mut self, json: &T) -> Self {
+        self.builder = self.builder.json(json);
+        self
+    }
+
+    pub(crate) fn body<T: Into<reqwest::Body>>(mut self, body: T) -> Self {
+        self.builder = self.builder.body(body);
+        self
+    }
+
+    pub(crate) async fn send(self) -> Result<reqwest::Response> {
+        let request = self.builder.build()?;
+        self.client.execute(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryBudgetConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// Answers with the given statuses in order, repeating the last one
+    struct Statuses(Vec<ResponseTemplate>, AtomicUsize);
+
+    impl Respond for Statuses {
+        fn respond(&self, _request: &Request) -> ResponseTemplate {
+            let call = self.1.fetch_add(1, Ordering::SeqCst);
+            self.0[call.min(self.0.len() - 1)].clone()
+        }
+    }
+
+    fn client(max_attempts: usize) -> HttpClient {
+        HttpClient::new(reqwest::Client::new()).with_retry_config(RetryConfig {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            jitter_factor: 0.0,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_retries_retryable_statuses() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/zip/nodes"))
+            .respond_with(Statuses(
+                vec![
+                    ResponseTemplate::new(503),
+                    ResponseTemplate::new(429).insert_header("Retry-After", "0"),
+                    ResponseTemplate::new(200),
+                ],
+                AtomicUsize::new(0),
+            ))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let response = client(3)
+            .put(&format!("{}/api/zip/nodes", server.uri()))
+            .json(&serde_json::json!({ "nodeId": "n1" }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for request in server.received_requests().await.unwrap() {
+            assert_eq!(request.body, br#"{"nodeId":"n1"}"#);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_requests_are_retried_only_when_enabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let response = client(3).post(&server.uri()).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        let opted_in = HttpClient::new(reqwest::Client::new()).with_retry_config(RetryConfig {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            jitter_factor: 0.0,
+            retry_non_idempotent: true,
+            ..Default::default()
+        });
+        opted_in.post(&server.uri()).send().await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 1 + 3);
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_response_when_attempts_run_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let response = client(2).get(&server.uri()).send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_is_shared_across_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let client = HttpClient::new(reqwest::Client::new()).with_retry_config(RetryConfig {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            jitter_factor: 0.0,
+            budget: Some(RetryBudgetConfig {
+                retry_ratio: 0.1,
+                burst: 2,
+            }),
+            ..Default::default()
+        });
+        for _ in 0..25 {
+            // Clones draw on the same budget
+            let response = client.clone().get(&server.uri()).send().await.unwrap();
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        }
+
+        // The first request uses both retries in the budget; the other 24
+        // fail after one attempt instead of three. Failed requests don't
+        // refill the budget, so no retries come back as failures pile up.
+        assert_eq!(server.received_requests().await.unwrap().len(), 3 + 24);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_after_attempts_run_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let err = client(2).get(&server.uri()).send().await.unwrap_err();
+        assert!(matches!(err, ZealError::RateLimitError { .. }));
+        assert_eq!(err.retry_after(), Some(Duration::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_requests() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Minimal server that records how many requests it handles at once
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        {
+            let (active, peak) = (active.clone(), peak.clone());
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = listener.accept().await.unwrap();
+                    let (active, peak) = (active.clone(), peak.clone());
+                    tokio::spawn(async move {
+                        let mut request = Vec::new();
+                        let mut buf = [0u8; 1024];
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            let n = socket.read(&mut buf).await.unwrap();
+                            request.extend_from_slice(&buf[..n]);
+                        }
+                        let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        active.fetch_sub(1, Ordering::SeqCst);
+                        socket
+                            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                            .await
+                            .unwrap();
+                    });
+                }
+            });
+        }
+
+        let client = HttpClient::new(reqwest::Client::new()).with_max_concurrent_requests(Some(3));
+        let requests = (0..12).map(|_| {
+            let (client, url) = (client.clone(), url.clone());
+            async move {
+                let response = client.get(&url).send().await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+        });
+        futures_util::future::join_all(requests).await;
+
+        // The limit is reached but never exceeded
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+
+    /// Check that a request failed with the pool timeout error
+    fn assert_pool_timeout(error: &ZealError) {
+        match error {
+            ZealError::ConnectionError { message } => {
+                assert!(message.contains("max_connections_per_host"), "{}", message)
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_exhaustion_fails_without_retrying() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+            .mount(&server)
+            .await;
+
+        // A single slot, so every request but the first waits on the ones
+        // ahead and runs out of time before its turn ends
+        let client = HttpClient::new(reqwest::Client::new())
+            .with_retry_config(RetryConfig::default())
+            .with_max_concurrent_requests(Some(1))
+            .with_pool_acquire_timeout(Some(Duration::from_millis(300)));
+        let requests = (0..4).map(|_| {
+            let (client, url) = (client.clone(), server.uri());
+            async move {
+                let started = tokio::time::Instant::now();
+                (client.get(&url).send().await, started.elapsed())
+            }
+        });
+        let results = futures_util::future::join_all(requests).await;
+
+        let succeeded = results.iter().filter(|(result, _)| result.is_ok()).count();
+        assert_eq!(succeeded, 1);
+        for (result, elapsed) in results {
+            let Err(error) = result else { continue };
+            // Failed after one acquire timeout, not after a series of
+            // retried waits
+            assert!(elapsed < Duration::from_millis(550), "{:?}", elapsed);
+            assert_pool_timeout(&error);
+        }
+        // The queued requests timed out without being retried; at most the
+        // first one after the successful request got to the server
+        let received = server.received_requests().await.unwrap().len();
+        assert!(received <= 2, "{} requests reached the server", received);
+    }
+
+    #[tokio::test]
+    async fn test_pool_timeout_applies_without_concurrency_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // The default retry config retries connection errors, so this also
+        // checks that a pool timeout isn't
+        let client = HttpClient::new(reqwest::Client::new())
+            .with_retry_config(RetryConfig::default())
+            .with_pool_acquire_timeout(Some(Duration::from_millis(100)));
+        let started = tokio::time::Instant::now();
+        let error = client.get(&server.uri()).send().await.unwrap_err();
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_pool_timeout(&error);
+    }
+}
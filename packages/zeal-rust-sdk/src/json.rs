@@ -0,0 +1,46 @@
+//! JSON deserialization, using `simd-json` when the feature is enabled
+
+use crate::errors::Result;
+use serde::de::DeserializeOwned;
+
+/// Deserialize `bytes` as JSON
+#[cfg(feature = "simd-json")]
+pub(crate) fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    // simd-json parses in place, so it needs its own mutable copy
+    let mut buffer = bytes.to_vec();
+    simd_json::serde::from_slice(&mut buffer).map_err(|err| {
+        crate::errors::ZealError::SerializationError {
+            source: Box::new(err),
+        }
+    })
+}
+
+/// Deserialize `bytes` as JSON
+#[cfg(not(feature = "simd-json"))]
+pub(crate) fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+/// Read a response body and deserialize it as JSON
+pub(crate) async fn from_response<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let body = response.bytes().await?;
+    from_slice(&body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ZealError;
+
+    #[test]
+    fn test_from_slice() {
+        let value: serde_json::Value = from_slice(br#"{"nodes":[{"id":"a"}]}"#).unwrap();
+        assert_eq!(value["nodes"][0]["id"], "a");
+
+        let err = from_slice::<serde_json::Value>(b"{not json").unwrap_err();
+        assert!(matches!(
+            err,
+            ZealError::JsonError { .. } | ZealError::SerializationError { .. }
+        ));
+    }
+}
@@ -36,41 +36,65 @@
 //! }
 //! ```
 
+pub mod api;
 pub mod auth;
+mod batching;
+pub mod cassette;
 pub mod client;
 pub mod config;
 pub mod errors;
 pub mod events;
+pub mod export;
+mod http;
+mod json;
 pub mod observables;
 pub mod orchestrator;
+pub mod retry;
+pub mod runtime;
+pub mod secrets;
+pub mod spans;
 pub mod subscription;
 pub mod templates;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod trace_batcher;
 pub mod traces;
 pub mod types;
+pub mod visual_state_batcher;
 pub mod webhooks;
+pub mod websocket;
 
 // #[cfg(feature = "telemetry")]
 // pub mod telemetry;
 
-#[cfg(feature = "webhook-server")]
-pub use subscription::start_webhook_server;
-
 // Re-export main types
+pub use api::{OrchestratorApi, TemplatesApi, TracesApi, WebhooksApi};
+pub use auth::{RefreshingTokenProvider, SharedTokenProvider, StaticTokenProvider, TokenProvider};
 pub use client::ZealClient;
-pub use config::{ClientConfig, PerformanceConfig};
-pub use errors::{Result, ZealError};
+pub use config::{
+    ClientConfig, ClientConfigBuilder, PerformanceConfig, PerformanceConfigBuilder,
+    RecordReplayConfig, RecordReplayMode, RetryBudgetConfig, RetryConfig,
+};
+pub use errors::{Result, ZealApiErrorBody, ZealError};
+pub use retry::{retry_with_budget, retry_with_config, RetryBudget};
+pub use runtime::{CapabilityMismatch, Runtime};
+pub use secrets::{SecretEncryptor, SecretProperties};
+pub use spans::{ExecutionTracker, NodeSpan, SpanStatus};
 pub use subscription::{SubscriptionOptions, WebhookSubscription};
+pub use trace_batcher::{TraceBatchError, TraceBatcher};
 pub use types::*;
+pub use visual_state_batcher::VisualStateBatcher;
+pub use websocket::{Latency, SocketState, ZealWebSocketClient, ZipEventStream};
 
 // Re-export key traits and functions
 pub use events::{
     ConnectionState, ConnectionStateEvent, ElementState, ElementType, ExecutionCompletedEvent,
-    ExecutionFailedEvent, ExecutionStartedEvent, NodeCompletedEvent, NodeExecutingEvent,
-    NodeFailedEvent, NodeWarningEvent, VisualStateElement, VisualStateUpdate, WorkflowCreatedEvent,
-    WorkflowDeletedEvent, WorkflowUpdatedEvent, ZipControlEvent, ZipExecutionEvent,
-    ZipWebSocketEvent, ZipWebhookEvent, ZipWorkflowEvent,
+    ExecutionFailedEvent, ExecutionFailure, ExecutionStartedEvent, FailureScope,
+    NodeCompletedEvent, NodeExecutingEvent, NodeFailedEvent, NodeWarningEvent, VisualStateElement,
+    VisualStateUpdate, WorkflowCreatedEvent, WorkflowDeletedEvent, WorkflowUpdatedEvent,
+    ZipControlEvent, ZipExecutionEvent, ZipWebSocketEvent, ZipWebhookEvent, ZipWorkflowEvent,
 };
-pub use observables::{ObservableExt, ZealObservable};
+pub use observables::{merge, ObservableExt, StreamTimeout, ZealObservable};
 pub use traces::{TraceEvent, TraceEventType, TraceStatus};
 
 /// SDK version
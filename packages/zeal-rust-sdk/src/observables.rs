@@ -1,8 +1,11 @@
 //! Observable stream extensions for event processing
 
 use futures_util::Stream;
+use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::Sleep;
 
 /// Extension trait for observable streams
 pub trait ZealObservable<T>: Stream<Item = T> + Sized {
@@ -16,10 +19,103 @@ pub trait ZealObservable<T>: Stream<Item = T> + Sized {
             predicate,
         }
     }
+
+    /// Collect items into batches of up to `size`. A partial batch is
+    /// emitted when the stream ends. A `size` of zero is treated as one.
+    fn buffer(self, size: usize) -> BufferStream<Self, T> {
+        let size = size.max(1);
+        BufferStream {
+            stream: self,
+            size,
+            items: Vec::with_capacity(size),
+            done: false,
+        }
+    }
+
+    /// Like [`buffer`](Self::buffer), but also emits a partial batch once
+    /// `timeout` has passed since its first item arrived, so latency stays
+    /// bounded when the stream is slow
+    fn buffer_timeout(self, size: usize, timeout: Duration) -> BufferTimeoutStream<Self, T> {
+        let size = size.max(1);
+        BufferTimeoutStream {
+            stream: self,
+            size,
+            timeout,
+            items: Vec::with_capacity(size),
+            deadline: None,
+            done: false,
+        }
+    }
+
+    /// Emit an item only once the stream has been quiet for `duration`.
+    ///
+    /// The upstream is polled as fast as it produces; each new item replaces
+    /// the one waiting to be emitted and restarts the timer, so a burst
+    /// collapses into its last item and at most one item is held. A waiting
+    /// item is emitted immediately when the stream ends.
+    fn debounce(self, duration: Duration) -> DebounceStream<Self, T> {
+        DebounceStream {
+            stream: self,
+            duration,
+            pending: None,
+            deadline: None,
+            done: false,
+        }
+    }
+
+    /// Emit at most one item per `interval`.
+    ///
+    /// The first item is emitted immediately and opens an interval. Items
+    /// arriving during the interval are not queued: only the latest is kept
+    /// and emitted when the interval ends (opening the next one), so the
+    /// final state of a burst is never lost and memory stays bounded when the
+    /// upstream is faster than the interval.
+    fn throttle(self, interval: Duration) -> ThrottleStream<Self, T> {
+        ThrottleStream {
+            stream: self,
+            interval,
+            pending: None,
+            window: None,
+            done: false,
+        }
+    }
+
+    /// Yield each item as `Ok`, and a [`StreamTimeout`] error whenever no
+    /// item arrives within `duration`.
+    ///
+    /// The timer starts when the stream is first polled and restarts on the
+    /// next poll after every item or error, so a stall produces one error per
+    /// `duration` until the stream recovers or ends. Useful for noticing a
+    /// dead feed and reconnecting.
+    fn timeout(self, duration: Duration) -> TimeoutStream<Self> {
+        TimeoutStream {
+            stream: self,
+            duration,
+            sleep: None,
+        }
+    }
+
+    /// Interleave this stream with `other`, see [`merge`]
+    fn merge_with(self, other: Self) -> MergeStream<Self> {
+        merge(vec![self, other])
+    }
 }
 
 impl<S, T> ZealObservable<T> for S where S: Stream<Item = T> {}
 
+/// Interleave the items of several streams as they arrive, ending once every
+/// input has ended.
+///
+/// Inputs are polled round-robin, starting after the one that produced the
+/// previous item, so a stream that always has an item ready can't starve the
+/// others.
+pub fn merge<S: Stream>(streams: Vec<S>) -> MergeStream<S> {
+    MergeStream {
+        streams: streams.into_iter().map(Box::pin).collect(),
+        next: 0,
+    }
+}
+
 /// Alias for the main observable extension trait
 pub use ZealObservable as ObservableExt;
 
@@ -55,3 +151,485 @@ where
         }
     }
 }
+
+/// Stream that collects items into batches
+#[pin_project::pin_project]
+pub struct BufferStream<S, T> {
+    #[pin]
+    stream: S,
+    size: usize,
+    items: Vec<T>,
+    done: bool,
+}
+
+impl<S, T> Stream for BufferStream<S, T>
+where
+    S: Stream<Item = T>,
+{
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.items.push(item);
+                    if this.items.len() >= *this.size {
+                        return Poll::Ready(Some(std::mem::replace(
+                            this.items,
+                            Vec::with_capacity(*this.size),
+                        )));
+                    }
+                }
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    if this.items.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(std::mem::take(this.items)));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Stream that collects items into batches, flushing on size or timeout
+#[pin_project::pin_project]
+pub struct BufferTimeoutStream<S, T> {
+    #[pin]
+    stream: S,
+    size: usize,
+    timeout: Duration,
+    items: Vec<T>,
+    deadline: Option<Pin<Box<Sleep>>>,
+    done: bool,
+}
+
+impl<S, T> Stream for BufferTimeoutStream<S, T>
+where
+    S: Stream<Item = T>,
+{
+    type Item = Vec<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.items.is_empty() {
+                        *this.deadline = Some(Box::pin(tokio::time::sleep(*this.timeout)));
+                    }
+                    this.items.push(item);
+                    if this.items.len() >= *this.size {
+                        *this.deadline = None;
+                        return Poll::Ready(Some(std::mem::replace(
+                            this.items,
+                            Vec::with_capacity(*this.size),
+                        )));
+                    }
+                }
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    *this.deadline = None;
+                    if this.items.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(std::mem::take(this.items)));
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        let expired = this
+            .deadline
+            .as_mut()
+            .is_some_and(|deadline| deadline.as_mut().poll(cx).is_ready());
+        if !expired {
+            return Poll::Pending;
+        }
+
+        *this.deadline = None;
+        Poll::Ready(Some(std::mem::replace(
+            this.items,
+            Vec::with_capacity(*this.size),
+        )))
+    }
+}
+
+/// Stream that emits an item after a quiet period
+#[pin_project::pin_project]
+pub struct DebounceStream<S, T> {
+    #[pin]
+    stream: S,
+    duration: Duration,
+    pending: Option<T>,
+    deadline: Option<Pin<Box<Sleep>>>,
+    done: bool,
+}
+
+impl<S, T> Stream for DebounceStream<S, T>
+where
+    S: Stream<Item = T>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if *this.done {
+            return Poll::Ready(this.pending.take());
+        }
+
+        loop {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    *this.pending = Some(item);
+                    let deadline = tokio::time::Instant::now() + *this.duration;
+                    match this.deadline.as_mut() {
+                        Some(sleep) => sleep.as_mut().reset(deadline),
+                        None => *this.deadline = Some(Box::pin(tokio::time::sleep_until(deadline))),
+                    }
+                }
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    *this.deadline = None;
+                    return Poll::Ready(this.pending.take());
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        let expired = this
+            .deadline
+            .as_mut()
+            .is_some_and(|deadline| deadline.as_mut().poll(cx).is_ready());
+        if !expired {
+            return Poll::Pending;
+        }
+
+        *this.deadline = None;
+        match this.pending.take() {
+            Some(item) => Poll::Ready(Some(item)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Stream that emits at most one item per interval
+#[pin_project::pin_project]
+pub struct ThrottleStream<S, T> {
+    #[pin]
+    stream: S,
+    interval: Duration,
+    pending: Option<T>,
+    window: Option<Pin<Box<Sleep>>>,
+    done: bool,
+}
+
+impl<S, T> Stream for ThrottleStream<S, T>
+where
+    S: Stream<Item = T>,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        while !*this.done {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.window.is_none() {
+                        *this.window = Some(Box::pin(tokio::time::sleep(*this.interval)));
+                        return Poll::Ready(Some(item));
+                    }
+                    *this.pending = Some(item);
+                }
+                Poll::Ready(None) => *this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        if let Some(window) = this.window.as_mut() {
+            if window.as_mut().poll(cx).is_pending() {
+                if *this.done && this.pending.is_none() {
+                    return Poll::Ready(None);
+                }
+                return Poll::Pending;
+            }
+
+            *this.window = None;
+            if let Some(item) = this.pending.take() {
+                *this.window = Some(Box::pin(tokio::time::sleep(*this.interval)));
+                return Poll::Ready(Some(item));
+            }
+        }
+
+        if *this.done {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Error item of [`ZealObservable::timeout`]: no item arrived in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("No stream item received within {elapsed:?}")]
+pub struct StreamTimeout {
+    /// How long the stream was quiet
+    pub elapsed: Duration,
+}
+
+/// Stream that reports stalls as errors
+#[pin_project::pin_project]
+pub struct TimeoutStream<S> {
+    #[pin]
+    stream: S,
+    duration: Duration,
+    /// Created on poll rather than up front, since a timer needs a runtime
+    #[pin]
+    sleep: Option<Sleep>,
+}
+
+impl<S, T> Stream for TimeoutStream<S>
+where
+    S: Stream<Item = T>,
+{
+    type Item = std::result::Result<T, StreamTimeout>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        if this.sleep.is_none() {
+            this.sleep.set(Some(tokio::time::sleep(*this.duration)));
+        }
+
+        let item = match this.stream.poll_next(cx) {
+            Poll::Ready(Some(item)) => Ok(item),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {
+                let sleep = this.sleep.as_mut().as_pin_mut();
+                if sleep.is_some_and(|sleep| sleep.poll(cx).is_pending()) {
+                    return Poll::Pending;
+                }
+                Err(StreamTimeout {
+                    elapsed: *this.duration,
+                })
+            }
+        };
+
+        this.sleep.set(None);
+        Poll::Ready(Some(item))
+    }
+}
+
+/// Stream that interleaves several streams
+pub struct MergeStream<S> {
+    streams: Vec<Pin<Box<S>>>,
+    /// Index of the stream to poll first
+    next: usize,
+}
+
+impl<S: Stream> Stream for MergeStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let mut polled = 0;
+        while polled < this.streams.len() {
+            let index = (this.next + polled) % this.streams.len();
+            match this.streams[index].as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.next = index + 1;
+                    return Poll::Ready(Some(item));
+                }
+                Poll::Ready(None) => {
+                    // The following streams shift down a slot, so the same
+                    // offset now points at the next unpolled stream
+                    this.streams.remove(index);
+                    if index < this.next {
+                        this.next -= 1;
+                    }
+                }
+                Poll::Pending => polled += 1,
+            }
+        }
+
+        if this.streams.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{stream, StreamExt};
+
+    #[tokio::test]
+    async fn test_buffer_flushes_partial_batch_at_end() {
+        let batches: Vec<Vec<i32>> = stream::iter(1..=5).buffer(2).collect().await;
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        let empty: Vec<Vec<i32>> = stream::iter(Vec::<i32>::new()).buffer(2).collect().await;
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_buffer_timeout_flushes_when_interval_elapses() {
+        let (tx, received) = channel();
+        let mut batches = Box::pin(received.buffer_timeout(3, Duration::from_millis(50)));
+
+        for i in 1..=4 {
+            tx.send(i).unwrap();
+        }
+        assert_eq!(batches.next().await, Some(vec![1, 2, 3]));
+
+        // Only one item is pending, so the timeout flushes it
+        let started = tokio::time::Instant::now();
+        assert_eq!(batches.next().await, Some(vec![4]));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        tx.send(5).unwrap();
+        drop(tx);
+        assert_eq!(batches.next().await, Some(vec![5]));
+        assert_eq!(batches.next().await, None);
+    }
+
+    fn channel() -> (
+        tokio::sync::mpsc::UnboundedSender<i32>,
+        impl Stream<Item = i32>,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let received = stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        });
+        (tx, received)
+    }
+
+    #[tokio::test]
+    async fn test_debounce_emits_last_item_of_burst() {
+        let (tx, received) = channel();
+        let mut debounced = Box::pin(received.debounce(Duration::from_millis(50)));
+
+        for i in 1..=3 {
+            tx.send(i).unwrap();
+        }
+        let started = tokio::time::Instant::now();
+        assert_eq!(debounced.next().await, Some(3));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        // A waiting item is flushed when the stream ends
+        tx.send(4).unwrap();
+        drop(tx);
+        assert_eq!(debounced.next().await, Some(4));
+        assert_eq!(debounced.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_throttle_emits_first_and_latest_per_interval() {
+        let (tx, received) = channel();
+        let mut throttled = Box::pin(received.throttle(Duration::from_millis(50)));
+
+        tx.send(1).unwrap();
+        assert_eq!(throttled.next().await, Some(1));
+
+        for i in 2..=4 {
+            tx.send(i).unwrap();
+        }
+        let started = tokio::time::Instant::now();
+        assert_eq!(throttled.next().await, Some(4));
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        drop(tx);
+        assert_eq!(throttled.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_reports_stalls_and_resets_on_items() {
+        let (tx, received) = channel();
+        let mut timed = Box::pin(received.timeout(Duration::from_millis(50)));
+
+        tx.send(1).unwrap();
+        assert_eq!(timed.next().await, Some(Ok(1)));
+
+        let started = tokio::time::Instant::now();
+        assert_eq!(
+            timed.next().await,
+            Some(Err(StreamTimeout {
+                elapsed: Duration::from_millis(50)
+            }))
+        );
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        // Still usable after a timeout, and composes with other combinators
+        tx.send(2).unwrap();
+        tx.send(3).unwrap();
+        drop(tx);
+        let odd = ZealObservable::filter(timed, |item| item.as_ref().is_ok_and(|n| n % 2 == 1));
+        let rest: Vec<_> = odd.collect().await;
+        assert_eq!(rest, vec![Ok(3)]);
+    }
+
+    #[test]
+    fn test_timeout_can_be_built_outside_a_runtime() {
+        let timed = stream::iter(vec![1, 2]).timeout(Duration::from_millis(50));
+
+        let items = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(timed.collect::<Vec<_>>());
+        assert_eq!(items, vec![Ok(1), Ok(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_completes_when_all_inputs_complete() {
+        let merged: Vec<i32> = merge(vec![
+            stream::iter(vec![1, 2, 3]),
+            stream::iter(vec![]),
+            stream::iter(vec![10]),
+        ])
+        .collect()
+        .await;
+        assert_eq!(merged, vec![1, 10, 2, 3]);
+
+        let empty: Vec<i32> = merge(Vec::<stream::Iter<std::vec::IntoIter<i32>>>::new())
+            .collect()
+            .await;
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_does_not_starve_quiet_streams() {
+        let chatty = stream::repeat(0).boxed();
+        let quiet = stream::iter(vec![1, 2]).boxed();
+        let merged: Vec<i32> = chatty.merge_with(quiet).take(6).collect().await;
+        assert_eq!(merged, vec![0, 1, 0, 2, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_merge_interleaves_live_streams() {
+        let (tx_a, a) = channel();
+        let (tx_b, b) = channel();
+        let mut merged = merge(vec![a.boxed(), b.boxed()]);
+
+        tx_b.send(1).unwrap();
+        assert_eq!(merged.next().await, Some(1));
+        tx_a.send(2).unwrap();
+        assert_eq!(merged.next().await, Some(2));
+
+        drop(tx_a);
+        tx_b.send(3).unwrap();
+        assert_eq!(merged.next().await, Some(3));
+        drop(tx_b);
+        assert_eq!(merged.next().await, None);
+    }
+}
@@ -1,10 +1,35 @@
 //! Orchestrator API for workflow management
 
-use crate::errors::{Result, ZealError};
+use crate::errors::{map_error_response, Result, ZealError};
+use crate::http::HttpClient;
+use crate::json;
+use crate::secrets::SecretProperties;
 use crate::types::*;
+use futures_util::stream::{self, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use tokio_util::sync::CancellationToken;
+
+/// One entry in a batch endpoint response: the usual response body, or an
+/// error for that item
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BatchItem<T> {
+    Failed {
+        error: String,
+        #[serde(default)]
+        status: Option<u16>,
+        #[serde(default)]
+        code: Option<String>,
+    },
+    Succeeded(T),
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResponse<T> {
+    results: Vec<BatchItem<T>>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListWorkflowsParams {
@@ -33,20 +58,168 @@ pub struct WorkflowState {
     pub metadata: serde_json::Value,
 }
 
+/// Graph contents of a workflow state. Sections left out of a projected
+/// [`OrchestratorAPI::get_workflow_state_fields`] response are empty.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStateData {
-    pub nodes: Vec<serde_json::Value>,
-    pub connections: Vec<serde_json::Value>,
-    pub groups: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    pub connections: Vec<WorkflowConnection>,
+    #[serde(default)]
+    pub groups: Vec<WorkflowGroup>,
+}
+
+/// Section of a workflow state that [`OrchestratorAPI::get_workflow_state_fields`]
+/// can request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StateField {
+    Nodes,
+    Connections,
+    Groups,
+}
+
+impl StateField {
+    /// Every section, the default projection
+    pub const ALL: [StateField; 3] = [Self::Nodes, Self::Connections, Self::Groups];
+
+    /// Name of the section in the `fields` query parameter
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Nodes => "nodes",
+            Self::Connections => "connections",
+            Self::Groups => "groups",
+        }
+    }
+}
+
+/// Node in a workflow graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    pub position: Position,
+    #[serde(default)]
+    pub properties: HashMap<String, serde_json::Value>,
+    /// Fields not covered above, e.g. `metadata`
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Connection between two node ports in a workflow graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowConnection {
+    pub id: String,
+    pub source: NodePort,
+    pub target: NodePort,
+    /// Fields not covered above, e.g. `state` and `metadata`
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Group of nodes in a workflow graph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowGroup {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(rename = "nodeIds", default)]
+    pub node_ids: Vec<String>,
+    /// Fields not covered above, e.g. `position`, `size` and `color`
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl WorkflowState {
+    /// Compare the graph contents of this state with a later one. Elements
+    /// are matched by ID; one whose fields differ in any way is modified.
+    /// Workflow-level fields such as `name` and `metadata` are not compared.
+    pub fn diff(&self, other: &WorkflowState) -> WorkflowDiff {
+        WorkflowDiff {
+            nodes: diff_by_id(&self.state.nodes, &other.state.nodes, |n| &n.id),
+            connections: diff_by_id(&self.state.connections, &other.state.connections, |c| &c.id),
+            groups: diff_by_id(&self.state.groups, &other.state.groups, |g| &g.id),
+        }
+    }
+}
+
+/// Changes between two workflow states, see [`WorkflowState::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkflowDiff {
+    pub nodes: IdChanges,
+    pub connections: IdChanges,
+    pub groups: IdChanges,
+}
+
+impl WorkflowDiff {
+    /// Check whether the two states have the same graph contents
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.connections.is_empty() && self.groups.is_empty()
+    }
+}
+
+/// IDs of the elements of one kind that changed. Added and modified IDs are
+/// in the order of the newer state, removed IDs in the order of the older.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<String>,
+}
+
+impl IdChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn diff_by_id<T: PartialEq>(old: &[T], new: &[T], id: fn(&T) -> &String) -> IdChanges {
+    let old_by_id: HashMap<&String, &T> = old.iter().map(|item| (id(item), item)).collect();
+    let new_ids: std::collections::HashSet<&String> = new.iter().map(id).collect();
+
+    let mut changes = IdChanges::default();
+    for item in new {
+        match old_by_id.get(id(item)) {
+            None => changes.added.push(id(item).clone()),
+            Some(previous) if *previous != item => changes.modified.push(id(item).clone()),
+            Some(_) => {}
+        }
+    }
+    changes.removed = old
+        .iter()
+        .map(id)
+        .filter(|item_id| !new_ids.contains(item_id))
+        .cloned()
+        .collect();
+    changes
+}
+
+/// Result of fetching several workflow states at once
+#[derive(Debug, Default)]
+pub struct WorkflowStatesBatch {
+    /// States that were fetched successfully, keyed by workflow ID
+    pub states: HashMap<String, WorkflowState>,
+    /// Per-workflow failures, keyed by workflow ID
+    pub errors: HashMap<String, ZealError>,
+}
+
+impl WorkflowStatesBatch {
+    /// Check whether every requested workflow was fetched
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateNodeRequest {
     #[serde(rename = "workflowId")]
     pub workflow_id: String,
-    #[serde(rename = "graphId")]
+    #[serde(rename = "graphId", skip_serializing_if = "Option::is_none")]
     pub graph_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub properties: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub position: Option<Position>,
 }
 
@@ -55,6 +228,45 @@ pub struct UpdateNodeResponse {
     pub success: bool,
 }
 
+/// Compute the property changes needed to turn `current` into `desired`.
+///
+/// Changed and added keys map to their desired value; keys missing from
+/// `desired` map to `null` so the server clears them.
+pub fn property_diff(
+    current: &HashMap<String, serde_json::Value>,
+    desired: &HashMap<String, serde_json::Value>,
+) -> HashMap<String, serde_json::Value> {
+    let mut diff: HashMap<String, serde_json::Value> = desired
+        .iter()
+        .filter(|(key, value)| current.get(*key) != Some(*value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    for key in current.keys() {
+        if !desired.contains_key(key) {
+            diff.insert(key.clone(), serde_json::Value::Null);
+        }
+    }
+
+    diff
+}
+
+/// Result of deleting several nodes at once
+#[derive(Debug, Default)]
+pub struct DeleteNodesBatch {
+    /// Nodes that were deleted, keyed by node ID
+    pub deleted: HashMap<String, DeleteNodeResponse>,
+    /// Per-node failures, keyed by node ID
+    pub errors: HashMap<String, ZealError>,
+}
+
+impl DeleteNodesBatch {
+    /// Check whether every requested node was deleted
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteNodeResponse {
     pub success: bool,
@@ -81,6 +293,17 @@ pub struct CreateGroupRequest {
     pub description: Option<String>,
 }
 
+/// Counts of the entities removed by [`OrchestratorAPI::clear_graph`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClearGraphResponse {
+    #[serde(rename = "nodesRemoved")]
+    pub nodes_removed: usize,
+    #[serde(rename = "connectionsRemoved")]
+    pub connections_removed: usize,
+    #[serde(rename = "groupsRemoved")]
+    pub groups_removed: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateGroupResponse {
     pub success: bool,
@@ -92,26 +315,47 @@ pub struct CreateGroupResponse {
 /// Orchestrator API for creating and managing workflows
 pub struct OrchestratorAPI {
     base_url: String,
-    client: Client,
+    client: HttpClient,
+    secrets: Option<SecretProperties>,
+    batch_concurrency: usize,
 }
 
 impl OrchestratorAPI {
-    /// Create a new Orchestrator API instance
+    /// Create a new Orchestrator API instance with its own HTTP client and
+    /// connection pool (see [`ZealClient`](crate::ZealClient) for sharing one)
     pub fn new(base_url: &str) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            client: Client::new(),
-        }
+        Self::with_http_client(base_url, HttpClient::standalone())
     }
 
     /// Create a new Orchestrator API instance with custom HTTP client
     pub fn with_client(base_url: &str, client: Client) -> Self {
+        Self::with_http_client(base_url, HttpClient::new(client))
+    }
+
+    /// Create with the SDK's shared HTTP transport
+    pub(crate) fn with_http_client(base_url: &str, client: HttpClient) -> Self {
         Self {
             base_url: base_url.to_string(),
             client,
+            secrets: None,
+            batch_concurrency: crate::config::PerformanceConfig::default().max_connections_per_host,
         }
     }
 
+    /// Encrypt secret property values in `add_node` and `update_node` requests
+    pub fn with_secret_properties(mut self, secrets: SecretProperties) -> Self {
+        self.secrets = Some(secrets);
+        self
+    }
+
+    /// Maximum concurrent requests for [`delete_nodes`](Self::delete_nodes) and
+    /// for batch operations that fall back to individual requests
+    /// (default: `max_connections_per_host`)
+    pub fn with_batch_concurrency(mut self, concurrency: usize) -> Self {
+        self.batch_concurrency = concurrency.max(1);
+        self
+    }
+
     /// Create a new workflow
     pub async fn create_workflow(
         &self,
@@ -132,21 +376,47 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to create workflow: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("create workflow", status, &body));
         }
 
-        let workflow_response = response.json::<CreateWorkflowResponse>().await?;
+        let workflow_response = json::from_response::<CreateWorkflowResponse>(response).await?;
         Ok(workflow_response)
     }
 
+    /// Update a workflow's name, description or metadata
+    pub async fn update_workflow(
+        &self,
+        workflow_id: &str,
+        request: UpdateWorkflowRequest,
+    ) -> Result<UpdateWorkflowResponse> {
+        let url = format!(
+            "{}/api/zip/orchestrator/workflows/{}",
+            self.base_url.trim_end_matches('/'),
+            workflow_id
+        );
+
+        let response = self
+            .client
+            .patch(&url)
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(ZealError::not_found("workflow", workflow_id));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("update workflow", status, &body));
+        }
+
+        let update_response = json::from_response::<UpdateWorkflowResponse>(response).await?;
+        Ok(update_response)
+    }
+
     /// List workflows
     pub async fn list_workflows(
         &self,
@@ -175,61 +445,215 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to list workflows: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("list workflows", status, &body));
         }
 
-        let workflows_response = response.json::<ListWorkflowsResponse>().await?;
+        let workflows_response = json::from_response::<ListWorkflowsResponse>(response).await?;
         Ok(workflows_response)
     }
 
+    /// Iterate over every workflow, fetching `page_size` at a time.
+    ///
+    /// Each page advances the offset by the number of workflows received,
+    /// until `total` workflows have been seen or the server returns an empty
+    /// page. The stream ends after yielding an error.
+    pub fn list_all_workflows(
+        &self,
+        page_size: u32,
+    ) -> impl futures_util::Stream<Item = Result<serde_json::Value>> + '_ {
+        struct Pager {
+            offset: u32,
+            pending: std::collections::VecDeque<serde_json::Value>,
+            finished: bool,
+        }
+
+        let state = Pager {
+            offset: 0,
+            pending: std::collections::VecDeque::new(),
+            finished: false,
+        };
+
+        stream::unfold(state, move |mut pager| async move {
+            if let Some(workflow) = pager.pending.pop_front() {
+                return Some((Ok(workflow), pager));
+            }
+            if pager.finished {
+                return None;
+            }
+            if page_size == 0 {
+                pager.finished = true;
+                return Some((
+                    Err(ZealError::validation_error(
+                        "page_size",
+                        "must be greater than zero",
+                    )),
+                    pager,
+                ));
+            }
+
+            let params = ListWorkflowsParams {
+                limit: Some(page_size),
+                offset: Some(pager.offset),
+            };
+            match self.list_workflows(Some(params)).await {
+                Ok(page) => {
+                    let fetched = page.workflows.len() as u32;
+                    pager.offset += fetched;
+                    pager.finished = fetched == 0 || pager.offset >= page.total;
+                    pager.pending.extend(page.workflows);
+                    pager
+                        .pending
+                        .pop_front()
+                        .map(|workflow| (Ok(workflow), pager))
+                }
+                Err(err) => {
+                    pager.finished = true;
+                    Some((Err(err), pager))
+                }
+            }
+        })
+    }
+
     /// Get workflow state
     pub async fn get_workflow_state(
         &self,
         workflow_id: &str,
         graph_id: Option<&str>,
+    ) -> Result<WorkflowState> {
+        self.get_workflow_state_fields(workflow_id, graph_id, &StateField::ALL)
+            .await
+    }
+
+    /// Get workflow state with only the requested graph sections, e.g. just
+    /// the nodes for a layout tool. The other sections come back empty. An
+    /// empty `fields` requests every section.
+    pub async fn get_workflow_state_fields(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        fields: &[StateField],
     ) -> Result<WorkflowState> {
         let graph_id = graph_id.unwrap_or("main");
-        let url = format!(
+        let mut url = format!(
             "{}/api/zip/orchestrator/workflows/{}/state?graphId={}",
             self.base_url.trim_end_matches('/'),
             workflow_id,
             graph_id
         );
 
+        let mut fields = fields.to_vec();
+        fields.sort();
+        fields.dedup();
+        // Servers without projection support keep getting the plain request
+        if !fields.is_empty() && fields.len() < StateField::ALL.len() {
+            let fields: Vec<_> = fields.iter().map(StateField::as_str).collect();
+            url.push_str(&format!("&fields={}", fields.join(",")));
+        }
+
         let response = self.client.get(&url).send().await?;
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("get workflow state", status, &body));
+        }
+
+        let state = json::from_response::<WorkflowState>(response).await?;
+        Ok(state)
+    }
+
+    /// Fetch a workflow's state and write it to `writer` as NDJSON records
+    /// (see [`crate::export`]), returning the number of records written.
+    ///
+    /// Only the output is streamed. The state is fetched with
+    /// [`get_workflow_state`](Self::get_workflow_state), so the whole
+    /// response is buffered and parsed before the first record is written.
+    pub async fn export_workflow_ndjson<W: tokio::io::AsyncWrite + Unpin>(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        writer: &mut W,
+    ) -> Result<usize> {
+        let state = self.get_workflow_state(workflow_id, graph_id).await?;
+        crate::export::write_workflow_ndjson(&state, writer).await
+    }
+
+    /// Check whether a workflow exists without downloading its state.
+    ///
+    /// Sends a `HEAD` request for the workflow state: `200` means it exists,
+    /// `404` that it does not, and any other status is an error.
+    pub async fn workflow_exists(&self, workflow_id: &str) -> Result<bool> {
+        let url = format!(
+            "{}/api/zip/orchestrator/workflows/{}/state",
+            self.base_url.trim_end_matches('/'),
+            workflow_id
+        );
+
+        let response = self.client.head(&url).send().await?;
+
+        let status = response.status();
+        match status.as_u16() {
+            200..=299 => Ok(true),
+            404 => Ok(false),
+            _ => Err(ZealError::api_error(
                 status.as_u16(),
-                format!("Failed to get workflow state: {}", status),
-                Some(error_text),
+                format!("Failed to check workflow existence: {}", status),
+                None,
+            )),
+        }
+    }
+
+    /// Get the state of several workflows, running at most `concurrency` requests at a time.
+    /// A failure for one workflow is recorded in the batch instead of aborting the others.
+    pub async fn get_workflow_states(
+        &self,
+        ids: &[&str],
+        concurrency: usize,
+    ) -> Result<WorkflowStatesBatch> {
+        if concurrency == 0 {
+            return Err(ZealError::validation_error(
+                "concurrency",
+                "must be greater than zero",
             ));
         }
 
-        let state = response.json::<WorkflowState>().await?;
-        Ok(state)
+        let mut requests = Vec::with_capacity(ids.len());
+        for &id in ids {
+            requests.push(async move { (id.to_string(), self.get_workflow_state(id, None).await) });
+        }
+
+        let results = stream::iter(requests)
+            .buffer_unordered(concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut batch = WorkflowStatesBatch::default();
+        for (id, result) in results {
+            match result {
+                Ok(state) => {
+                    batch.states.insert(id, state);
+                }
+                Err(err) => {
+                    batch.errors.insert(id, err);
+                }
+            }
+        }
+
+        Ok(batch)
     }
 
     /// Add a node to a workflow
-    pub async fn add_node(&self, request: AddNodeRequest) -> Result<AddNodeResponse> {
+    pub async fn add_node(&self, mut request: AddNodeRequest) -> Result<AddNodeResponse> {
         let url = format!(
             "{}/api/zip/orchestrator/nodes",
             self.base_url.trim_end_matches('/')
         );
 
+        if let (Some(secrets), Some(values)) = (&self.secrets, request.property_values.as_mut()) {
+            secrets.encrypt(values)?;
+        }
+
         let response = self
             .client
             .post(&url)
@@ -240,18 +664,11 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to add node: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("add node", status, &body));
         }
 
-        let node_response = response.json::<AddNodeResponse>().await?;
+        let node_response = json::from_response::<AddNodeResponse>(response).await?;
         Ok(node_response)
     }
 
@@ -259,7 +676,7 @@ impl OrchestratorAPI {
     pub async fn update_node(
         &self,
         node_id: &str,
-        updates: UpdateNodeRequest,
+        mut updates: UpdateNodeRequest,
     ) -> Result<UpdateNodeResponse> {
         let url = format!(
             "{}/api/zip/orchestrator/nodes/{}",
@@ -267,6 +684,10 @@ impl OrchestratorAPI {
             node_id
         );
 
+        if let (Some(secrets), Some(properties)) = (&self.secrets, updates.properties.as_mut()) {
+            secrets.encrypt(properties)?;
+        }
+
         let response = self
             .client
             .patch(&url)
@@ -277,21 +698,40 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to update node: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("update node", status, &body));
         }
 
-        let update_response = response.json::<UpdateNodeResponse>().await?;
+        let update_response = json::from_response::<UpdateNodeResponse>(response).await?;
         Ok(update_response)
     }
 
+    /// Update only the node properties that differ between `current` and
+    /// `desired`, sending removed keys as `null`.
+    ///
+    /// Returns `None` without making a request when nothing changed.
+    pub async fn update_node_diff(
+        &self,
+        node_id: &str,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+        current: &HashMap<String, serde_json::Value>,
+        desired: &HashMap<String, serde_json::Value>,
+    ) -> Result<Option<UpdateNodeResponse>> {
+        let changes = property_diff(current, desired);
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let updates = UpdateNodeRequest {
+            workflow_id: workflow_id.to_string(),
+            graph_id: graph_id.map(str::to_string),
+            properties: Some(changes),
+            position: None,
+        };
+        self.update_node(node_id, updates).await.map(Some)
+    }
+
     /// Delete a node
     pub async fn delete_node(
         &self,
@@ -312,21 +752,102 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to delete node: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("delete node", status, &body));
         }
 
-        let delete_response = response.json::<DeleteNodeResponse>().await?;
+        let delete_response = json::from_response::<DeleteNodeResponse>(response).await?;
         Ok(delete_response)
     }
 
+    /// Delete several nodes, running at most the configured
+    /// [batch concurrency](Self::with_batch_concurrency) requests at a time.
+    /// A failure for one node is recorded in the batch instead of aborting the others.
+    ///
+    /// The server removes every connection attached to a deleted node, so connections
+    /// between the deleted nodes do not need to be removed separately.
+    pub async fn delete_nodes(
+        &self,
+        workflow_id: &str,
+        node_ids: &[&str],
+        graph_id: Option<&str>,
+    ) -> DeleteNodesBatch {
+        let mut requests = Vec::with_capacity(node_ids.len());
+        for &node_id in node_ids {
+            requests.push(async move {
+                (
+                    node_id.to_string(),
+                    self.delete_node(node_id, workflow_id, graph_id).await,
+                )
+            });
+        }
+
+        let results = stream::iter(requests)
+            .buffer_unordered(self.batch_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut batch = DeleteNodesBatch::default();
+        for (node_id, result) in results {
+            match result {
+                Ok(response) => {
+                    batch.deleted.insert(node_id, response);
+                }
+                Err(err) => {
+                    batch.errors.insert(node_id, err);
+                }
+            }
+        }
+
+        batch
+    }
+
+    /// Remove every node, connection and group from a graph.
+    ///
+    /// Connections and groups are removed first, then nodes are deleted concurrently.
+    /// The server has no bulk clear operation and deletions cannot be rolled back, so
+    /// a failure part way leaves the graph partially cleared; the first error is returned.
+    pub async fn clear_graph(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> Result<ClearGraphResponse> {
+        let state = self.get_workflow_state(workflow_id, graph_id).await?;
+        let mut cleared = ClearGraphResponse::default();
+
+        for connection_id in state.state.connections.into_iter().map(|c| c.id) {
+            self.remove_connection(RemoveConnectionRequest {
+                workflow_id: workflow_id.to_string(),
+                graph_id: graph_id.map(str::to_string),
+                connection_id,
+            })
+            .await?;
+            cleared.connections_removed += 1;
+        }
+
+        for group_id in state.state.groups.into_iter().map(|g| g.id) {
+            self.remove_group(RemoveGroupRequest {
+                workflow_id: workflow_id.to_string(),
+                graph_id: graph_id.map(str::to_string),
+                group_id,
+            })
+            .await?;
+            cleared.groups_removed += 1;
+        }
+
+        let node_ids: Vec<&str> = state.state.nodes.iter().map(|n| n.id.as_str()).collect();
+        let mut batch = self.delete_nodes(workflow_id, &node_ids, graph_id).await;
+        cleared.nodes_removed = batch.deleted.len();
+        if let Some(node_id) = node_ids.iter().find(|id| batch.errors.contains_key(**id)) {
+            return Err(batch
+                .errors
+                .remove(*node_id)
+                .expect("error recorded for node"));
+        }
+
+        Ok(cleared)
+    }
+
     /// Connect two nodes
     pub async fn connect_nodes(&self, request: ConnectNodesRequest) -> Result<ConnectionResponse> {
         let url = format!(
@@ -344,21 +865,245 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to connect nodes: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("connect nodes", status, &body));
         }
 
-        let connection_response = response.json::<ConnectionResponse>().await?;
+        let connection_response = json::from_response::<ConnectionResponse>(response).await?;
         Ok(connection_response)
     }
 
+    /// Add several nodes in order, stopping before the next request once
+    /// `cancel` fires.
+    ///
+    /// If the batch is cancelled or a request fails, nodes already created by
+    /// this call are deleted before the error is returned. To keep the nodes
+    /// that succeeded and get a result per node instead, use
+    /// [`add_nodes_best_effort`](Self::add_nodes_best_effort).
+    pub async fn add_nodes(
+        &self,
+        requests: Vec<AddNodeRequest>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<AddNodeResponse>> {
+        let mut created: Vec<(String, Option<String>, AddNodeResponse)> = Vec::new();
+
+        for request in requests {
+            let outcome = if cancel.is_cancelled() {
+                Err(ZealError::cancelled("add_nodes"))
+            } else {
+                let workflow_id = request.workflow_id.clone();
+                let graph_id = request.graph_id.clone();
+                self.add_node(request)
+                    .await
+                    .map(|node| created.push((workflow_id, graph_id, node)))
+            };
+
+            if let Err(err) = outcome {
+                for (workflow_id, graph_id, node) in created.iter().rev() {
+                    if let Err(e) = self
+                        .delete_node(&node.node_id, workflow_id, graph_id.as_deref())
+                        .await
+                    {
+                        tracing::warn!("Failed to roll back node {}: {}", node.node_id, e);
+                    }
+                }
+                return Err(err);
+            }
+        }
+
+        Ok(created.into_iter().map(|(_, _, node)| node).collect())
+    }
+
+    /// Create several connections in order, stopping before the next request
+    /// once `cancel` fires.
+    ///
+    /// If the batch is cancelled or a request fails, connections already
+    /// created by this call are removed before the error is returned. To keep
+    /// the connections that succeeded and get a result per connection
+    /// instead, use [`connect_nodes_best_effort`](Self::connect_nodes_best_effort).
+    pub async fn connect_nodes_batch(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<ConnectionResponse>> {
+        let mut created: Vec<(String, Option<String>, ConnectionResponse)> = Vec::new();
+
+        for request in requests {
+            let outcome = if cancel.is_cancelled() {
+                Err(ZealError::cancelled("connect_nodes_batch"))
+            } else {
+                let workflow_id = request.workflow_id.clone();
+                let graph_id = request.graph_id.clone();
+                self.connect_nodes(request)
+                    .await
+                    .map(|connection| created.push((workflow_id, graph_id, connection)))
+            };
+
+            if let Err(err) = outcome {
+                for (workflow_id, graph_id, connection) in created.iter().rev() {
+                    let remove = RemoveConnectionRequest {
+                        workflow_id: workflow_id.clone(),
+                        graph_id: graph_id.clone(),
+                        connection_id: connection.connection_id.clone(),
+                    };
+                    if let Err(e) = self.remove_connection(remove).await {
+                        tracing::warn!(
+                            "Failed to roll back connection {}: {}",
+                            connection.connection_id,
+                            e
+                        );
+                    }
+                }
+                return Err(err);
+            }
+        }
+
+        Ok(created
+            .into_iter()
+            .map(|(_, _, connection)| connection)
+            .collect())
+    }
+
+    /// Add several nodes in a single request, returning one result per
+    /// request in the same order.
+    ///
+    /// Unlike [`add_nodes`](Self::add_nodes), a failed node does not roll back
+    /// the others; each failure is reported in its slot and the outer error is
+    /// only for the batch request itself. If the server has no batch endpoint,
+    /// the nodes are added with concurrent individual requests instead.
+    pub async fn add_nodes_best_effort(
+        &self,
+        requests: Vec<AddNodeRequest>,
+    ) -> Result<Vec<Result<AddNodeResponse>>> {
+        let mut encrypted = requests.clone();
+        if let Some(secrets) = &self.secrets {
+            for values in encrypted
+                .iter_mut()
+                .filter_map(|r| r.property_values.as_mut())
+            {
+                secrets.encrypt(values)?;
+            }
+        }
+
+        let results = match self.post_batch("nodes", &encrypted, "add nodes").await? {
+            Some(results) => results,
+            None => {
+                stream::iter(requests)
+                    .map(|request| self.add_node(request))
+                    .buffered(self.batch_concurrency)
+                    .collect()
+                    .await
+            }
+        };
+        log_batch_failures("add_nodes", &results);
+        Ok(results)
+    }
+
+    /// Create several connections in a single request, returning one result
+    /// per request in the same order.
+    ///
+    /// Unlike [`connect_nodes_batch`](Self::connect_nodes_batch), a failed
+    /// connection does not roll back the others; each failure is reported in
+    /// its slot. Falls back to concurrent individual requests if the server
+    /// has no batch endpoint.
+    pub async fn connect_nodes_best_effort(
+        &self,
+        requests: Vec<ConnectNodesRequest>,
+    ) -> Result<Vec<Result<ConnectionResponse>>> {
+        let results = match self
+            .post_batch("connections", &requests, "connect nodes")
+            .await?
+        {
+            Some(results) => results,
+            None => {
+                stream::iter(requests)
+                    .map(|request| self.connect_nodes(request))
+                    .buffered(self.batch_concurrency)
+                    .collect()
+                    .await
+            }
+        };
+        log_batch_failures("connect_nodes", &results);
+        Ok(results)
+    }
+
+    /// POST `items` to `/api/zip/orchestrator/{resource}/batch` as
+    /// `{ "<resource>": [...] }`. Returns `None` if the server does not
+    /// support the batch endpoint.
+    async fn post_batch<Req, Resp>(
+        &self,
+        resource: &str,
+        items: &[Req],
+        action: &str,
+    ) -> Result<Option<Vec<Result<Resp>>>>
+    where
+        Req: Serialize,
+        Resp: serde::de::DeserializeOwned,
+    {
+        if items.is_empty() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let url = format!(
+            "{}/api/zip/orchestrator/{}/batch",
+            self.base_url.trim_end_matches('/'),
+            resource
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ resource: items }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if matches!(status.as_u16(), 404 | 405 | 501) {
+            tracing::debug!(
+                "Batch endpoint for {} unavailable ({}), sending individual requests",
+                resource,
+                status
+            );
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response(action, status, &body));
+        }
+
+        let batch = json::from_response::<BatchResponse<Resp>>(response).await?;
+        if batch.results.len() != items.len() {
+            return Err(ZealError::Other {
+                message: format!(
+                    "Batch {} returned {} results for {} requests",
+                    resource,
+                    batch.results.len(),
+                    items.len()
+                ),
+            });
+        }
+
+        Ok(Some(
+            batch
+                .results
+                .into_iter()
+                .map(|item| match item {
+                    BatchItem::Succeeded(response) => Ok(response),
+                    BatchItem::Failed {
+                        error,
+                        status,
+                        code,
+                    } => Err(ZealError::api_error(
+                        status.unwrap_or(400),
+                        format!("Failed to {}: {}", action, error),
+                        code,
+                    )),
+                })
+                .collect(),
+        ))
+    }
+
     /// Create a node group
     pub async fn create_group(&self, request: CreateGroupRequest) -> Result<CreateGroupResponse> {
         let url = format!(
@@ -376,18 +1121,11 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to create group: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("create group", status, &body));
         }
 
-        let group_response = response.json::<CreateGroupResponse>().await?;
+        let group_response = json::from_response::<CreateGroupResponse>(response).await?;
         Ok(group_response)
     }
 
@@ -411,18 +1149,11 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to remove connection: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("remove connection", status, &body));
         }
 
-        let remove_response = response.json::<RemoveConnectionResponse>().await?;
+        let remove_response = json::from_response::<RemoveConnectionResponse>(response).await?;
         Ok(remove_response)
     }
 
@@ -443,18 +1174,11 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to update group: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("update group", status, &body));
         }
 
-        let update_response = response.json::<UpdateGroupResponse>().await?;
+        let update_response = json::from_response::<UpdateGroupResponse>(response).await?;
         Ok(update_response)
     }
 
@@ -475,18 +1199,939 @@ impl OrchestratorAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to remove group: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("remove group", status, &body));
         }
 
-        let remove_response = response.json::<RemoveGroupResponse>().await?;
+        let remove_response = json::from_response::<RemoveGroupResponse>(response).await?;
         Ok(remove_response)
     }
 }
+
+/// Warn about per-item failures in a batch so they are not lost if the
+/// caller only checks the outer result
+fn log_batch_failures<T>(operation: &str, results: &[Result<T>]) {
+    let failed = results.iter().filter(|result| result.is_err()).count();
+    if failed > 0 {
+        tracing::warn!(
+            "{}: {} of {} batch items failed",
+            operation,
+            failed,
+            results.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use wiremock::matchers::{body_json, method, path, path_regex, query_param};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    fn workflow_state_json(workflow_id: &str) -> serde_json::Value {
+        serde_json::json!({
+            "workflowId": workflow_id,
+            "graphId": "main",
+            "name": format!("Workflow {}", workflow_id),
+            "description": "",
+            "version": 1,
+            "state": { "nodes": [], "connections": [], "groups": [] },
+            "metadata": {}
+        })
+    }
+
+    #[test]
+    fn test_workflow_state_diff() {
+        let state = |nodes: serde_json::Value, connections: serde_json::Value| {
+            let mut json = workflow_state_json("wf-1");
+            json["state"]["nodes"] = nodes;
+            json["state"]["connections"] = connections;
+            json["state"]["groups"] = serde_json::json!([{ "id": "g-1", "nodeIds": ["a"] }]);
+            serde_json::from_value::<WorkflowState>(json).unwrap()
+        };
+        let node = |id: &str, x: f64, metadata: serde_json::Value| {
+            serde_json::json!({
+                "id": id,
+                "type": "script",
+                "position": { "x": x, "y": 0.0 },
+                "metadata": metadata
+            })
+        };
+        let connection = |id: &str, from: &str, to: &str| {
+            serde_json::json!({
+                "id": id,
+                "source": { "nodeId": from, "portId": "out" },
+                "target": { "nodeId": to, "portId": "in" }
+            })
+        };
+
+        let before = state(
+            serde_json::json!([
+                node("a", 0.0, serde_json::json!({})),
+                node("b", 0.0, serde_json::json!({})),
+                node("c", 0.0, serde_json::json!({ "title": "C" })),
+            ]),
+            serde_json::json!([connection("ab", "a", "b"), connection("bc", "b", "c")]),
+        );
+        assert!(before.diff(&before.clone()).is_empty());
+
+        let after = state(
+            serde_json::json!([
+                node("d", 0.0, serde_json::json!({})),
+                node("a", 5.0, serde_json::json!({})),
+                // Only a field outside the typed ones changed
+                node("c", 0.0, serde_json::json!({ "title": "C2" })),
+            ]),
+            serde_json::json!([connection("ab", "a", "b"), connection("ad", "a", "d")]),
+        );
+        let diff = before.diff(&after);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.nodes,
+            IdChanges {
+                added: vec!["d".to_string()],
+                removed: vec!["b".to_string()],
+                modified: vec!["a".to_string(), "c".to_string()],
+            }
+        );
+        assert_eq!(diff.connections.added, vec!["ad"]);
+        assert_eq!(diff.connections.removed, vec!["bc"]);
+        assert!(diff.connections.modified.is_empty());
+        assert!(diff.groups.is_empty());
+
+        // The reverse diff swaps added and removed
+        let reverse = after.diff(&before);
+        assert_eq!(reverse.nodes.added, vec!["b"]);
+        assert_eq!(reverse.nodes.removed, vec!["d"]);
+    }
+
+    #[test]
+    fn test_workflow_state_data_is_typed_and_keeps_unknown_fields() {
+        let data: WorkflowStateData = serde_json::from_value(serde_json::json!({
+            "nodes": [{
+                "id": "node-1",
+                "type": "http-request",
+                "position": { "x": 10.0, "y": 20.5 },
+                "properties": { "url": "https://example.com" },
+                "metadata": { "title": "Fetch" }
+            }],
+            "connections": [{
+                "id": "conn-1",
+                "source": { "nodeId": "node-1", "portId": "out" },
+                "target": { "nodeId": "node-2", "portId": "in" },
+                "state": "success"
+            }],
+            "groups": [{
+                "id": "group-1",
+                "title": "Inputs",
+                "nodeIds": ["node-1"],
+                "color": "#fff"
+            }]
+        }))
+        .unwrap();
+
+        let node = &data.nodes[0];
+        assert_eq!(node.node_type, "http-request");
+        assert_eq!(node.position.y, 20.5);
+        assert_eq!(node.properties["url"], "https://example.com");
+        assert_eq!(node.extra["metadata"]["title"], "Fetch");
+
+        let connection = &data.connections[0];
+        assert_eq!(connection.source.node_id, "node-1");
+        assert_eq!(connection.target.port_id, "in");
+        assert_eq!(connection.extra["state"], "success");
+
+        let group = &data.groups[0];
+        assert_eq!(group.node_ids, ["node-1"]);
+        assert_eq!(group.extra["color"], "#fff");
+
+        // Unknown fields survive a round trip
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["nodes"][0]["metadata"]["title"], "Fetch");
+        assert_eq!(json["groups"][0]["nodeIds"][0], "node-1");
+    }
+
+    /// Serves `total` workflows a page at a time, failing at `fail_at_offset`
+    struct PagedWorkflows {
+        total: u32,
+        fail_at_offset: Option<u32>,
+    }
+
+    impl Respond for PagedWorkflows {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let param = |name: &str| {
+                request
+                    .url
+                    .query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.parse::<u32>().unwrap())
+                    .unwrap()
+            };
+            let (limit, offset) = (param("limit"), param("offset"));
+            if self.fail_at_offset == Some(offset) {
+                return ResponseTemplate::new(400);
+            }
+            let workflows: Vec<_> = (offset..(offset + limit).min(self.total))
+                .map(|i| serde_json::json!({ "id": format!("wf-{}", i) }))
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "workflows": workflows,
+                "total": self.total,
+                "limit": limit,
+                "offset": offset,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_all_workflows_fetches_every_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/orchestrator/workflows"))
+            .respond_with(PagedWorkflows {
+                total: 5,
+                fail_at_offset: None,
+            })
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let ids: Vec<String> = api
+            .list_all_workflows(2)
+            .map(|workflow| workflow.unwrap()["id"].as_str().unwrap().to_string())
+            .collect()
+            .await;
+        assert_eq!(ids, ["wf-0", "wf-1", "wf-2", "wf-3", "wf-4"]);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_all_workflows_stops_after_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/orchestrator/workflows"))
+            .respond_with(PagedWorkflows {
+                total: 5,
+                fail_at_offset: Some(2),
+            })
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let results: Vec<_> = api.list_all_workflows(2).collect().await;
+        assert_eq!(results.len(), 3);
+        assert!(results[..2].iter().all(|result| result.is_ok()));
+        assert!(matches!(
+            results[2],
+            Err(ZealError::ApiError { status: 400, .. })
+        ));
+
+        let results: Vec<_> = api.list_all_workflows(0).collect().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_workflow_states_collects_per_id_errors() {
+        let server = MockServer::start().await;
+        for id in ["wf-1", "wf-2"] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/api/zip/orchestrator/workflows/{}/state",
+                    id
+                )))
+                .respond_with(ResponseTemplate::new(200).set_body_json(workflow_state_json(id)))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/api/zip/orchestrator/workflows/wf-missing/state"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let batch = api
+            .get_workflow_states(&["wf-1", "wf-missing", "wf-2"], 2)
+            .await
+            .unwrap();
+
+        assert!(!batch.is_complete());
+        assert_eq!(batch.states.len(), 2);
+        assert_eq!(batch.states["wf-1"].workflow_id, "wf-1");
+        assert_eq!(batch.states["wf-2"].workflow_id, "wf-2");
+        assert!(matches!(
+            batch.errors.get("wf-missing"),
+            Some(ZealError::ApiError { status: 404, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_exists() {
+        let server = MockServer::start().await;
+        for (id, status) in [("wf-1", 200), ("wf-missing", 404), ("wf-forbidden", 403)] {
+            Mock::given(method("HEAD"))
+                .and(path(format!(
+                    "/api/zip/orchestrator/workflows/{}/state",
+                    id
+                )))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&server)
+                .await;
+        }
+
+        let api = OrchestratorAPI::new(&server.uri());
+        assert!(api.workflow_exists("wf-1").await.unwrap());
+        assert!(!api.workflow_exists("wf-missing").await.unwrap());
+
+        let err = api.workflow_exists("wf-forbidden").await.unwrap_err();
+        assert!(matches!(err, ZealError::ApiError { status: 403, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_delete_nodes_collects_per_node_errors() {
+        let server = MockServer::start().await;
+        for id in ["node-1", "node-2", "node-3"] {
+            Mock::given(method("DELETE"))
+                .and(path(format!("/api/zip/orchestrator/nodes/{}", id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "success": true,
+                    "message": format!("Node {} deleted", id)
+                })))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("DELETE"))
+            .and(path("/api/zip/orchestrator/nodes/node-missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri()).with_batch_concurrency(2);
+        let batch = api
+            .delete_nodes(
+                "wf-1",
+                &["node-1", "node-missing", "node-2", "node-3"],
+                None,
+            )
+            .await;
+
+        assert!(!batch.is_complete());
+        assert_eq!(batch.deleted.len(), 3);
+        assert_eq!(batch.deleted["node-2"].message, "Node node-2 deleted");
+        assert!(matches!(
+            batch.errors.get("node-missing"),
+            Some(ZealError::ApiError { status: 404, .. })
+        ));
+    }
+
+    /// In-memory graph served over the orchestrator endpoints used by `clear_graph`
+    struct FakeGraph {
+        nodes: Mutex<Vec<String>>,
+        connections: Mutex<Vec<String>>,
+        groups: Mutex<Vec<String>>,
+    }
+
+    impl FakeGraph {
+        fn new(nodes: &[&str], connections: &[&str], groups: &[&str]) -> Self {
+            let owned = |ids: &[&str]| Mutex::new(ids.iter().map(|id| id.to_string()).collect());
+            Self {
+                nodes: owned(nodes),
+                connections: owned(connections),
+                groups: owned(groups),
+            }
+        }
+    }
+
+    impl Respond for FakeGraph {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let entities = |list: &Mutex<Vec<String>>, entity: fn(&str) -> serde_json::Value| {
+                list.lock()
+                    .unwrap()
+                    .iter()
+                    .map(|id| entity(id))
+                    .collect::<Vec<_>>()
+            };
+            let remove = |list: &Mutex<Vec<String>>, id: &str| {
+                list.lock().unwrap().retain(|existing| existing != id);
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "success": true, "message": "removed" }))
+            };
+            let body = || serde_json::from_slice::<serde_json::Value>(&request.body).unwrap();
+            let path = request.url.path();
+
+            match request.method.to_string().as_str() {
+                "GET" => {
+                    let mut state = workflow_state_json("wf-1");
+                    state["state"] = serde_json::json!({
+                        "nodes": entities(&self.nodes, |id| serde_json::json!({
+                            "id": id,
+                            "type": "test",
+                            "position": { "x": 0.0, "y": 0.0 }
+                        })),
+                        "connections": entities(&self.connections, |id| serde_json::json!({
+                            "id": id,
+                            "source": { "nodeId": "a", "portId": "out" },
+                            "target": { "nodeId": "b", "portId": "in" }
+                        })),
+                        "groups": entities(&self.groups, |id| serde_json::json!({ "id": id })),
+                    });
+                    ResponseTemplate::new(200).set_body_json(state)
+                }
+                "DELETE" if path.ends_with("/connections") => {
+                    remove(&self.connections, body()["connectionId"].as_str().unwrap())
+                }
+                "DELETE" if path.ends_with("/groups") => {
+                    remove(&self.groups, body()["groupId"].as_str().unwrap())
+                }
+                "DELETE" => remove(&self.nodes, path.rsplit('/').next().unwrap()),
+                _ => ResponseTemplate::new(405),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clear_graph_removes_everything() {
+        let server = MockServer::start().await;
+        Mock::given(path_regex("^/api/zip/orchestrator/"))
+            .respond_with(FakeGraph::new(
+                &["node-1", "node-2", "node-3"],
+                &["conn-1", "conn-2"],
+                &["group-1"],
+            ))
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let cleared = api.clear_graph("wf-1", None).await.unwrap();
+        assert_eq!(
+            cleared,
+            ClearGraphResponse {
+                nodes_removed: 3,
+                connections_removed: 2,
+                groups_removed: 1,
+            }
+        );
+
+        let state = api.get_workflow_state("wf-1", None).await.unwrap();
+        assert!(state.state.nodes.is_empty());
+        assert!(state.state.connections.is_empty());
+        assert!(state.state.groups.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_add_node_encrypts_secret_properties() {
+        use crate::secrets::tests::XorEncryptor;
+        use std::sync::Arc;
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/nodes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "nodeId": "node-1",
+                "node": {
+                    "id": "node-1",
+                    "type": "http",
+                    "position": { "x": 0.0, "y": 0.0 },
+                    "metadata": {}
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let secrets = SecretProperties::new(Arc::new(XorEncryptor(0x42)), ["token"]);
+        let api = OrchestratorAPI::new(&server.uri()).with_secret_properties(secrets.clone());
+
+        let mut request = add_node_request("http");
+        request.property_values = Some(props(&[
+            ("token", serde_json::json!("s3cret")),
+            ("url", serde_json::json!("https://example.com")),
+        ]));
+        api.add_node(request).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let token = body["propertyValues"]["token"].as_str().unwrap();
+        assert!(token.starts_with(crate::secrets::ENCRYPTED_PREFIX));
+        assert!(!token.contains("s3cret"));
+        assert_eq!(body["propertyValues"]["url"], "https://example.com");
+
+        let mut stored: HashMap<String, serde_json::Value> =
+            serde_json::from_value(body["propertyValues"].clone()).unwrap();
+        secrets.decrypt(&mut stored).unwrap();
+        assert_eq!(stored["token"], "s3cret");
+    }
+
+    #[tokio::test]
+    async fn test_get_workflow_state_fields_projects_sections() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/orchestrator/workflows/wf-1/state"))
+            .and(query_param("fields", "nodes,groups"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "workflowId": "wf-1",
+                "graphId": "main",
+                "name": "Layout",
+                "description": "",
+                "version": 1,
+                "state": {
+                    "nodes": [{
+                        "id": "node-1",
+                        "type": "http",
+                        "position": { "x": 10.0, "y": 20.0 }
+                    }],
+                    "groups": []
+                },
+                "metadata": {}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let state = api
+            .get_workflow_state_fields(
+                "wf-1",
+                None,
+                &[StateField::Groups, StateField::Nodes, StateField::Nodes],
+            )
+            .await
+            .unwrap();
+        assert_eq!(state.state.nodes.len(), 1);
+        assert_eq!(state.state.nodes[0].position.y, 20.0);
+        assert!(state.state.connections.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_workflow_state_requests_all_sections_by_default() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/orchestrator/workflows/wf-1/state"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "workflowId": "wf-1",
+                "graphId": "main",
+                "name": "Full",
+                "description": "",
+                "version": 1,
+                "state": { "nodes": [], "connections": [], "groups": [] },
+                "metadata": {}
+            })))
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        api.get_workflow_state("wf-1", None).await.unwrap();
+        api.get_workflow_state_fields("wf-1", None, &[])
+            .await
+            .unwrap();
+
+        for request in server.received_requests().await.unwrap() {
+            assert!(!request.url.query_pairs().any(|(key, _)| key == "fields"));
+            assert!(request
+                .url
+                .query_pairs()
+                .any(|(key, value)| key == "graphId" && value == "main"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_workflow_states_rejects_zero_concurrency() {
+        let api = OrchestratorAPI::new("http://localhost:3000");
+        let result = api.get_workflow_states(&["wf-1"], 0).await;
+        assert!(matches!(result, Err(ZealError::ValidationError { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_update_workflow_sends_only_provided_fields() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/zip/orchestrator/workflows/wf-1"))
+            .and(body_json(serde_json::json!({ "name": "Renamed" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "workflow": { "id": "wf-1", "name": "Renamed" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let response = api
+            .update_workflow(
+                "wf-1",
+                UpdateWorkflowRequest {
+                    name: Some("Renamed".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.workflow["name"], "Renamed");
+    }
+
+    #[tokio::test]
+    async fn test_update_workflow_metadata_only() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/zip/orchestrator/workflows/wf-1"))
+            .and(body_json(
+                serde_json::json!({ "metadata": { "owner": "ops" } }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "workflow": { "id": "wf-1" }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let mut metadata = HashMap::new();
+        metadata.insert("owner".to_string(), serde_json::json!("ops"));
+        let response = api
+            .update_workflow(
+                "wf-1",
+                UpdateWorkflowRequest {
+                    metadata: Some(metadata),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(response.success);
+    }
+
+    #[tokio::test]
+    async fn test_update_workflow_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/zip/orchestrator/workflows/wf-missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let result = api
+            .update_workflow("wf-missing", UpdateWorkflowRequest::default())
+            .await;
+
+        assert!(matches!(result, Err(ZealError::NotFound { .. })));
+    }
+
+    /// Creates numbered nodes and cancels the batch after `cancel_after` nodes
+    struct CancellingNodeResponder {
+        created: AtomicUsize,
+        cancel_after: usize,
+        cancel: CancellationToken,
+    }
+
+    impl Respond for CancellingNodeResponder {
+        fn respond(&self, _request: &Request) -> ResponseTemplate {
+            let n = self.created.fetch_add(1, Ordering::SeqCst) + 1;
+            if n == self.cancel_after {
+                self.cancel.cancel();
+            }
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "nodeId": format!("node-{}", n),
+                "node": {
+                    "id": format!("node-{}", n),
+                    "type": "test",
+                    "position": { "x": 0.0, "y": 0.0 },
+                    "metadata": {}
+                }
+            }))
+        }
+    }
+
+    fn add_node_request(template_id: &str) -> AddNodeRequest {
+        AddNodeRequest {
+            workflow_id: "wf-1".to_string(),
+            graph_id: None,
+            template_id: template_id.to_string(),
+            position: Position { x: 0.0, y: 0.0 },
+            property_values: None,
+        }
+    }
+
+    fn props(pairs: &[(&str, serde_json::Value)]) -> HashMap<String, serde_json::Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_property_diff() {
+        let current = props(&[
+            ("same", serde_json::json!(1)),
+            ("changed", serde_json::json!("old")),
+            ("removed", serde_json::json!(true)),
+        ]);
+        let desired = props(&[
+            ("same", serde_json::json!(1)),
+            ("changed", serde_json::json!("new")),
+            ("added", serde_json::json!([1, 2])),
+        ]);
+
+        let diff = property_diff(&current, &desired);
+        assert_eq!(
+            diff,
+            props(&[
+                ("changed", serde_json::json!("new")),
+                ("added", serde_json::json!([1, 2])),
+                ("removed", serde_json::Value::Null),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_node_diff_sends_only_changed_keys() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/api/zip/orchestrator/nodes/node-1"))
+            .and(body_json(serde_json::json!({
+                "workflowId": "wf-1",
+                "properties": { "url": "https://new.example.com", "retries": null }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "success": true })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let current = props(&[
+            ("url", serde_json::json!("https://old.example.com")),
+            ("method", serde_json::json!("GET")),
+            ("retries", serde_json::json!(3)),
+        ]);
+        let desired = props(&[
+            ("url", serde_json::json!("https://new.example.com")),
+            ("method", serde_json::json!("GET")),
+        ]);
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let response = api
+            .update_node_diff("node-1", "wf-1", None, &current, &desired)
+            .await
+            .unwrap();
+        assert!(response.unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_update_node_diff_skips_unchanged() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let current = props(&[("url", serde_json::json!("https://example.com"))]);
+        let api = OrchestratorAPI::new(&server.uri());
+        let response = api
+            .update_node_diff("node-1", "wf-1", None, &current, &current.clone())
+            .await
+            .unwrap();
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_add_nodes_cancelled_rolls_back_created_nodes() {
+        let server = MockServer::start().await;
+        let cancel = CancellationToken::new();
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/nodes"))
+            .respond_with(CancellingNodeResponder {
+                created: AtomicUsize::new(0),
+                cancel_after: 2,
+                cancel: cancel.clone(),
+            })
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"^/api/zip/orchestrator/nodes/node-\d+$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "message": "deleted"
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let requests = (0..5)
+            .map(|i| add_node_request(&format!("t{}", i)))
+            .collect();
+        let err = api.add_nodes(requests, &cancel).await.unwrap_err();
+        assert!(matches!(err, ZealError::Cancelled { .. }));
+
+        let deleted: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == wiremock::http::Method::Delete)
+            .map(|r| r.url.path().to_string())
+            .collect();
+        assert_eq!(
+            deleted,
+            vec![
+                "/api/zip/orchestrator/nodes/node-2",
+                "/api/zip/orchestrator/nodes/node-1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_nodes_completes_without_cancellation() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/nodes"))
+            .respond_with(CancellingNodeResponder {
+                created: AtomicUsize::new(0),
+                cancel_after: usize::MAX,
+                cancel: CancellationToken::new(),
+            })
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let requests = (0..3)
+            .map(|i| add_node_request(&format!("t{}", i)))
+            .collect();
+        let nodes = api
+            .add_nodes(requests, &CancellationToken::new())
+            .await
+            .unwrap();
+        let ids: Vec<_> = nodes.iter().map(|n| n.node_id.as_str()).collect();
+        assert_eq!(ids, vec!["node-1", "node-2", "node-3"]);
+    }
+
+    #[tokio::test]
+    async fn test_add_nodes_best_effort_reports_per_item_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/nodes/batch"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "results": [
+                    {
+                        "nodeId": "node-1",
+                        "node": {
+                            "id": "node-1",
+                            "type": "test",
+                            "position": { "x": 0.0, "y": 0.0 },
+                            "metadata": {}
+                        }
+                    },
+                    { "error": "unknown template", "status": 422, "code": "TEMPLATE_NOT_FOUND" }
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/nodes"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let api = OrchestratorAPI::new(&server.uri());
+        let results = api
+            .add_nodes_best_effort(vec![add_node_request("t1"), add_node_request("missing")])
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap().node_id, "node-1");
+        assert!(matches!(
+            &results[1],
+            Err(ZealError::ApiError { status: 422, error_code: Some(code), .. })
+                if code == "TEMPLATE_NOT_FOUND"
+        ));
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["nodes"][1]["templateId"], "missing");
+    }
+
+    /// Creates a connection named after its source node; fails for "bad"
+    struct ConnectionResponder;
+
+    impl Respond for ConnectionResponder {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let source = body["source"]["nodeId"].as_str().unwrap();
+            if source == "bad" {
+                return ResponseTemplate::new(400).set_body_string("invalid port");
+            }
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "connectionId": format!("conn-{}", source),
+                "connection": {}
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_nodes_best_effort_falls_back_to_individual_requests() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/connections/batch"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/orchestrator/connections"))
+            .respond_with(ConnectionResponder)
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let request = |source: &str| ConnectNodesRequest {
+            workflow_id: "wf-1".to_string(),
+            graph_id: None,
+            source: NodePort {
+                node_id: source.to_string(),
+                port_id: "out".to_string(),
+            },
+            target: NodePort {
+                node_id: "sink".to_string(),
+                port_id: "in".to_string(),
+            },
+        };
+        let api = OrchestratorAPI::new(&server.uri()).with_batch_concurrency(2);
+        let results = api
+            .connect_nodes_best_effort(vec![
+                request("a"),
+                request("bad"),
+                request("c"),
+                request("d"),
+            ])
+            .await
+            .unwrap();
+
+        let outcome: Vec<_> = results
+            .iter()
+            .map(|result| match result {
+                Ok(connection) => connection.connection_id.clone(),
+                Err(err) => err.to_string(),
+            })
+            .collect();
+        assert_eq!(outcome[0], "conn-a");
+        assert!(matches!(
+            results[1],
+            Err(ZealError::ApiError { status: 400, .. })
+        ));
+        assert_eq!(outcome[2..], ["conn-c", "conn-d"]);
+    }
+}
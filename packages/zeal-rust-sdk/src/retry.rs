@@ -0,0 +1,336 @@
+//! Retry with exponential backoff driven by [`RetryConfig`]
+
+use crate::config::{RetryBudgetConfig, RetryConfig};
+use crate::errors::{Result, ZealError};
+use rand::Rng;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::debug;
+
+/// Thousandths of a token, so deposits of a fractional `retry_ratio` add up
+/// exactly
+const TOKEN: u64 = 1000;
+
+/// Shared retry token bucket, see [`RetryBudgetConfig`]
+#[derive(Debug)]
+pub struct RetryBudget {
+    /// Available balance in thousandths of a token
+    balance: AtomicU64,
+    deposit: u64,
+    capacity: u64,
+}
+
+impl RetryBudget {
+    /// Create a full bucket
+    pub fn new(config: &RetryBudgetConfig) -> Self {
+        let capacity = config.burst as u64 * TOKEN;
+        Self {
+            balance: AtomicU64::new(capacity),
+            deposit: (config.retry_ratio.max(0.0) * TOKEN as f64).round() as u64,
+            capacity,
+        }
+    }
+
+    /// Record a successful request
+    pub fn deposit(&self) {
+        let _ = self
+            .balance
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |balance| {
+                Some((balance + self.deposit).min(self.capacity))
+            });
+    }
+
+    /// Whether there is a token for a retry, without spending it
+    pub fn can_retry(&self) -> bool {
+        self.balance.load(Ordering::SeqCst) >= TOKEN
+    }
+
+    /// Spend a token on a retry, returning false if the budget is exhausted
+    pub fn try_withdraw(&self) -> bool {
+        self.balance
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |balance| {
+                balance.checked_sub(TOKEN)
+            })
+            .is_ok()
+    }
+}
+
+/// Run `op` until it succeeds, fails with an error that should not be
+/// retried, or `config.max_attempts` attempts have been made.
+///
+/// Errors are retried when [`ZealError::is_retryable`] is true or when they are
+/// API errors with one of `config.retryable_status_codes`. The delay before
+/// each retry is the error's [`retry_after`](ZealError::retry_after) if set,
+/// capped at `config.max_delay`, otherwise exponential backoff with jitter.
+pub async fn retry_with_config<F, Fut, T>(config: &RetryConfig, op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    retry_with_budget(config, None, op).await
+}
+
+/// Like [`retry_with_config`], but every retry spends a token from `budget`
+/// and every success deposits one back. A failure is returned as-is once the
+/// budget is exhausted.
+pub async fn retry_with_budget<F, Fut, T>(
+    config: &RetryConfig,
+    budget: Option<&RetryBudget>,
+    op: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    retry_with_budget_if(config, budget, op, |err| should_retry(config, err)).await
+}
+
+/// Like [`retry_with_budget`], but only errors for which `retryable` returns
+/// true are retried
+pub(crate) async fn retry_with_budget_if<F, Fut, T, R>(
+    config: &RetryConfig,
+    budget: Option<&RetryBudget>,
+    mut op: F,
+    retryable: R,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    R: Fn(&ZealError) -> bool,
+{
+    let mut attempt = 1;
+    loop {
+        let err = match op().await {
+            Ok(value) => {
+                if let Some(budget) = budget {
+                    budget.deposit();
+                }
+                return Ok(value);
+            }
+            Err(err) => err,
+        };
+
+        if attempt >= config.max_attempts || !retryable(&err) {
+            return Err(err);
+        }
+        if budget.is_some_and(|budget| !budget.try_withdraw()) {
+            debug!("Retry budget exhausted, not retrying ({})", err);
+            return Err(err);
+        }
+
+        let delay = err
+            .retry_after()
+            .map(|delay| delay.min(config.max_delay))
+            .unwrap_or_else(|| backoff_delay(config, attempt));
+        debug!(
+            "Attempt {} failed ({}), retrying in {:?}",
+            attempt, err, delay
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Check whether an error should be retried under the given config
+pub fn should_retry(config: &RetryConfig, err: &ZealError) -> bool {
+    match err {
+        ZealError::ApiError { status, .. } if config.retryable_status_codes.contains(status) => {
+            true
+        }
+        _ => err.is_retryable(),
+    }
+}
+
+/// Delay after the given (1-based) failed attempt: `initial_delay` multiplied
+/// by `backoff_multiplier` per attempt, capped at `max_delay`, then randomized
+/// by up to `jitter_factor` in either direction
+pub fn backoff_delay(config: &RetryConfig, attempt: usize) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(i32::MAX as usize) as i32;
+    let base = config.initial_delay.as_secs_f64() * config.backoff_multiplier.powi(exponent);
+    let capped = base.min(config.max_delay.as_secs_f64());
+
+    let jitter = config.jitter_factor.clamp(0.0, 1.0);
+    let factor = if jitter > 0.0 {
+        1.0 + rand::thread_rng().gen_range(-jitter..=jitter)
+    } else {
+        1.0
+    };
+
+    Duration::from_secs_f64((capped * factor).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn config(max_attempts: usize) -> RetryConfig {
+        RetryConfig {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            jitter_factor: 0.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let config = RetryConfig {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+            backoff_multiplier: 2.0,
+            jitter_factor: 0.0,
+            ..Default::default()
+        };
+        let delays: Vec<_> = (1..=4)
+            .map(|attempt| backoff_delay(&config, attempt).as_millis())
+            .collect();
+        assert_eq!(delays, [100, 200, 350, 350]);
+
+        let jittered = backoff_delay(
+            &RetryConfig {
+                jitter_factor: 0.5,
+                ..config
+            },
+            1,
+        );
+        assert!(jittered >= Duration::from_millis(50) && jittered <= Duration::from_millis(150));
+    }
+
+    #[tokio::test]
+    async fn test_retries_retryable_errors_until_success() {
+        let calls = AtomicUsize::new(0);
+        let result = retry_with_config(&config(3), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(ZealError::api_error(503, "Unavailable".to_string(), None)),
+                1 => Err(ZealError::timeout_error("request")),
+                _ => Ok("done"),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stops_on_non_retryable_error_and_after_max_attempts() {
+        let calls = AtomicUsize::new(0);
+        let err = retry_with_config(&config(3), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ZealError::api_error(400, "Bad".to_string(), None))
+        })
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ZealError::ApiError { status: 400, .. }));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let calls = AtomicUsize::new(0);
+        retry_with_config(&config(2), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ZealError::connection_error("refused"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_capped_at_max_delay() {
+        let calls = AtomicUsize::new(0);
+        let started = tokio::time::Instant::now();
+        let result = retry_with_config(&config(2), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(ZealError::rate_limit_error(
+                    "Slow down",
+                    Some(Duration::from_secs(3600)),
+                )),
+                _ => Ok(()),
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_retry_budget_tokens() {
+        let budget = RetryBudget::new(&RetryBudgetConfig {
+            retry_ratio: 0.1,
+            burst: 2,
+        });
+        assert!(budget.try_withdraw());
+        assert!(budget.try_withdraw());
+        assert!(!budget.can_retry());
+        assert!(!budget.try_withdraw());
+
+        // Ten completed requests earn one retry
+        for _ in 0..9 {
+            budget.deposit();
+        }
+        assert!(!budget.can_retry());
+        budget.deposit();
+        assert!(budget.try_withdraw());
+
+        // Deposits never exceed the burst
+        for _ in 0..100 {
+            budget.deposit();
+        }
+        assert!(budget.try_withdraw());
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_curtails_failure_spike() {
+        let budget = RetryBudget::new(&RetryBudgetConfig {
+            retry_ratio: 0.1,
+            burst: 3,
+        });
+        let calls = AtomicUsize::new(0);
+        for _ in 0..20 {
+            retry_with_budget(&config(3), Some(&budget), || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err::<(), _>(ZealError::connection_error("refused"))
+            })
+            .await
+            .unwrap_err();
+        }
+        // 20 first attempts plus the 3 retries in the burst, instead of 60 calls
+        assert_eq!(calls.load(Ordering::SeqCst), 23);
+
+        // Successes refill the budget
+        for _ in 0..10 {
+            retry_with_budget(&config(3), Some(&budget), || async { Ok(()) })
+                .await
+                .unwrap();
+        }
+        let calls = AtomicUsize::new(0);
+        retry_with_budget(&config(3), Some(&budget), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ZealError::connection_error("refused"))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_configured_status_codes_are_retried() {
+        let config = RetryConfig {
+            retryable_status_codes: vec![409],
+            ..Default::default()
+        };
+        assert!(should_retry(
+            &config,
+            &ZealError::api_error(409, "Conflict".to_string(), None)
+        ));
+        assert!(!should_retry(
+            &config,
+            &ZealError::api_error(404, "Missing".to_string(), None)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! Runtime descriptors and matching against node template requirements
+
+use crate::errors::ZealError;
+use crate::types::NodeTemplate;
+use semver::Version;
+
+/// Describes a runtime that executes nodes
+#[derive(Debug, Clone)]
+pub struct Runtime {
+    /// Executor name, matched against `RuntimeRequirements::executor`
+    pub executor: String,
+    /// Runtime version, checked against `RuntimeRequirements::version`
+    pub version: Version,
+    /// Capabilities provided by this runtime
+    pub capabilities: Vec<String>,
+}
+
+/// Reason a runtime cannot execute a node template
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CapabilityMismatch {
+    #[error("Template requires executor '{required}' but runtime is '{actual}'")]
+    Executor { required: String, actual: String },
+
+    #[error("Template requires version '{required}' but runtime is {actual}")]
+    Version { required: String, actual: Version },
+
+    #[error("Runtime is missing capabilities: {}", missing.join(", "))]
+    MissingCapabilities { missing: Vec<String> },
+
+    #[error("Template has an invalid version requirement: {reason}")]
+    InvalidVersionRequirement { required: String, reason: String },
+}
+
+impl Runtime {
+    /// Create a runtime descriptor
+    pub fn new(executor: impl Into<String>, version: Version, capabilities: Vec<String>) -> Self {
+        Self {
+            executor: executor.into(),
+            version,
+            capabilities,
+        }
+    }
+
+    /// Check whether this runtime satisfies a template's runtime requirements.
+    ///
+    /// Templates without runtime requirements can be executed by any runtime.
+    pub fn can_execute(&self, template: &NodeTemplate) -> Result<(), CapabilityMismatch> {
+        let Some(requirements) = &template.runtime else {
+            return Ok(());
+        };
+
+        if requirements.executor != self.executor {
+            return Err(CapabilityMismatch::Executor {
+                required: requirements.executor.clone(),
+                actual: self.executor.clone(),
+            });
+        }
+
+        let version_req = requirements.version_req().map_err(|e| {
+            CapabilityMismatch::InvalidVersionRequirement {
+                required: requirements.version.clone().unwrap_or_default(),
+                reason: match e {
+                    ZealError::ValidationError { message, .. } => message,
+                    other => other.to_string(),
+                },
+            }
+        })?;
+        if let (Some(req), Some(required)) = (version_req, &requirements.version) {
+            if !req.matches(&self.version) {
+                return Err(CapabilityMismatch::Version {
+                    required: required.clone(),
+                    actual: self.version.clone(),
+                });
+            }
+        }
+
+        let missing: Vec<String> = requirements
+            .capabilities
+            .iter()
+            .flatten()
+            .filter(|capability| !self.capabilities.contains(capability))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(CapabilityMismatch::MissingCapabilities { missing });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::RuntimeRequirements;
+
+    fn template(runtime: Option<RuntimeRequirements>) -> NodeTemplate {
+        NodeTemplate {
+            id: "node".to_string(),
+            type_name: "processor".to_string(),
+            title: "Node".to_string(),
+            subtitle: None,
+            category: "processing".to_string(),
+            subcategory: None,
+            description: String::new(),
+            icon: "box".to_string(),
+            variant: None,
+            shape: None,
+            size: None,
+            ports: vec![],
+            properties: None,
+            property_rules: None,
+            runtime,
+            display: None,
+        }
+    }
+
+    fn requirements(version: Option<&str>, capabilities: &[&str]) -> RuntimeRequirements {
+        RuntimeRequirements {
+            executor: "rust".to_string(),
+            version: version.map(str::to_string),
+            required_env_vars: None,
+            capabilities: Some(capabilities.iter().map(|c| c.to_string()).collect()),
+        }
+    }
+
+    fn runtime() -> Runtime {
+        Runtime::new(
+            "rust",
+            Version::new(1, 4, 0),
+            vec!["http".to_string(), "gpu".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_can_execute_matching_template() {
+        let template = template(Some(requirements(Some(">=1.0.0, <2.0.0"), &["http"])));
+        assert_eq!(runtime().can_execute(&template), Ok(()));
+    }
+
+    #[test]
+    fn test_can_execute_without_requirements() {
+        assert_eq!(runtime().can_execute(&template(None)), Ok(()));
+    }
+
+    #[test]
+    fn test_executor_mismatch() {
+        let mut reqs = requirements(None, &[]);
+        reqs.executor = "python".to_string();
+
+        assert_eq!(
+            runtime().can_execute(&template(Some(reqs))),
+            Err(CapabilityMismatch::Executor {
+                required: "python".to_string(),
+                actual: "rust".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_version_mismatch() {
+        let template = template(Some(requirements(Some(">=2.0.0"), &[])));
+        assert!(matches!(
+            runtime().can_execute(&template),
+            Err(CapabilityMismatch::Version { .. })
+        ));
+    }
+
+    #[test]
+    fn test_version_requirement_satisfied() {
+        for req in ["1.4.0", "^1", "~1.4", ">=1.0.0, <2.0.0", "*"] {
+            let template = template(Some(requirements(Some(req), &[])));
+            assert_eq!(runtime().can_execute(&template), Ok(()), "{}", req);
+        }
+    }
+
+    #[test]
+    fn test_version_requirement_unsatisfied() {
+        for req in ["=1.3.9", "^2", "<1.4.0", ">=1.5.0, <2.0.0"] {
+            let template = template(Some(requirements(Some(req), &[])));
+            assert_eq!(
+                runtime().can_execute(&template),
+                Err(CapabilityMismatch::Version {
+                    required: req.to_string(),
+                    actual: Version::new(1, 4, 0),
+                }),
+                "{}",
+                req
+            );
+        }
+    }
+
+    #[test]
+    fn test_malformed_version_requirement() {
+        let reqs = requirements(Some("latest and greatest"), &[]);
+        assert!(reqs.version_req().is_err());
+
+        let result = runtime().can_execute(&template(Some(reqs)));
+        assert!(matches!(
+            result,
+            Err(CapabilityMismatch::InvalidVersionRequirement { ref required, .. })
+                if required == "latest and greatest"
+        ));
+    }
+
+    #[test]
+    fn test_missing_capabilities() {
+        let template = template(Some(requirements(None, &["http", "fs", "network"])));
+        assert_eq!(
+            runtime().can_execute(&template),
+            Err(CapabilityMismatch::MissingCapabilities {
+                missing: vec!["fs".to_string(), "network".to_string()],
+            })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Client-side encryption of secret node property values
+//!
+//! Properties declared with [`PropertyType::Secret`] in a node template are
+//! encrypted by the orchestrator before they are sent in `add_node` or
+//! `update_node`, so the server only ever stores ciphertext. The cipher is
+//! supplied by the application through [`SecretEncryptor`] (AES-GCM, a KMS, ...).
+
+use crate::errors::{Result, ZealError};
+use crate::types::{NodeTemplate, PropertyType};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Prefix marking a property value as ciphertext produced by [`SecretProperties`]
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Encrypts and decrypts secret property values
+pub trait SecretEncryptor: Send + Sync + Debug {
+    /// Encrypt plaintext into a string that is safe to send as JSON
+    fn encrypt(&self, plaintext: &[u8]) -> Result<String>;
+
+    /// Decrypt a string previously returned by [`encrypt`](Self::encrypt)
+    fn decrypt(&self, ciphertext: &str) -> Result<Vec<u8>>;
+}
+
+/// The set of secret property names and the encryptor used for them
+#[derive(Debug, Clone)]
+pub struct SecretProperties {
+    encryptor: Arc<dyn SecretEncryptor>,
+    fields: HashSet<String>,
+}
+
+impl SecretProperties {
+    /// Treat the given property names as secrets
+    pub fn new<I, S>(encryptor: Arc<dyn SecretEncryptor>, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            encryptor,
+            fields: fields.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Treat every property declared as [`PropertyType::Secret`] in any of the
+    /// templates as a secret. Property names are matched across templates.
+    pub fn from_templates(encryptor: Arc<dyn SecretEncryptor>, templates: &[NodeTemplate]) -> Self {
+        let fields = templates
+            .iter()
+            .filter_map(|template| template.properties.as_ref())
+            .flatten()
+            .filter(|(_, definition)| matches!(definition.property_type, PropertyType::Secret))
+            .map(|(name, _)| name.clone());
+
+        Self::new(encryptor, fields)
+    }
+
+    /// Check whether a property name is a secret
+    pub fn is_secret(&self, name: &str) -> bool {
+        self.fields.contains(name)
+    }
+
+    /// Encrypt secret values in place. Null values (cleared properties) and
+    /// values that are already encrypted are left untouched.
+    pub fn encrypt(&self, properties: &mut HashMap<String, serde_json::Value>) -> Result<()> {
+        for (name, value) in properties.iter_mut() {
+            if !self.is_secret(name) || value.is_null() || is_encrypted(value) {
+                continue;
+            }
+
+            let plaintext = serde_json::to_vec(value)?;
+            let ciphertext = self.encryptor.encrypt(&plaintext)?;
+            *value = serde_json::Value::String(format!("{}{}", ENCRYPTED_PREFIX, ciphertext));
+        }
+        Ok(())
+    }
+
+    /// Decrypt every encrypted value in place
+    pub fn decrypt(&self, properties: &mut HashMap<String, serde_json::Value>) -> Result<()> {
+        for (name, value) in properties.iter_mut() {
+            let Some(ciphertext) = value
+                .as_str()
+                .and_then(|v| v.strip_prefix(ENCRYPTED_PREFIX))
+            else {
+                continue;
+            };
+
+            let plaintext = self.encryptor.decrypt(ciphertext)?;
+            *value = serde_json::from_slice(&plaintext).map_err(|e| {
+                ZealError::other(format!("Invalid decrypted value for '{}': {}", name, e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn is_encrypted(value: &serde_json::Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|v| v.starts_with(ENCRYPTED_PREFIX))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use base64::Engine;
+
+    /// Reversible stand-in for a real cipher
+    #[derive(Debug)]
+    pub(crate) struct XorEncryptor(pub(crate) u8);
+
+    impl SecretEncryptor for XorEncryptor {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<String> {
+            let bytes: Vec<u8> = plaintext.iter().map(|b| b ^ self.0).collect();
+            Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> Result<Vec<u8>> {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(ciphertext)
+                .map_err(|e| ZealError::other(e.to_string()))?;
+            Ok(bytes.iter().map(|b| b ^ self.0).collect())
+        }
+    }
+
+    #[test]
+    fn test_round_trip_only_touches_secrets() {
+        let secrets = SecretProperties::new(Arc::new(XorEncryptor(0x5a)), ["apiKey"]);
+        let original: HashMap<String, serde_json::Value> = [
+            ("apiKey".to_string(), serde_json::json!("sk-live-123")),
+            ("retries".to_string(), serde_json::json!(3)),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut properties = original.clone();
+        secrets.encrypt(&mut properties).unwrap();
+        let encrypted = properties["apiKey"].as_str().unwrap();
+        assert!(encrypted.starts_with(ENCRYPTED_PREFIX));
+        assert!(!encrypted.contains("sk-live-123"));
+        assert_eq!(properties["retries"], serde_json::json!(3));
+
+        // Encrypting twice does not double-wrap
+        let once = properties.clone();
+        secrets.encrypt(&mut properties).unwrap();
+        assert_eq!(properties, once);
+
+        secrets.decrypt(&mut properties).unwrap();
+        assert_eq!(properties, original);
+    }
+}
@@ -0,0 +1,465 @@
+//! Correlation of node execution events into spans
+//!
+//! [`ExecutionTracker`] pairs `node.executing` with the matching
+//! `node.completed`/`node.failed` event for the same node within an execution
+//! session and produces a [`NodeSpan`] with the measured duration.
+
+use crate::events::{ZipEventBase, ZipExecutionEvent, ZipWebhookEvent};
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Outcome of a node span
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanStatus {
+    Completed,
+    Failed,
+    /// The start or end event never arrived before the span timed out
+    Incomplete,
+}
+
+/// A single node execution, from `node.executing` to its end event
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSpan {
+    pub node_id: String,
+    /// Execution session the node ran in, if known
+    pub session_id: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub status: SpanStatus,
+    /// Measured duration, or the duration reported by the end event when the
+    /// start is unknown
+    pub duration: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SpanKey {
+    workflow_id: String,
+    session_id: Option<String>,
+    node_id: String,
+}
+
+#[derive(Debug)]
+struct SpanEnd {
+    at: DateTime<Utc>,
+    status: SpanStatus,
+    reported_ms: Option<u64>,
+}
+
+#[derive(Debug)]
+struct PendingSpan {
+    start: Option<DateTime<Utc>>,
+    end: Option<SpanEnd>,
+    /// Timestamp of the first event seen for this span, used for timeouts
+    first_seen: DateTime<Utc>,
+}
+
+/// Correlates node lifecycle events into [`NodeSpan`]s.
+///
+/// Node events are attributed to the session of the most recent
+/// `execution.started` event for their workflow, unless they carry a
+/// `sessionId` in their metadata. End events that arrive before their start
+/// event are held until the start arrives. Spans still open after `timeout`
+/// are emitted as [`SpanStatus::Incomplete`].
+#[derive(Debug)]
+pub struct ExecutionTracker {
+    timeout: Duration,
+    sessions: HashMap<String, String>,
+    pending: HashMap<SpanKey, PendingSpan>,
+}
+
+impl ExecutionTracker {
+    /// Create a tracker that gives up on open spans after `timeout`
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            sessions: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Number of spans waiting for their start or end event
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Process one event, returning any spans it closes
+    pub fn record(&mut self, event: &ZipWebhookEvent) -> Vec<NodeSpan> {
+        let ZipWebhookEvent::Execution(event) = event else {
+            return Vec::new();
+        };
+
+        match event {
+            ZipExecutionEvent::ExecutionStarted(e) => {
+                self.sessions
+                    .insert(e.base.workflow_id.clone(), e.session_id.clone());
+                Vec::new()
+            }
+            ZipExecutionEvent::NodeExecuting(e) => {
+                let key = self.key(&e.base, &e.node_id);
+                self.start(key, parse_timestamp(&e.base.timestamp))
+            }
+            ZipExecutionEvent::NodeCompleted(e) => {
+                let key = self.key(&e.base, &e.node_id);
+                let end = SpanEnd {
+                    at: parse_timestamp(&e.base.timestamp),
+                    status: SpanStatus::Completed,
+                    reported_ms: e.duration,
+                };
+                self.end(key, end)
+            }
+            ZipExecutionEvent::NodeFailed(e) => {
+                let key = self.key(&e.base, &e.node_id);
+                let end = SpanEnd {
+                    at: parse_timestamp(&e.base.timestamp),
+                    status: SpanStatus::Failed,
+                    reported_ms: None,
+                };
+                self.end(key, end)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Emit spans that have been open for longer than the timeout as incomplete
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<NodeSpan> {
+        let timeout = chrono::Duration::from_std(self.timeout).unwrap_or(chrono::Duration::MAX);
+        let expired: Vec<SpanKey> = self
+            .pending
+            .iter()
+            .filter(|(_, span)| now - span.first_seen >= timeout)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|key| {
+                let span = self.pending.remove(&key)?;
+                Some(incomplete(key, span))
+            })
+            .collect()
+    }
+
+    /// Emit every open span as incomplete
+    pub fn flush(&mut self) -> Vec<NodeSpan> {
+        self.pending
+            .drain()
+            .map(|(key, span)| incomplete(key, span))
+            .collect()
+    }
+
+    /// Consume an event stream and yield spans as they close.
+    ///
+    /// Open spans are checked against the timeout whenever the stream is idle
+    /// for that long, and flushed as incomplete when the stream ends.
+    pub fn track<S>(self, events: S) -> impl Stream<Item = NodeSpan>
+    where
+        S: Stream<Item = ZipWebhookEvent> + Unpin,
+    {
+        let state = (self, events, VecDeque::new(), false);
+        futures_util::stream::unfold(
+            state,
+            |(mut tracker, mut events, mut ready, mut done)| async move {
+                loop {
+                    if let Some(span) = ready.pop_front() {
+                        return Some((span, (tracker, events, ready, done)));
+                    }
+                    if done {
+                        return None;
+                    }
+
+                    match tokio::time::timeout(tracker.timeout, events.next()).await {
+                        Ok(Some(event)) => {
+                            ready.extend(tracker.record(&event));
+                            ready.extend(tracker.expire(Utc::now()));
+                        }
+                        Ok(None) => {
+                            ready.extend(tracker.flush());
+                            done = true;
+                        }
+                        Err(_) => ready.extend(tracker.expire(Utc::now())),
+                    }
+                }
+            },
+        )
+    }
+
+    fn key(&self, base: &ZipEventBase, node_id: &str) -> SpanKey {
+        let session_id = base
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("sessionId"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| self.sessions.get(&base.workflow_id).cloned());
+
+        SpanKey {
+            workflow_id: base.workflow_id.clone(),
+            session_id,
+            node_id: node_id.to_string(),
+        }
+    }
+
+    fn start(&mut self, key: SpanKey, at: DateTime<Utc>) -> Vec<NodeSpan> {
+        let mut closed = Vec::new();
+
+        match self.pending.remove(&key) {
+            // End event arrived first
+            Some(PendingSpan {
+                start: None,
+                end: Some(end),
+                ..
+            }) => {
+                closed.push(complete(key, at, end));
+                return closed;
+            }
+            // A new start for a node that never finished
+            Some(previous) => closed.push(incomplete(key.clone(), previous)),
+            None => {}
+        }
+
+        self.pending.insert(
+            key,
+            PendingSpan {
+                start: Some(at),
+                end: None,
+                first_seen: at,
+            },
+        );
+        closed
+    }
+
+    fn end(&mut self, key: SpanKey, end: SpanEnd) -> Vec<NodeSpan> {
+        match self.pending.remove(&key) {
+            Some(PendingSpan {
+                start: Some(start), ..
+            }) => vec![complete(key, start, end)],
+            previous => {
+                let mut closed = Vec::new();
+                if let Some(previous) = previous {
+                    closed.push(incomplete(key.clone(), previous));
+                }
+                let first_seen = end.at;
+                self.pending.insert(
+                    key,
+                    PendingSpan {
+                        start: None,
+                        end: Some(end),
+                        first_seen,
+                    },
+                );
+                closed
+            }
+        }
+    }
+}
+
+impl Default for ExecutionTracker {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(300))
+    }
+}
+
+fn complete(key: SpanKey, start: DateTime<Utc>, end: SpanEnd) -> NodeSpan {
+    let duration = (end.at - start)
+        .to_std()
+        .ok()
+        .or_else(|| end.reported_ms.map(Duration::from_millis));
+
+    NodeSpan {
+        node_id: key.node_id,
+        session_id: key.session_id,
+        start: Some(start),
+        end: Some(end.at),
+        status: end.status,
+        duration,
+    }
+}
+
+fn incomplete(key: SpanKey, span: PendingSpan) -> NodeSpan {
+    NodeSpan {
+        node_id: key.node_id,
+        session_id: key.session_id,
+        start: span.start,
+        end: span.end.as_ref().map(|end| end.at),
+        status: SpanStatus::Incomplete,
+        duration: span
+            .end
+            .and_then(|end| end.reported_ms)
+            .map(Duration::from_millis),
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::*;
+
+    fn base(timestamp: &str) -> ZipEventBase {
+        ZipEventBase {
+            id: "evt".to_string(),
+            timestamp: timestamp.to_string(),
+            workflow_id: "wf-1".to_string(),
+            graph_id: None,
+            metadata: None,
+        }
+    }
+
+    fn started(session_id: &str, timestamp: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::ExecutionStarted(ExecutionStartedEvent {
+            base: base(timestamp),
+            event_type: "execution.started".to_string(),
+            session_id: session_id.to_string(),
+            workflow_name: "Workflow".to_string(),
+            trigger: None,
+        }))
+    }
+
+    fn executing(node_id: &str, timestamp: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::NodeExecuting(NodeExecutingEvent {
+            base: base(timestamp),
+            event_type: "node.executing".to_string(),
+            node_id: node_id.to_string(),
+            input_connections: vec![],
+        }))
+    }
+
+    fn completed(node_id: &str, timestamp: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::NodeCompleted(NodeCompletedEvent {
+            base: base(timestamp),
+            event_type: "node.completed".to_string(),
+            node_id: node_id.to_string(),
+            output_connections: vec![],
+            duration: None,
+            output_size: None,
+        }))
+    }
+
+    fn failed(node_id: &str, timestamp: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::NodeFailed(NodeFailedEvent {
+            base: base(timestamp),
+            event_type: "node.failed".to_string(),
+            node_id: node_id.to_string(),
+            output_connections: vec![],
+            error: None,
+        }))
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        parse_timestamp(timestamp)
+    }
+
+    #[test]
+    fn test_completed_lifecycle() {
+        let mut tracker = ExecutionTracker::default();
+        assert!(tracker
+            .record(&started("session-1", "2024-01-01T00:00:00Z"))
+            .is_empty());
+        assert!(tracker
+            .record(&executing("node-1", "2024-01-01T00:00:01Z"))
+            .is_empty());
+
+        let spans = tracker.record(&completed("node-1", "2024-01-01T00:00:01.250Z"));
+        assert_eq!(
+            spans,
+            vec![NodeSpan {
+                node_id: "node-1".to_string(),
+                session_id: Some("session-1".to_string()),
+                start: Some(at("2024-01-01T00:00:01Z")),
+                end: Some(at("2024-01-01T00:00:01.250Z")),
+                status: SpanStatus::Completed,
+                duration: Some(Duration::from_millis(250)),
+            }]
+        );
+        assert_eq!(tracker.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_failed_lifecycle() {
+        let mut tracker = ExecutionTracker::default();
+        tracker.record(&started("session-1", "2024-01-01T00:00:00Z"));
+        tracker.record(&executing("node-1", "2024-01-01T00:00:01Z"));
+
+        let spans = tracker.record(&failed("node-1", "2024-01-01T00:00:03Z"));
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].status, SpanStatus::Failed);
+        assert_eq!(spans[0].duration, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_out_of_order_end_event() {
+        let mut tracker = ExecutionTracker::default();
+        assert!(tracker
+            .record(&completed("node-1", "2024-01-01T00:00:02Z"))
+            .is_empty());
+        assert_eq!(tracker.pending_count(), 1);
+
+        let spans = tracker.record(&executing("node-1", "2024-01-01T00:00:01Z"));
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].status, SpanStatus::Completed);
+        assert_eq!(spans[0].start, Some(at("2024-01-01T00:00:01Z")));
+        assert_eq!(spans[0].duration, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_incomplete_lifecycle_times_out() {
+        let mut tracker = ExecutionTracker::new(Duration::from_secs(30));
+        tracker.record(&started("session-1", "2024-01-01T00:00:00Z"));
+        tracker.record(&executing("node-1", "2024-01-01T00:00:01Z"));
+
+        assert!(tracker.expire(at("2024-01-01T00:00:20Z")).is_empty());
+
+        let spans = tracker.expire(at("2024-01-01T00:00:31Z"));
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].status, SpanStatus::Incomplete);
+        assert_eq!(spans[0].session_id.as_deref(), Some("session-1"));
+        assert_eq!(spans[0].end, None);
+        assert_eq!(spans[0].duration, None);
+        assert_eq!(tracker.pending_count(), 0);
+    }
+
+    #[test]
+    fn test_nodes_correlated_per_session() {
+        let mut tracker = ExecutionTracker::default();
+        tracker.record(&started("session-1", "2024-01-01T00:00:00Z"));
+        tracker.record(&executing("node-1", "2024-01-01T00:00:01Z"));
+        tracker.record(&started("session-2", "2024-01-01T00:01:00Z"));
+
+        // The end event belongs to the new session, not the old open span
+        assert!(tracker
+            .record(&completed("node-1", "2024-01-01T00:01:01Z"))
+            .is_empty());
+        assert_eq!(tracker.pending_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_track_stream_flushes_incomplete_spans() {
+        let now = Utc::now();
+        let ts = |offset_ms: i64| (now + chrono::Duration::milliseconds(offset_ms)).to_rfc3339();
+        let events = futures_util::stream::iter(vec![
+            started("session-1", &ts(0)),
+            executing("node-1", &ts(10)),
+            executing("node-2", &ts(10)),
+            completed("node-1", &ts(20)),
+        ]);
+
+        let spans: Vec<NodeSpan> = ExecutionTracker::new(Duration::from_secs(60))
+            .track(events)
+            .collect()
+            .await;
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].node_id, "node-1");
+        assert_eq!(spans[0].status, SpanStatus::Completed);
+        assert_eq!(spans[0].duration, Some(Duration::from_millis(10)));
+        assert_eq!(spans[1].node_id, "node-2");
+        assert_eq!(spans[1].status, SpanStatus::Incomplete);
+    }
+}
@@ -1,6 +1,8 @@
 //! Templates API for managing node templates
 
-use crate::errors::{Result, ZealError};
+use crate::errors::{map_error_response, Result, ZealError};
+use crate::http::HttpClient;
+use crate::json;
 use crate::types::*;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -24,33 +26,71 @@ pub struct DeleteTemplateResponse {
     pub message: String,
 }
 
+/// Whether [`TemplatesAPI::update_with_options`] compares the update against
+/// the currently registered template for breaking changes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BreakingChangeCheck {
+    /// Send the update without checking
+    #[default]
+    Off,
+    /// Log breaking changes and send the update anyway
+    Warn,
+    /// Refuse updates with breaking changes unless `allow_breaking` is set
+    Strict,
+}
+
+/// Options for [`TemplatesAPI::update_with_options`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpdateTemplateOptions {
+    pub breaking_change_check: BreakingChangeCheck,
+    /// Send the update even if the strict check finds breaking changes
+    pub allow_breaking: bool,
+}
+
 /// Templates API for managing node templates
 pub struct TemplatesAPI {
     base_url: String,
-    client: Client,
+    client: HttpClient,
 }
 
 impl TemplatesAPI {
-    /// Create a new Templates API instance
+    /// Create a new Templates API instance with its own HTTP client and
+    /// connection pool (see [`ZealClient`](crate::ZealClient) for sharing one)
     pub fn new(base_url: &str) -> Self {
         Self {
             base_url: base_url.to_string(),
-            client: Client::new(),
+            client: HttpClient::standalone(),
         }
     }
 
     /// Create a new Templates API instance with custom HTTP client
     pub fn with_client(base_url: &str, client: Client) -> Self {
+        Self::with_http_client(base_url, HttpClient::new(client))
+    }
+
+    /// Create with the SDK's shared HTTP transport
+    pub(crate) fn with_http_client(base_url: &str, client: HttpClient) -> Self {
         Self {
             base_url: base_url.to_string(),
             client,
         }
     }
 
-    /// Register node templates
+    /// Register node templates.
+    ///
+    /// Every template is checked with [`NodeTemplate::validate`] first, so a
+    /// malformed template fails locally without sending the request.
     pub async fn register(
         &self,
         request: RegisterTemplatesRequest,
+    ) -> Result<RegisterTemplatesResponse> {
+        validate_templates(&request.templates)?;
+        self.send_registration(&request).await
+    }
+
+    async fn send_registration(
+        &self,
+        request: &RegisterTemplatesRequest,
     ) -> Result<RegisterTemplatesResponse> {
         let url = format!(
             "{}/api/zip/templates/register",
@@ -61,27 +101,65 @@ impl TemplatesAPI {
             .client
             .post(&url)
             .header("Content-Type", "application/json")
-            .json(&request)
+            .json(request)
             .send()
             .await?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to register templates: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("register templates", status, &body));
         }
 
-        let registration_response = response.json::<RegisterTemplatesResponse>().await?;
+        let registration_response =
+            json::from_response::<RegisterTemplatesResponse>(response).await?;
         Ok(registration_response)
     }
 
+    /// Register node templates in chunks of at most `chunk_size` templates per request.
+    ///
+    /// All templates are validated before the first chunk is sent. Chunks are
+    /// sent sequentially and their results aggregated in the original template
+    /// order. Registration stops at the first failing chunk.
+    pub async fn register_chunked(
+        &self,
+        request: RegisterTemplatesRequest,
+        chunk_size: usize,
+    ) -> Result<RegisterTemplatesResponse> {
+        if chunk_size == 0 {
+            return Err(ZealError::validation_error(
+                "chunk_size",
+                "Chunk size must be greater than zero",
+            ));
+        }
+
+        validate_templates(&request.templates)?;
+        let RegisterTemplatesRequest {
+            namespace,
+            templates,
+            webhook_url,
+        } = request;
+
+        let mut aggregated = RegisterTemplatesResponse {
+            registered: 0,
+            templates: Vec::with_capacity(templates.len()),
+        };
+
+        for chunk in templates.chunks(chunk_size) {
+            let response = self
+                .send_registration(&RegisterTemplatesRequest {
+                    namespace: namespace.clone(),
+                    templates: chunk.to_vec(),
+                    webhook_url: webhook_url.clone(),
+                })
+                .await?;
+            aggregated.registered += response.registered;
+            aggregated.templates.extend(response.templates);
+        }
+
+        Ok(aggregated)
+    }
+
     /// List templates for a namespace
     pub async fn list(&self, namespace: &str) -> Result<ListTemplatesResponse> {
         let url = format!(
@@ -94,18 +172,11 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to list templates: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("list templates", status, &body));
         }
 
-        let templates_response = response.json::<ListTemplatesResponse>().await?;
+        let templates_response = json::from_response::<ListTemplatesResponse>(response).await?;
         Ok(templates_response)
     }
 
@@ -116,6 +187,54 @@ impl TemplatesAPI {
         template_id: &str,
         updates: NodeTemplate,
     ) -> Result<UpdateTemplateResponse> {
+        self.update_with_options(
+            namespace,
+            template_id,
+            updates,
+            UpdateTemplateOptions::default(),
+        )
+        .await
+    }
+
+    /// Update a template, first checking it for breaking changes against the
+    /// registered version (see [`NodeTemplate::breaking_changes`]) when
+    /// configured. In strict mode breaking changes fail with a
+    /// [`ZealError::ValidationError`] listing them, unless `allow_breaking`
+    /// is set. A template that isn't registered yet has nothing to break.
+    pub async fn update_with_options(
+        &self,
+        namespace: &str,
+        template_id: &str,
+        updates: NodeTemplate,
+        options: UpdateTemplateOptions,
+    ) -> Result<UpdateTemplateResponse> {
+        if options.breaking_change_check != BreakingChangeCheck::Off {
+            let breaking = match self.get(namespace, template_id).await {
+                Ok(current) => current.breaking_changes(&updates),
+                Err(ZealError::NotFound { .. }) => Vec::new(),
+                Err(err) => return Err(err),
+            };
+
+            if !breaking.is_empty() {
+                if options.breaking_change_check == BreakingChangeCheck::Strict
+                    && !options.allow_breaking
+                {
+                    let changes: Vec<_> = breaking.iter().map(|b| b.to_string()).collect();
+                    return Err(ZealError::validation_error(
+                        "template".to_string(),
+                        format!(
+                            "Update to template '{}' has breaking changes: {}",
+                            template_id,
+                            changes.join("; ")
+                        ),
+                    ));
+                }
+                for change in &breaking {
+                    tracing::warn!("Template '{}' breaking change: {}", template_id, change);
+                }
+            }
+        }
+
         let url = format!(
             "{}/api/zip/templates/{}/{}",
             self.base_url.trim_end_matches('/'),
@@ -133,18 +252,11 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to update template: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("update template", status, &body));
         }
 
-        let update_response = response.json::<UpdateTemplateResponse>().await?;
+        let update_response = json::from_response::<UpdateTemplateResponse>(response).await?;
         Ok(update_response)
     }
 
@@ -165,18 +277,11 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to delete template: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("delete template", status, &body));
         }
 
-        let delete_response = response.json::<DeleteTemplateResponse>().await?;
+        let delete_response = json::from_response::<DeleteTemplateResponse>(response).await?;
         Ok(delete_response)
     }
 
@@ -188,18 +293,11 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to list categories: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("list categories", status, &body));
         }
 
-        let categories_response = response.json::<ListCategoriesResponse>().await?;
+        let categories_response = json::from_response::<ListCategoriesResponse>(response).await?;
         Ok(categories_response)
     }
 
@@ -221,18 +319,12 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to register categories: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("register categories", status, &body));
         }
 
-        let registration_response = response.json::<RegisterCategoriesResponse>().await?;
+        let registration_response =
+            json::from_response::<RegisterCategoriesResponse>(response).await?;
         Ok(registration_response)
     }
 
@@ -254,18 +346,11 @@ impl TemplatesAPI {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to upload bundle: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("upload bundle", status, &body));
         }
 
-        let upload_response = response.json::<UploadBundleResponse>().await?;
+        let upload_response = json::from_response::<UploadBundleResponse>(response).await?;
         Ok(upload_response)
     }
 
@@ -280,3 +365,260 @@ impl TemplatesAPI {
             .ok_or_else(|| ZealError::not_found("template", template_id))
     }
 }
+
+/// Validate templates before registration, logging layout warnings
+fn validate_templates(templates: &[NodeTemplate]) -> Result<()> {
+    for template in templates {
+        for warning in template.validate(PortLayoutStrictness::default())? {
+            tracing::warn!("Template '{}': {}", template.id, warning);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// Echoes back one registration result per template in the request body
+    struct EchoRegistration;
+
+    impl Respond for EchoRegistration {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: RegisterTemplatesRequest = serde_json::from_slice(&request.body).unwrap();
+            let templates: Vec<_> = body
+                .templates
+                .iter()
+                .map(|t| {
+                    serde_json::json!({
+                        "id": t.id,
+                        "globalId": format!("{}/{}", body.namespace, t.id),
+                        "status": "registered"
+                    })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "registered": templates.len(),
+                "templates": templates
+            }))
+        }
+    }
+
+    fn template(id: &str) -> NodeTemplate {
+        NodeTemplate {
+            id: id.to_string(),
+            type_name: "test".to_string(),
+            title: id.to_string(),
+            subtitle: None,
+            category: "testing".to_string(),
+            subcategory: None,
+            description: String::new(),
+            icon: "box".to_string(),
+            variant: None,
+            shape: None,
+            size: None,
+            ports: vec![Port {
+                id: "in".to_string(),
+                label: "In".to_string(),
+                port_type: PortType::Input,
+                position: PortPosition::Left,
+                data_type: None,
+                required: None,
+                multiple: None,
+            }],
+            properties: None,
+            property_rules: None,
+            runtime: None,
+            display: None,
+        }
+    }
+
+    fn register_request(count: usize) -> RegisterTemplatesRequest {
+        RegisterTemplatesRequest {
+            namespace: "ns".to_string(),
+            templates: (0..count).map(|i| template(&format!("t{}", i))).collect(),
+            webhook_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_chunked_splits_and_aggregates() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/templates/register"))
+            .respond_with(EchoRegistration)
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let api = TemplatesAPI::new(&server.uri());
+        let response = api.register_chunked(register_request(5), 2).await.unwrap();
+
+        assert_eq!(response.registered, 5);
+        let ids: Vec<_> = response.templates.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["t0", "t1", "t2", "t3", "t4"]);
+
+        let sizes: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| {
+                serde_json::from_slice::<RegisterTemplatesRequest>(&r.body)
+                    .unwrap()
+                    .templates
+                    .len()
+            })
+            .collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_register_chunked_rejects_zero_chunk_size() {
+        let api = TemplatesAPI::new("http://localhost:3000");
+        let err = api
+            .register_chunked(register_request(1), 0)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZealError::ValidationError { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_invalid_template_locally() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/templates/register"))
+            .respond_with(EchoRegistration)
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let api = TemplatesAPI::new(&server.uri());
+        let mut request = register_request(3);
+        request.templates[2].title.clear();
+
+        let err = api.register(request.clone()).await.unwrap_err();
+        assert!(matches!(err, ZealError::ValidationError { ref field, .. } if field == "title"));
+        // No chunk is sent when a later template is invalid
+        let err = api.register_chunked(request, 1).await.unwrap_err();
+        assert!(matches!(err, ZealError::ValidationError { .. }));
+    }
+
+    async fn mount_registered(server: &MockServer, registered: &NodeTemplate) {
+        Mock::given(method("GET"))
+            .and(path("/api/zip/templates/ns"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "namespace": "ns",
+                "templates": [registered],
+                "count": 1
+            })))
+            .mount(server)
+            .await;
+    }
+
+    async fn mount_update(server: &MockServer, expected: u64) {
+        Mock::given(method("PUT"))
+            .and(path("/api/zip/templates/ns/t0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "template": {}
+            })))
+            .expect(expected)
+            .mount(server)
+            .await;
+    }
+
+    fn options(check: BreakingChangeCheck, allow_breaking: bool) -> UpdateTemplateOptions {
+        UpdateTemplateOptions {
+            breaking_change_check: check,
+            allow_breaking,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_strict_refuses_breaking_changes() {
+        let server = MockServer::start().await;
+        mount_registered(&server, &template("t0")).await;
+        mount_update(&server, 1).await;
+
+        let api = TemplatesAPI::new(&server.uri());
+        let mut breaking = template("t0");
+        breaking.ports[0].id = "input".to_string();
+
+        let err = api
+            .update_with_options(
+                "ns",
+                "t0",
+                breaking.clone(),
+                options(BreakingChangeCheck::Strict, false),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ZealError::ValidationError { ref message, .. } if message.contains("ports.in")
+        ));
+
+        // The override sends the update anyway
+        let response = api
+            .update_with_options(
+                "ns",
+                "t0",
+                breaking,
+                options(BreakingChangeCheck::Strict, true),
+            )
+            .await
+            .unwrap();
+        assert!(response.success);
+    }
+
+    #[tokio::test]
+    async fn test_update_strict_allows_non_breaking_changes() {
+        let server = MockServer::start().await;
+        mount_registered(&server, &template("t0")).await;
+        mount_update(&server, 1).await;
+
+        let api = TemplatesAPI::new(&server.uri());
+        let mut renamed = template("t0");
+        renamed.title = "Renamed".to_string();
+
+        let response = api
+            .update_with_options(
+                "ns",
+                "t0",
+                renamed,
+                options(BreakingChangeCheck::Strict, false),
+            )
+            .await
+            .unwrap();
+        assert!(response.success);
+    }
+
+    #[tokio::test]
+    async fn test_update_warn_mode_sends_breaking_changes() {
+        let server = MockServer::start().await;
+        mount_registered(&server, &template("t0")).await;
+        mount_update(&server, 2).await;
+
+        let api = TemplatesAPI::new(&server.uri());
+        let mut breaking = template("t0");
+        breaking.ports[0].data_type = Some("number".to_string());
+        let mut renamed = template("t0");
+        renamed.title = "Renamed".to_string();
+
+        for updates in [breaking, renamed] {
+            let response = api
+                .update_with_options(
+                    "ns",
+                    "t0",
+                    updates,
+                    options(BreakingChangeCheck::Warn, false),
+                )
+                .await
+                .unwrap();
+            assert!(response.success);
+        }
+    }
+}
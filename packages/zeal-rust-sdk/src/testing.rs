@@ -0,0 +1,198 @@
+//! Test support utilities (enabled with the `testing` feature)
+
+use crate::events::ZipWebhookEvent;
+use futures_util::{Stream, StreamExt};
+use std::time::Duration;
+
+/// Fluent assertions over a stream of webhook events.
+///
+/// Each expectation consumes events until one matches, skipping unrelated
+/// events, and panics if the stream ends or the timeout set with
+/// [`within`](Self::within) elapses first.
+///
+/// ```ignore
+/// let mut events = EventStreamAssert::new(subscription.as_observable())
+///     .within(Duration::from_secs(5));
+/// events.expect_node_event("node-1", "node.executing").await;
+/// events.expect_node_event("node-1", "node.completed").await;
+/// ```
+pub struct EventStreamAssert<S> {
+    stream: S,
+    timeout: Duration,
+}
+
+impl<S> EventStreamAssert<S>
+where
+    S: Stream<Item = ZipWebhookEvent> + Unpin,
+{
+    /// Wrap a stream with a default timeout of 5 seconds per expectation
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Set the timeout applied to each subsequent expectation
+    pub fn within(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait for an event of the given type
+    pub async fn expect_event_type(&mut self, event_type: &str) -> ZipWebhookEvent {
+        self.expect(&format!("event of type '{}'", event_type), |event| {
+            event.event_type() == event_type
+        })
+        .await
+    }
+
+    /// Wait for any event concerning the given node
+    pub async fn expect_node(&mut self, node_id: &str) -> ZipWebhookEvent {
+        self.expect(&format!("event for node '{}'", node_id), |event| {
+            event.node_id() == Some(node_id)
+        })
+        .await
+    }
+
+    /// Wait for an event of the given type concerning the given node
+    pub async fn expect_node_event(&mut self, node_id: &str, event_type: &str) -> ZipWebhookEvent {
+        self.expect(
+            &format!("'{}' event for node '{}'", event_type, node_id),
+            |event| event.node_id() == Some(node_id) && event.event_type() == event_type,
+        )
+        .await
+    }
+
+    /// Wait for an event matching `predicate`
+    pub async fn expect<F>(&mut self, description: &str, predicate: F) -> ZipWebhookEvent
+    where
+        F: Fn(&ZipWebhookEvent) -> bool,
+    {
+        let mut events = self.collect_until(description, predicate).await;
+        events
+            .pop()
+            .expect("collect_until returns the matching event")
+    }
+
+    /// Collect events up to and including the first one matching `predicate`
+    pub async fn collect_until<F>(
+        &mut self,
+        description: &str,
+        predicate: F,
+    ) -> Vec<ZipWebhookEvent>
+    where
+        F: Fn(&ZipWebhookEvent) -> bool,
+    {
+        let mut seen = Vec::new();
+        let search = async {
+            while let Some(event) = self.stream.next().await {
+                let matched = predicate(&event);
+                seen.push(event);
+                if matched {
+                    return true;
+                }
+            }
+            false
+        };
+
+        match tokio::time::timeout(self.timeout, search).await {
+            Ok(true) => seen,
+            Ok(false) => panic!(
+                "Event stream ended before {} (saw {:?})",
+                description,
+                event_types(&seen)
+            ),
+            Err(_) => panic!(
+                "Timed out after {:?} waiting for {} (saw {:?})",
+                self.timeout,
+                description,
+                event_types(&seen)
+            ),
+        }
+    }
+}
+
+fn event_types(events: &[ZipWebhookEvent]) -> Vec<&str> {
+    events.iter().map(|e| e.event_type()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::*;
+    use crate::subscription::WebhookSubscription;
+    use crate::webhooks::WebhooksAPI;
+
+    fn base() -> ZipEventBase {
+        ZipEventBase {
+            id: "evt".to_string(),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            workflow_id: "wf-1".to_string(),
+            graph_id: None,
+            metadata: None,
+        }
+    }
+
+    fn executing(node_id: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::NodeExecuting(NodeExecutingEvent {
+            base: base(),
+            event_type: "node.executing".to_string(),
+            node_id: node_id.to_string(),
+            input_connections: vec![],
+        }))
+    }
+
+    fn completed(node_id: &str) -> ZipWebhookEvent {
+        ZipWebhookEvent::Execution(ZipExecutionEvent::NodeCompleted(NodeCompletedEvent {
+            base: base(),
+            event_type: "node.completed".to_string(),
+            node_id: node_id.to_string(),
+            output_connections: vec![],
+            duration: Some(12),
+            output_size: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_expectations_against_mock_subscription() {
+        let subscription =
+            WebhookSubscription::new(WebhooksAPI::new("http://localhost:3000"), None);
+        let mut events =
+            EventStreamAssert::new(subscription.as_observable()).within(Duration::from_secs(1));
+
+        let emitter = tokio::spawn(async move {
+            for event in [
+                executing("node-1"),
+                executing("node-2"),
+                completed("node-2"),
+                completed("node-1"),
+            ] {
+                subscription.emit(event);
+                tokio::task::yield_now().await;
+            }
+        });
+
+        events.expect_node_event("node-1", "node.executing").await;
+        let noise = events
+            .collect_until("node-2 completion", |e| {
+                e.event_type() == "node.completed" && e.node_id() == Some("node-2")
+            })
+            .await;
+        assert_eq!(noise.len(), 2);
+        let done = events.expect_node("node-1").await;
+        assert_eq!(done.event_type(), "node.completed");
+
+        emitter.await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Timed out")]
+    async fn test_expectation_times_out() {
+        let (_tx, rx) = futures::channel::mpsc::unbounded::<ZipWebhookEvent>();
+        EventStreamAssert::new(rx)
+            .within(Duration::from_millis(20))
+            .expect_event_type("node.completed")
+            .await;
+    }
+}
@@ -0,0 +1,243 @@
+//! Background batching of trace events
+//!
+//! [`TraceBatcher`] accepts events one at a time and submits them to
+//! [`TracesAPI::submit_events`] in batches, flushing when a batch is full or
+//! when its oldest event has waited for the batch timeout. Failed submissions
+//! are reported on a channel instead of being lost silently.
+
+use crate::batching::{self, BatcherHandle, Pending};
+use crate::config::PerformanceConfig;
+use crate::errors::{Result, ZealError};
+use crate::traces::TracesAPI;
+use crate::types::TraceEvent;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// A batch that could not be submitted
+#[derive(Debug)]
+pub struct TraceBatchError {
+    pub error: ZealError,
+    pub events: Vec<TraceEvent>,
+}
+
+/// Batches trace events for a session and submits them on a background task.
+///
+/// Dropping the batcher without calling [`close`](Self::close) still submits
+/// the queued events, but nothing waits for that submission to finish.
+#[derive(Debug)]
+pub struct TraceBatcher {
+    handle: BatcherHandle<mpsc::Sender<TraceEvent>>,
+}
+
+impl TraceBatcher {
+    /// Start a batcher using `trace_batch_size` and `trace_batch_timeout`
+    /// from the performance config. Returns the batcher and a receiver for
+    /// batches that failed to submit; dropping the receiver only logs failures.
+    pub fn spawn(
+        api: TracesAPI,
+        session_id: impl Into<String>,
+        performance: &PerformanceConfig,
+    ) -> (Self, mpsc::UnboundedReceiver<TraceBatchError>) {
+        Self::with_limits(
+            api,
+            session_id,
+            performance.trace_batch_size,
+            performance.trace_batch_timeout,
+        )
+    }
+
+    /// Start a batcher flushing every `batch_size` events or `batch_timeout`
+    /// after the first event of a batch, whichever comes first
+    pub fn with_limits(
+        api: TracesAPI,
+        session_id: impl Into<String>,
+        batch_size: usize,
+        batch_timeout: Duration,
+    ) -> (Self, mpsc::UnboundedReceiver<TraceBatchError>) {
+        let batch_size = batch_size.max(1);
+        let (sender, receiver) = mpsc::channel(batch_size);
+        let (errors, error_receiver) = mpsc::unbounded_channel();
+        let pending = PendingEvents {
+            api,
+            session_id: session_id.into(),
+            events: Vec::with_capacity(batch_size),
+            batch_size,
+            errors,
+        };
+        let task = tokio::spawn(batching::run(receiver, pending, batch_timeout));
+
+        (
+            Self {
+                handle: BatcherHandle::new(sender, task),
+            },
+            error_receiver,
+        )
+    }
+
+    /// Queue an event. Waits if the background task is behind by a full batch.
+    pub async fn record(&self, event: TraceEvent) -> Result<()> {
+        let sender = self
+            .handle
+            .sender()
+            .ok_or_else(|| ZealError::other("Trace batcher is closed"))?;
+        sender
+            .send(event)
+            .await
+            .map_err(|_| ZealError::other("Trace batcher task has stopped"))
+    }
+
+    /// Flush the remaining events and wait for the background task to finish
+    pub async fn close(mut self) {
+        self.handle.close().await;
+    }
+}
+
+/// Events waiting to be submitted for one session
+struct PendingEvents {
+    api: TracesAPI,
+    session_id: String,
+    events: Vec<TraceEvent>,
+    batch_size: usize,
+    errors: mpsc::UnboundedSender<TraceBatchError>,
+}
+
+impl Pending for PendingEvents {
+    type Item = TraceEvent;
+
+    fn push(&mut self, event: TraceEvent) {
+        self.events.push(event);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.events.len() >= self.batch_size
+    }
+
+    async fn flush(&mut self) {
+        if self.events.is_empty() {
+            return;
+        }
+
+        let events = std::mem::take(&mut self.events);
+        debug!(
+            "Submitting {} trace events for {}",
+            events.len(),
+            self.session_id
+        );
+        if let Err(error) = self
+            .api
+            .submit_events(&self.session_id, events.clone())
+            .await
+        {
+            warn!(
+                "Failed to submit {} trace events for {}: {}",
+                events.len(),
+                self.session_id,
+                error
+            );
+            let _ = self.errors.send(TraceBatchError { error, events });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TraceEventType;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn submitted_batch_sizes(server: &MockServer) -> Vec<usize> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                body["events"].as_array().unwrap().len()
+            })
+            .collect()
+    }
+
+    fn event(i: usize) -> TraceEvent {
+        TraceEvent::now(format!("node-{}", i), TraceEventType::Output)
+    }
+
+    async fn mock_submit(server: &MockServer, status: u16) {
+        Mock::given(method("POST"))
+            .and(path("/api/zip/traces/session-1/events"))
+            .respond_with(
+                ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                    "success": true,
+                    "eventsProcessed": 0
+                })),
+            )
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_flushes_full_batches_and_remainder_on_close() {
+        let server = MockServer::start().await;
+        mock_submit(&server, 200).await;
+
+        let (batcher, mut errors) = TraceBatcher::with_limits(
+            TracesAPI::new(&server.uri()),
+            "session-1",
+            2,
+            Duration::from_secs(60),
+        );
+        for i in 0..5 {
+            batcher.record(event(i)).await.unwrap();
+        }
+        batcher.close().await;
+
+        assert_eq!(submitted_batch_sizes(&server).await, vec![2, 2, 1]);
+        assert!(errors.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_flushes_partial_batch_after_timeout() {
+        let server = MockServer::start().await;
+        mock_submit(&server, 200).await;
+
+        let (batcher, _errors) = TraceBatcher::with_limits(
+            TracesAPI::new(&server.uri()),
+            "session-1",
+            100,
+            Duration::from_millis(20),
+        );
+        batcher.record(event(0)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(submitted_batch_sizes(&server).await, vec![1]);
+        batcher.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_reports_failed_batches() {
+        let server = MockServer::start().await;
+        mock_submit(&server, 400).await;
+
+        let (batcher, mut errors) = TraceBatcher::with_limits(
+            TracesAPI::new(&server.uri()),
+            "session-1",
+            10,
+            Duration::from_secs(60),
+        );
+        batcher.record(event(0)).await.unwrap();
+        batcher.close().await;
+
+        let failed = errors.recv().await.unwrap();
+        assert!(matches!(
+            failed.error,
+            ZealError::ApiError { status: 400, .. }
+        ));
+        assert_eq!(failed.events.len(), 1);
+    }
+}
@@ -1,9 +1,45 @@
 //! Traces API for workflow execution tracing
-
-use crate::errors::{Result, ZealError};
+//!
+//! Event submissions whose JSON body is larger than the compression
+//! threshold are sent gzip-compressed with `Content-Encoding: gzip`; the
+//! server is expected to decode the body before parsing it. Smaller bodies
+//! are sent as plain JSON.
+
+use crate::config::PerformanceConfig;
+use crate::errors::{map_error_response, Result, ZealError};
+use crate::http::HttpClient;
+use crate::json;
 use crate::types::*;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::Stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::time::Duration;
+
+/// Filters and paging for [`TracesAPI::node_history_page`]
+#[derive(Debug, Clone, Default)]
+pub struct NodeHistoryQuery {
+    /// Maximum number of records to return
+    pub limit: Option<usize>,
+    /// Cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+    /// Only executions starting at or after this time (ms since epoch)
+    pub since: Option<i64>,
+    /// Only executions starting before this time (ms since epoch)
+    pub until: Option<i64>,
+}
+
+/// A page of node execution history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHistoryPage {
+    pub records: Vec<NodeExecutionRecord>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitEventsResponse {
@@ -71,32 +107,83 @@ pub struct BatchTraceResponse {
     pub success: bool,
 }
 
+/// Events recorded for a session, with the session's current status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEventsResponse {
+    pub events: Vec<TraceEvent>,
+    pub status: TraceStatus,
+}
+
+/// Default interval between polls in [`TracesAPI::tail_session`]
+pub const DEFAULT_TAIL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Traces API for managing execution traces
+#[derive(Clone)]
 pub struct TracesAPI {
     base_url: String,
-    client: Client,
+    client: HttpClient,
     session_id: Option<String>,
+    tail_poll_interval: Duration,
+    max_batch_size: usize,
+    compression_threshold: Option<usize>,
+    max_inline_data_size: Option<usize>,
 }
 
 impl TracesAPI {
-    /// Create a new Traces API instance
+    /// Create a new Traces API instance with its own HTTP client and
+    /// connection pool (see [`ZealClient`](crate::ZealClient) for sharing one)
     pub fn new(base_url: &str) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            client: Client::new(),
-            session_id: None,
-        }
+        Self::with_http_client(base_url, HttpClient::standalone())
     }
 
     /// Create a new Traces API instance with custom HTTP client
     pub fn with_client(base_url: &str, client: Client) -> Self {
+        Self::with_http_client(base_url, HttpClient::new(client))
+    }
+
+    /// Create with the SDK's shared HTTP transport
+    pub(crate) fn with_http_client(base_url: &str, client: HttpClient) -> Self {
+        let performance = PerformanceConfig::default();
         Self {
             base_url: base_url.to_string(),
             client,
             session_id: None,
+            tail_poll_interval: DEFAULT_TAIL_POLL_INTERVAL,
+            max_batch_size: performance.trace_batch_size,
+            compression_threshold: performance
+                .compression
+                .then_some(performance.compression_threshold),
+            max_inline_data_size: performance.max_inline_data_size,
         }
     }
 
+    /// Set how often [`tail_session`](Self::tail_session) polls for new events
+    pub fn with_tail_poll_interval(mut self, interval: Duration) -> Self {
+        self.tail_poll_interval = interval;
+        self
+    }
+
+    /// Set the most events [`submit_events`](Self::submit_events) sends per
+    /// request (default: `PerformanceConfig::trace_batch_size`)
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Set the body size in bytes above which event submissions are
+    /// gzip-compressed; `None` always sends plain JSON
+    pub fn with_compression_threshold(mut self, threshold: Option<usize>) -> Self {
+        self.compression_threshold = threshold;
+        self
+    }
+
+    /// Set the serialized size in bytes above which an event's `full_data`
+    /// is replaced by a truncated preview; `None` never truncates
+    pub fn with_max_inline_data_size(mut self, max_size: Option<usize>) -> Self {
+        self.max_inline_data_size = max_size;
+        self
+    }
+
     /// Create a new trace session
     pub async fn create_session(
         &mut self,
@@ -117,27 +204,28 @@ impl TracesAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to create trace session: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("create trace session", status, &body));
         }
 
-        let session_response = response.json::<CreateTraceSessionResponse>().await?;
+        let session_response = json::from_response::<CreateTraceSessionResponse>(response).await?;
         self.session_id = Some(session_response.session_id.clone());
         Ok(session_response)
     }
 
-    /// Submit trace events
+    /// Submit trace events.
+    ///
+    /// More than the configured maximum batch size are sent as several
+    /// sequential requests, and their `eventsProcessed` counts are summed. If a
+    /// request fails, the error is returned and later batches are not sent;
+    /// earlier batches have already been recorded.
+    ///
+    /// Events whose `full_data` is over the inline size limit are sent with a
+    /// truncated preview instead (see [`limit_inline_data`]).
     pub async fn submit_events(
         &self,
         session_id: &str,
-        events: Vec<TraceEvent>,
+        mut events: Vec<TraceEvent>,
     ) -> Result<SubmitEventsResponse> {
         let url = format!(
             "{}/api/zip/traces/{}/events",
@@ -145,35 +233,214 @@ impl TracesAPI {
             session_id
         );
 
+        for event in &mut events {
+            event.debug_check_timestamp();
+            self.limit_event_data(event)?;
+        }
+
+        if events.len() <= self.max_batch_size {
+            return self.post_events(&url, &events).await;
+        }
+
+        let mut aggregated = SubmitEventsResponse {
+            success: true,
+            events_processed: 0,
+        };
+        for batch in events.chunks(self.max_batch_size) {
+            let response = self.post_events(&url, batch).await?;
+            aggregated.success &= response.success;
+            aggregated.events_processed += response.events_processed;
+        }
+        Ok(aggregated)
+    }
+
+    async fn post_events(&self, url: &str, events: &[TraceEvent]) -> Result<SubmitEventsResponse> {
         let request_body = serde_json::json!({
             "events": events
         });
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&request_body)
-            .send()
-            .await?;
+        let response = self.post_json(url, &request_body).await?;
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to submit trace events: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("submit trace events", status, &body));
         }
 
-        let submit_response = response.json::<SubmitEventsResponse>().await?;
+        let submit_response = json::from_response::<SubmitEventsResponse>(response).await?;
         Ok(submit_response)
     }
 
+    /// Get the events of a session, optionally only those at or after
+    /// `since` (a millisecond timestamp)
+    pub async fn get_session_events(
+        &self,
+        session_id: &str,
+        since: Option<i64>,
+    ) -> Result<SessionEventsResponse> {
+        let mut url = format!(
+            "{}/api/zip/traces/{}/events",
+            self.base_url.trim_end_matches('/'),
+            session_id
+        );
+        if let Some(since) = since {
+            url.push_str(&format!("?since={}", since));
+        }
+
+        let response = self.client.get(&url).send().await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(ZealError::not_found("trace session", session_id));
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("get trace events", status, &body));
+        }
+
+        let events_response = json::from_response::<SessionEventsResponse>(response).await?;
+        Ok(events_response)
+    }
+
+    /// Get the most recent executions of a node across runs, newest first
+    pub async fn node_history(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        limit: usize,
+    ) -> Result<Vec<NodeExecutionRecord>> {
+        let query = NodeHistoryQuery {
+            limit: Some(limit),
+            ..Default::default()
+        };
+        Ok(self
+            .node_history_page(workflow_id, node_id, &query)
+            .await?
+            .records)
+    }
+
+    /// Get one page of a node's execution history. Pass the returned
+    /// `next_cursor` back in the query to fetch the following page.
+    pub async fn node_history_page(
+        &self,
+        workflow_id: &str,
+        node_id: &str,
+        query: &NodeHistoryQuery,
+    ) -> Result<NodeHistoryPage> {
+        let mut url = url::Url::parse(&format!(
+            "{}/api/zip/traces/workflows/{}/nodes/{}/history",
+            self.base_url.trim_end_matches('/'),
+            workflow_id,
+            node_id
+        ))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(limit) = query.limit {
+                pairs.append_pair("limit", &limit.to_string());
+            }
+            if let Some(cursor) = &query.cursor {
+                pairs.append_pair("cursor", cursor);
+            }
+            if let Some(since) = query.since {
+                pairs.append_pair("since", &since.to_string());
+            }
+            if let Some(until) = query.until {
+                pairs.append_pair("until", &until.to_string());
+            }
+        }
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
+
+        let response = self.client.get(url.as_str()).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("get node history", status, &body));
+        }
+
+        let page = json::from_response::<NodeHistoryPage>(response).await?;
+        Ok(page)
+    }
+
+    /// Follow a session's trace events as they are recorded.
+    ///
+    /// Polls for events from the last seen timestamp onwards and yields the
+    /// new ones in timestamp order. Events sharing a millisecond are told
+    /// apart by their position within it, so the server must return them in
+    /// a stable order. The stream ends once the session reaches a terminal
+    /// status, or after yielding an error (e.g. the session does not exist).
+    pub fn tail_session(&self, session_id: &str) -> impl Stream<Item = Result<TraceEvent>> + '_ {
+        struct Tail {
+            session_id: String,
+            last_seen: Option<i64>,
+            /// Events already yielded with the `last_seen` timestamp
+            seen_at_last: usize,
+            pending: VecDeque<TraceEvent>,
+            polled: bool,
+            finished: bool,
+        }
+
+        let state = Tail {
+            session_id: session_id.to_string(),
+            last_seen: None,
+            seen_at_last: 0,
+            pending: VecDeque::new(),
+            polled: false,
+            finished: false,
+        };
+
+        futures_util::stream::unfold(state, move |mut tail| async move {
+            loop {
+                if let Some(event) = tail.pending.pop_front() {
+                    return Some((Ok(event), tail));
+                }
+                if tail.finished {
+                    return None;
+                }
+
+                if tail.polled {
+                    tokio::time::sleep(self.tail_poll_interval).await;
+                }
+                tail.polled = true;
+
+                match self
+                    .get_session_events(&tail.session_id, tail.last_seen)
+                    .await
+                {
+                    Ok(batch) => {
+                        let mut events: Vec<TraceEvent> = batch
+                            .events
+                            .into_iter()
+                            .filter(|e| tail.last_seen.is_none_or(|last| e.timestamp >= last))
+                            .collect();
+                        events.sort_by_key(|e| e.timestamp);
+                        let already_seen = match tail.last_seen {
+                            Some(last) => events
+                                .iter()
+                                .take_while(|e| e.timestamp == last)
+                                .count()
+                                .min(tail.seen_at_last),
+                            None => 0,
+                        };
+                        if let Some(newest) = events.last().map(|e| e.timestamp) {
+                            tail.seen_at_last =
+                                events.iter().filter(|e| e.timestamp == newest).count();
+                            tail.last_seen = Some(newest);
+                        }
+                        tail.pending.extend(events.into_iter().skip(already_seen));
+                        tail.finished = !matches!(batch.status, TraceStatus::Running);
+                    }
+                    Err(err) => {
+                        tail.finished = true;
+                        return Some((Err(err), tail));
+                    }
+                }
+            }
+        })
+    }
+
     /// Submit a single trace event
     pub async fn submit_event(
         &self,
@@ -205,18 +472,11 @@ impl TracesAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to complete trace session: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("complete trace session", status, &body));
         }
 
-        let complete_response = response.json::<CompleteSessionResponse>().await?;
+        let complete_response = json::from_response::<CompleteSessionResponse>(response).await?;
 
         if self.session_id.as_deref() == Some(session_id) {
             self.session_id = None;
@@ -225,7 +485,8 @@ impl TracesAPI {
         Ok(complete_response)
     }
 
-    /// Helper method to trace node execution
+    /// Helper method to trace node execution. Use
+    /// [`TraceEventType::Warning`] for a node that completed with warnings.
     pub async fn trace_node_execution(
         &self,
         session_id: &str,
@@ -234,58 +495,51 @@ impl TracesAPI {
         data: serde_json::Value,
         duration: Option<std::time::Duration>,
     ) -> Result<()> {
-        let data_str = serde_json::to_string(&data)?;
-        let trace_data = TraceData {
-            size: data_str.len(),
-            data_type: "application/json".to_string(),
-            preview: Some(data.clone()),
-            full_data: Some(data),
-        };
-
         let event = TraceEvent {
-            timestamp: chrono::Utc::now().timestamp_millis(),
-            node_id: node_id.to_string(),
-            port_id: None,
-            event_type,
-            data: trace_data,
+            data: json_trace_data(data)?,
             duration,
-            metadata: None,
-            error: None,
+            ..TraceEvent::now(node_id, event_type)
         };
 
         self.submit_event(session_id, event).await?;
         Ok(())
     }
 
-    /// Batch trace submission
-    pub async fn submit_batch(&self, request: BatchTraceRequest) -> Result<BatchTraceResponse> {
+    /// Helper method to trace data flowing through a single port.
+    /// `direction` must be `TraceEventType::Input` or `TraceEventType::Output`.
+    pub async fn trace_port_data(
+        &self,
+        session_id: &str,
+        node_id: &str,
+        port_id: &str,
+        direction: TraceEventType,
+        data: serde_json::Value,
+    ) -> Result<()> {
+        let event = port_data_event(node_id, port_id, direction, data)?;
+        self.submit_event(session_id, event).await?;
+        Ok(())
+    }
+
+    /// Batch trace submission. Payloads are limited and compressed as in
+    /// [`submit_events`](Self::submit_events).
+    pub async fn submit_batch(&self, mut request: BatchTraceRequest) -> Result<BatchTraceResponse> {
         let url = format!(
             "{}/api/zip/traces/batch",
             self.base_url.trim_end_matches('/')
         );
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        for event in &mut request.events {
+            self.limit_event_data(event)?;
+        }
+        let response = self.post_json(&url, &request).await?;
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to submit batch trace: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("submit batch trace", status, &body));
         }
 
-        let batch_response = response.json::<BatchTraceResponse>().await?;
+        let batch_response = json::from_response::<BatchTraceResponse>(response).await?;
         Ok(batch_response)
     }
 
@@ -293,7 +547,408 @@ impl TracesAPI {
     pub fn current_session_id(&self) -> Option<&str> {
         self.session_id.as_deref()
     }
+
+    fn limit_event_data(&self, event: &mut TraceEvent) -> Result<()> {
+        match self.max_inline_data_size {
+            Some(max_size) => limit_inline_data(&mut event.data, max_size),
+            None => Ok(()),
+        }
+    }
+
+    /// POST `body` as JSON, gzip-compressed when it is over the compression
+    /// threshold
+    async fn post_json<T: Serialize>(&self, url: &str, body: &T) -> Result<reqwest::Response> {
+        let (body, compressed) = encode_json_body(body, self.compression_threshold)?;
+        let mut request = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json");
+        if compressed {
+            request = request.header("Content-Encoding", "gzip");
+        }
+        request.body(body).send().await
+    }
+}
+
+/// Keep a trace payload under `max_size` bytes of serialized JSON. When
+/// `full_data` is larger it is dropped, and its JSON text cut to `max_size`
+/// bytes becomes the preview. `size` still reports the original size.
+pub fn limit_inline_data(data: &mut TraceData, max_size: usize) -> Result<()> {
+    let Some(full_data) = &data.full_data else {
+        return Ok(());
+    };
+    let text = serde_json::to_string(full_data)?;
+    if text.len() <= max_size {
+        return Ok(());
+    }
+
+    let mut end = max_size;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    data.preview = Some(serde_json::Value::String(text[..end].to_string()));
+    data.full_data = None;
+    Ok(())
+}
+
+/// Serialize `body` as JSON, gzip-compressing it when it is larger than
+/// `threshold`. Returns the bytes and whether they are compressed.
+fn encode_json_body<T: Serialize>(body: &T, threshold: Option<usize>) -> Result<(Vec<u8>, bool)> {
+    let json = serde_json::to_vec(body)?;
+    match threshold {
+        Some(threshold) if json.len() > threshold => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&json)?;
+            Ok((encoder.finish()?, true))
+        }
+        _ => Ok((json, false)),
+    }
+}
+
+/// Wrap a JSON value as trace data
+fn json_trace_data(data: serde_json::Value) -> Result<TraceData> {
+    let data_str = serde_json::to_string(&data)?;
+    Ok(TraceData {
+        size: data_str.len(),
+        data_type: "application/json".to_string(),
+        preview: Some(data.clone()),
+        full_data: Some(data),
+    })
+}
+
+/// Build an input or output event for a port
+fn port_data_event(
+    node_id: &str,
+    port_id: &str,
+    direction: TraceEventType,
+    data: serde_json::Value,
+) -> Result<TraceEvent> {
+    if !matches!(direction, TraceEventType::Input | TraceEventType::Output) {
+        return Err(ZealError::validation_error(
+            "direction",
+            "Port data direction must be input or output",
+        ));
+    }
+
+    Ok(TraceEvent {
+        port_id: Some(port_id.to_string()),
+        data: json_trace_data(data)?,
+        ..TraceEvent::now(node_id, direction)
+    })
 }
 
 /// Re-export trace types from types.rs for convenience
 pub use crate::types::{TraceEvent, TraceEventType, TraceStatus};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    /// Returns one batch per poll, checking that `since` advances
+    struct IncrementalEvents {
+        polls: AtomicUsize,
+    }
+
+    impl Respond for IncrementalEvents {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let since = request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "since")
+                .map(|(_, value)| value.to_string());
+
+            let (expected_since, timestamps, status): (Option<&str>, &[i64], &str) =
+                match self.polls.fetch_add(1, Ordering::SeqCst) {
+                    0 => (None, &[1_000, 1_001], "running"),
+                    // `since` is inclusive, so the last seen event comes back
+                    1 => (Some("1001"), &[1_001], "running"),
+                    // A second event recorded in the same millisecond as the
+                    // last seen one must still be yielded
+                    2 => (Some("1001"), &[1_003, 1_001, 1_001, 1_002], "running"),
+                    _ => (Some("1003"), &[1_003, 1_004], "completed"),
+                };
+            assert_eq!(since.as_deref(), expected_since);
+
+            let events: Vec<TraceEvent> = timestamps
+                .iter()
+                .map(|&timestamp| TraceEvent {
+                    timestamp,
+                    ..TraceEvent::now(format!("node-{}", timestamp), TraceEventType::Output)
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "events": events,
+                "status": status
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tail_session_yields_new_events_until_terminal() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/traces/session-1/events"))
+            .respond_with(IncrementalEvents {
+                polls: AtomicUsize::new(0),
+            })
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri()).with_tail_poll_interval(Duration::from_millis(5));
+        let timestamps: Vec<i64> = api
+            .tail_session("session-1")
+            .map(|event| event.unwrap().timestamp)
+            .collect()
+            .await;
+
+        assert_eq!(timestamps, vec![1_000, 1_001, 1_001, 1_002, 1_003, 1_004]);
+    }
+
+    #[tokio::test]
+    async fn test_tail_session_not_found() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/traces/missing/events"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri());
+        let results: Vec<Result<TraceEvent>> = api.tail_session("missing").collect().await;
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(ZealError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_node_history_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/traces/workflows/wf-1/nodes/node-1/history"))
+            .and(query_param("limit", "2"))
+            .and(query_param("cursor", "page-2"))
+            .and(query_param("since", "1000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "records": [
+                    {
+                        "sessionId": "session-2",
+                        "status": "failed",
+                        "timestamp": 2_000,
+                        "duration": 150,
+                        "error": { "message": "Timed out", "stack": null, "code": "TIMEOUT" }
+                    },
+                    {
+                        "sessionId": "session-1",
+                        "executionId": "exec-1",
+                        "status": "completed",
+                        "timestamp": 1_000,
+                        "duration": 90
+                    }
+                ],
+                "nextCursor": "page-3"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri());
+        let page = api
+            .node_history_page(
+                "wf-1",
+                "node-1",
+                &NodeHistoryQuery {
+                    limit: Some(2),
+                    cursor: Some("page-2".to_string()),
+                    since: Some(1_000),
+                    until: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.next_cursor.as_deref(), Some("page-3"));
+        assert_eq!(page.records.len(), 2);
+        let failed = &page.records[0];
+        assert!(matches!(failed.status, TraceStatus::Failed));
+        assert_eq!(failed.duration, Some(Duration::from_millis(150)));
+        assert_eq!(failed.error.as_ref().unwrap().message, "Timed out");
+        assert_eq!(page.records[1].execution_id.as_deref(), Some("exec-1"));
+    }
+
+    #[tokio::test]
+    async fn test_node_history_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/zip/traces/workflows/wf-1/nodes/node-1/history"))
+            .and(query_param("limit", "10"))
+            .respond_with(ResponseTemplate::new(403).set_body_string("forbidden"))
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri());
+        let err = api.node_history("wf-1", "node-1", 10).await.unwrap_err();
+        assert!(matches!(err, ZealError::ApiError { status: 403, .. }));
+    }
+
+    /// Parse a request body, decompressing it if it was sent gzip-encoded
+    fn request_json(request: &Request) -> serde_json::Value {
+        let gzip = request
+            .headers
+            .iter()
+            .any(|(name, value)| name.as_str() == "content-encoding" && value.as_str() == "gzip");
+        if !gzip {
+            return serde_json::from_slice(&request.body).unwrap();
+        }
+        let decoder = flate2::read::GzDecoder::new(request.body.as_slice());
+        serde_json::from_reader(decoder).unwrap()
+    }
+
+    /// Acknowledges every event in the request body
+    struct CountingEvents;
+
+    impl Respond for CountingEvents {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body = request_json(request);
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "success": true,
+                "eventsProcessed": body["events"].as_array().unwrap().len()
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_events_splits_oversized_batches() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/traces/session-1/events"))
+            .respond_with(CountingEvents)
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri()).with_max_batch_size(3);
+        let events = (0..7)
+            .map(|i| TraceEvent::now(format!("node-{}", i), TraceEventType::Output))
+            .collect();
+        let response = api.submit_events("session-1", events).await.unwrap();
+        assert!(response.success);
+        assert_eq!(response.events_processed, 7);
+
+        let sizes: Vec<usize> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+                body["events"].as_array().unwrap().len()
+            })
+            .collect();
+        assert_eq!(sizes, [3, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_large_submissions_are_gzip_compressed() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/traces/session-1/events"))
+            .respond_with(CountingEvents)
+            .mount(&server)
+            .await;
+
+        let api = TracesAPI::new(&server.uri())
+            .with_compression_threshold(Some(1024))
+            .with_max_inline_data_size(None);
+        let small = TraceEvent::now("node-1", TraceEventType::Output);
+        let large = TraceEvent {
+            data: json_trace_data(serde_json::json!({ "rows": vec!["row"; 1000] })).unwrap(),
+            ..TraceEvent::now("node-2", TraceEventType::Output)
+        };
+        api.submit_events("session-1", vec![small]).await.unwrap();
+        let response = api.submit_events("session-1", vec![large]).await.unwrap();
+        assert_eq!(response.events_processed, 1);
+
+        let requests = server.received_requests().await.unwrap();
+        let encodings: Vec<Option<String>> = requests
+            .iter()
+            .map(|request| {
+                request
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name.as_str() == "content-encoding")
+                    .map(|(_, value)| value.as_str().to_string())
+            })
+            .collect();
+        assert_eq!(encodings, [None, Some("gzip".to_string())]);
+        assert!(requests[1].body.len() < 1024);
+
+        let body = request_json(&requests[1]);
+        assert_eq!(body["events"][0]["nodeId"], "node-2");
+        assert_eq!(
+            body["events"][0]["data"]["fullData"]["rows"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1000
+        );
+    }
+
+    #[test]
+    fn test_limit_inline_data() {
+        let mut data = json_trace_data(serde_json::json!({ "text": "é".repeat(100) })).unwrap();
+        let original_size = data.size;
+
+        limit_inline_data(&mut data, 1024).unwrap();
+        assert!(data.full_data.is_some());
+
+        limit_inline_data(&mut data, 16).unwrap();
+        assert_eq!(data.full_data, None);
+        // Cut on a character boundary, never splitting "é"
+        assert_eq!(data.preview, Some(serde_json::json!("{\"text\":\"ééé")));
+        assert_eq!(data.size, original_size);
+    }
+
+    #[test]
+    fn test_port_data_event() {
+        let event = port_data_event(
+            "node-1",
+            "out-1",
+            TraceEventType::Output,
+            serde_json::json!({ "value": 42 }),
+        )
+        .unwrap();
+        assert_eq!(event.node_id, "node-1");
+        assert_eq!(event.port_id.as_deref(), Some("out-1"));
+        assert!(matches!(event.event_type, TraceEventType::Output));
+        assert_eq!(
+            event.data.full_data,
+            Some(serde_json::json!({ "value": 42 }))
+        );
+
+        let event = port_data_event(
+            "node-1",
+            "in-1",
+            TraceEventType::Input,
+            serde_json::json!(1),
+        )
+        .unwrap();
+        assert_eq!(event.port_id.as_deref(), Some("in-1"));
+        assert!(matches!(event.event_type, TraceEventType::Input));
+
+        for direction in [
+            TraceEventType::Error,
+            TraceEventType::Log,
+            TraceEventType::Warning,
+        ] {
+            assert!(matches!(
+                port_data_event("node-1", "in-1", direction, serde_json::json!(1)),
+                Err(ZealError::ValidationError { .. })
+            ));
+        }
+    }
+}
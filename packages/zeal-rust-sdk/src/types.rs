@@ -70,6 +70,301 @@ pub struct NodeTemplate {
     pub display: Option<DisplayComponent>,
 }
 
+impl NodeTemplate {
+    /// Check the template for errors and collect non-fatal warnings.
+    ///
+    /// Errors name the offending field: an empty `id`, `title` or
+    /// `category`, no ports, duplicate port ids, a property whose validation
+    /// `min` exceeds its `max`, a `propertyRules` trigger that is not a
+    /// property key, or an invalid runtime version requirement. Ports placed
+    /// against the input-left/output-right convention are reported as
+    /// warnings according to `strictness`.
+    pub fn validate(
+        &self,
+        strictness: PortLayoutStrictness,
+    ) -> crate::errors::Result<Vec<ValidationWarning>> {
+        use crate::errors::ZealError;
+
+        for (field, value) in [
+            ("id", &self.id),
+            ("title", &self.title),
+            ("category", &self.category),
+        ] {
+            if value.trim().is_empty() {
+                return Err(ZealError::validation_error(
+                    field.to_string(),
+                    format!("Template '{}' must have a non-empty {}", self.id, field),
+                ));
+            }
+        }
+
+        if self.ports.is_empty() {
+            return Err(ZealError::validation_error(
+                "ports".to_string(),
+                format!("Template '{}' must have at least one port", self.id),
+            ));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for port in &self.ports {
+            if !seen.insert(port.id.as_str()) {
+                return Err(ZealError::validation_error(
+                    "ports".to_string(),
+                    format!("Duplicate port id '{}'", port.id),
+                ));
+            }
+        }
+
+        let properties = self.properties.as_ref();
+        for (key, property) in properties.into_iter().flatten() {
+            if let Some(PropertyValidation {
+                min: Some(min),
+                max: Some(max),
+                ..
+            }) = &property.validation
+            {
+                if min > max {
+                    return Err(ZealError::validation_error(
+                        format!("properties.{}.validation", key),
+                        format!("min ({}) is greater than max ({})", min, max),
+                    ));
+                }
+            }
+        }
+
+        if let Some(rules) = &self.property_rules {
+            for trigger in &rules.triggers {
+                if !properties.is_some_and(|p| p.contains_key(trigger)) {
+                    return Err(ZealError::validation_error(
+                        "propertyRules.triggers".to_string(),
+                        format!("Trigger '{}' is not a property of the template", trigger),
+                    ));
+                }
+            }
+        }
+
+        if let Some(runtime) = &self.runtime {
+            runtime.version_req()?;
+        }
+
+        Ok(self
+            .ports
+            .iter()
+            .filter_map(|port| port.validate_position_with(strictness).err())
+            .collect())
+    }
+
+    /// Compare this template with a newer version of it, reporting added,
+    /// removed and modified ports and properties along with changed metadata
+    /// (every other top-level field)
+    pub fn diff(&self, other: &NodeTemplate) -> TemplateDiff {
+        let ports = |template: &NodeTemplate| {
+            template
+                .ports
+                .iter()
+                .map(|port| (port.id.clone(), to_diff_value(port)))
+                .collect::<Vec<_>>()
+        };
+        let properties = |template: &NodeTemplate| {
+            let mut properties = template
+                .properties
+                .iter()
+                .flatten()
+                .map(|(key, property)| (key.clone(), to_diff_value(property)))
+                .collect::<Vec<_>>();
+            properties.sort_by(|a, b| a.0.cmp(&b.0));
+            properties
+        };
+
+        let mut before = to_diff_value(self);
+        let mut after = to_diff_value(other);
+        for value in [&mut before, &mut after] {
+            if let Some(object) = value.as_object_mut() {
+                object.remove("ports");
+                object.remove("properties");
+            }
+        }
+
+        TemplateDiff {
+            ports: ItemChanges::between(&ports(self), &ports(other)),
+            properties: ItemChanges::between(&properties(self), &properties(other)),
+            metadata: field_changes(&before, &after),
+        }
+    }
+
+    /// Changes in `other` that can invalidate existing nodes built from this
+    /// template: removed ports, ports whose data type changed, and removed
+    /// properties that were required
+    pub fn breaking_changes(&self, other: &NodeTemplate) -> Vec<ValidationWarning> {
+        let diff = self.diff(other);
+        let mut breaking: Vec<_> = diff
+            .ports
+            .removed
+            .iter()
+            .map(|id| ValidationWarning {
+                field: format!("ports.{}", id),
+                message: "port was removed".to_string(),
+            })
+            .collect();
+
+        for port in &diff.ports.modified {
+            if let Some(change) = port.changes.iter().find(|c| c.field == "dataType") {
+                breaking.push(ValidationWarning {
+                    field: format!("ports.{}", port.id),
+                    message: format!(
+                        "data type changed from {} to {}",
+                        change.before, change.after
+                    ),
+                });
+            }
+        }
+
+        for key in &diff.properties.removed {
+            let required = self
+                .properties
+                .as_ref()
+                .and_then(|properties| properties.get(key))
+                .and_then(|property| property.validation.as_ref())
+                .and_then(|validation| validation.required)
+                .unwrap_or(false);
+            if required {
+                breaking.push(ValidationWarning {
+                    field: format!("properties.{}", key),
+                    message: "required property was removed".to_string(),
+                });
+            }
+        }
+
+        breaking
+    }
+}
+
+/// Differences between two versions of a template, from
+/// [`NodeTemplate::diff`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateDiff {
+    pub ports: ItemChanges,
+    pub properties: ItemChanges,
+    /// Changed top-level fields other than ports and properties
+    pub metadata: Vec<FieldChange>,
+}
+
+impl TemplateDiff {
+    /// Whether the two versions are identical
+    pub fn is_empty(&self) -> bool {
+        self.ports.is_empty() && self.properties.is_empty() && self.metadata.is_empty()
+    }
+}
+
+/// Keyed items (ports by id, properties by key) that differ between versions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub modified: Vec<ModifiedItem>,
+}
+
+impl ItemChanges {
+    /// Whether no item was added, removed or modified
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    fn between(
+        before: &[(String, serde_json::Value)],
+        after: &[(String, serde_json::Value)],
+    ) -> Self {
+        let find = |items: &[(String, serde_json::Value)], key: &str| {
+            items.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+        };
+
+        let mut changes = Self::default();
+        for (key, old) in before {
+            match find(after, key) {
+                None => changes.removed.push(key.clone()),
+                Some(new) if &new != old => changes.modified.push(ModifiedItem {
+                    id: key.clone(),
+                    changes: field_changes(old, &new),
+                }),
+                Some(_) => {}
+            }
+        }
+        changes.added = after
+            .iter()
+            .filter(|(key, _)| find(before, key).is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        changes
+    }
+}
+
+/// An item present in both versions whose fields changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModifiedItem {
+    pub id: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A single field's value before and after; `null` when absent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+fn to_diff_value<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// Top-level fields that differ between two JSON objects, in key order
+fn field_changes(before: &serde_json::Value, after: &serde_json::Value) -> Vec<FieldChange> {
+    let null = serde_json::Value::Null;
+    let keys = before
+        .as_object()
+        .into_iter()
+        .chain(after.as_object())
+        .flat_map(|object| object.keys())
+        .collect::<std::collections::BTreeSet<_>>();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key).unwrap_or(&null);
+            let new = after.get(key).unwrap_or(&null);
+            (old != new).then(|| FieldChange {
+                field: key.clone(),
+                before: old.clone(),
+                after: new.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Non-fatal problem found while validating a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// How strictly port positions are checked against the convention that
+/// inputs sit on the left/top and outputs on the right/bottom
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PortLayoutStrictness {
+    /// Don't check port positions
+    Off,
+    /// Warn about inputs on the right and outputs on the left
+    #[default]
+    Lenient,
+    /// Also warn about inputs on the bottom and outputs on the top
+    Strict,
+}
+
 /// Node shape variants
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -102,6 +397,52 @@ pub struct Port {
     pub multiple: Option<bool>,
 }
 
+impl Port {
+    /// Warn if the port is on the side conventionally used by the opposite
+    /// port type (an input on the right or an output on the left)
+    pub fn validate_position(&self) -> Result<(), ValidationWarning> {
+        self.validate_position_with(PortLayoutStrictness::Lenient)
+    }
+
+    /// Like [`validate_position`](Self::validate_position) with configurable strictness
+    pub fn validate_position_with(
+        &self,
+        strictness: PortLayoutStrictness,
+    ) -> Result<(), ValidationWarning> {
+        let unconventional = match (&self.port_type, &self.position) {
+            (PortType::Input, PortPosition::Right) | (PortType::Output, PortPosition::Left) => {
+                strictness != PortLayoutStrictness::Off
+            }
+            (PortType::Input, PortPosition::Bottom) | (PortType::Output, PortPosition::Top) => {
+                strictness == PortLayoutStrictness::Strict
+            }
+            _ => false,
+        };
+
+        if !unconventional {
+            return Ok(());
+        }
+
+        let port_type = match self.port_type {
+            PortType::Input => "Input",
+            PortType::Output => "Output",
+        };
+        let position = match self.position {
+            PortPosition::Left => "left",
+            PortPosition::Right => "right",
+            PortPosition::Top => "top",
+            PortPosition::Bottom => "bottom",
+        };
+        Err(ValidationWarning {
+            field: format!("ports.{}", self.id),
+            message: format!(
+                "{} port is on the {}; inputs conventionally sit left/top and outputs right/bottom",
+                port_type, position
+            ),
+        })
+    }
+}
+
 /// Port type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -142,6 +483,8 @@ pub enum PropertyType {
     Boolean,
     Select,
     CodeEditor,
+    /// Sensitive value, encrypted client-side when secret encryption is configured
+    Secret,
 }
 
 /// Property validation rules
@@ -177,6 +520,41 @@ pub struct RuntimeRequirements {
     pub capabilities: Option<Vec<String>>,
 }
 
+impl RuntimeRequirements {
+    /// Parse `version` as a semver requirement (e.g. `">=1.0.0, <2.0.0"`)
+    pub fn version_req(&self) -> crate::errors::Result<Option<semver::VersionReq>> {
+        self.version
+            .as_deref()
+            .map(|version| {
+                semver::VersionReq::parse(version).map_err(|e| {
+                    crate::errors::ZealError::validation_error(
+                        "runtime.version".to_string(),
+                        format!("Invalid version requirement '{}': {}", version, e),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Check that every required env var is set in the current process,
+    /// returning the names of any that are missing
+    pub fn check_env(&self) -> Result<(), Vec<String>> {
+        let missing: Vec<String> = self
+            .required_env_vars
+            .iter()
+            .flatten()
+            .filter(|name| std::env::var_os(name.as_str()).is_none())
+            .cloned()
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+}
+
 /// Subcategory definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubcategoryDefinition {
@@ -302,11 +680,133 @@ pub struct CreateWorkflowResponse {
     #[serde(rename = "graphId")]
     pub graph_id: String,
     #[serde(rename = "embedUrl")]
-    pub embed_url: String,
+    pub embed_url: EmbedUrl,
 }
 
-/// 2D position
+/// URL of the embeddable editor for a workflow (`{app}/embed/{workflowId}`).
+/// Serialized as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EmbedUrl(url::Url);
+
+impl EmbedUrl {
+    /// The URL as a string
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Workflow id taken from the path segment after `embed`
+    pub fn workflow_id(&self) -> Option<&str> {
+        let mut segments = self.0.path_segments()?;
+        segments.find(|segment| *segment == "embed")?;
+        segments.next().filter(|id| !id.is_empty())
+    }
+
+    /// Return a copy with the given embed settings set as query parameters,
+    /// replacing any existing values for the same settings
+    pub fn with_options(&self, options: &EmbedOptions) -> Self {
+        let settings = options.query_pairs();
+        let mut url = self.0.clone();
+        let kept: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| !settings.iter().any(|(name, _)| name == key))
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+
+        if kept.is_empty() && settings.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut()
+                .clear()
+                .extend_pairs(kept)
+                .extend_pairs(settings);
+        }
+        Self(url)
+    }
+}
+
+impl std::str::FromStr for EmbedUrl {
+    type Err = crate::errors::ZealError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(url::Url::parse(s)?))
+    }
+}
+
+impl TryFrom<String> for EmbedUrl {
+    type Error = crate::errors::ZealError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<EmbedUrl> for String {
+    fn from(url: EmbedUrl) -> Self {
+        url.0.into()
+    }
+}
+
+impl std::fmt::Display for EmbedUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Display settings understood by the embedded editor. Unset options keep
+/// the editor's defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmbedOptions {
+    /// Show the minimap (`minimap`)
+    pub minimap: Option<bool>,
+    /// Show zoom controls (`zoom`)
+    pub zoom_controls: Option<bool>,
+    /// Show subgraph tabs (`tabs`)
+    pub subgraph_tabs: Option<bool>,
+    /// Allow users to add nodes (`allowCreate`)
+    pub allow_node_creation: Option<bool>,
+    /// Receive real-time updates from other editors (`collaborative`)
+    pub collaborative: Option<bool>,
+    /// Scroll to follow changes (`follow`)
+    pub follow: Option<bool>,
+}
+
+impl EmbedOptions {
+    fn query_pairs(&self) -> Vec<(&'static str, &'static str)> {
+        [
+            ("minimap", self.minimap),
+            ("zoom", self.zoom_controls),
+            ("tabs", self.subgraph_tabs),
+            ("allowCreate", self.allow_node_creation),
+            ("collaborative", self.collaborative),
+            ("follow", self.follow),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, if value? { "true" } else { "false" })))
+        .collect()
+    }
+}
+
+/// Update workflow request; only the provided fields are sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateWorkflowRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Update workflow response
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateWorkflowResponse {
+    pub success: bool,
+    pub workflow: serde_json::Value,
+}
+
+/// 2D position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub x: f64,
     pub y: f64,
@@ -332,6 +832,16 @@ pub struct AddNodeResponse {
     #[serde(rename = "nodeId")]
     pub node_id: String,
     pub node: NodeInfo,
+    /// Non-fatal warnings reported while creating the node
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl AddNodeResponse {
+    /// Whether the server reported any warnings
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
 }
 
 /// Node information
@@ -345,7 +855,7 @@ pub struct NodeInfo {
 }
 
 /// Node port reference
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NodePort {
     #[serde(rename = "nodeId")]
     pub node_id: String,
@@ -479,6 +989,28 @@ pub struct CreateTraceSessionResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Serde support for optional durations as integer milliseconds
+mod duration_ms {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(d) => serializer.serialize_u64(d.as_millis() as u64),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
+    }
+}
+
 /// Trace event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TraceEvent {
@@ -490,11 +1022,48 @@ pub struct TraceEvent {
     #[serde(rename = "eventType")]
     pub event_type: TraceEventType,
     pub data: TraceData,
+    /// Duration, sent as integer milliseconds
+    #[serde(default, with = "duration_ms")]
     pub duration: Option<std::time::Duration>,
     pub metadata: Option<TraceEventMetadata>,
     pub error: Option<TraceError>,
 }
 
+/// Millisecond timestamps below this value (early 1973) are almost certainly
+/// seconds passed by mistake
+const MIN_PLAUSIBLE_TIMESTAMP_MS: i64 = 100_000_000_000;
+
+impl TraceEvent {
+    /// Create an event for a node, timestamped now in milliseconds
+    pub fn now(node_id: impl Into<String>, event_type: TraceEventType) -> Self {
+        Self {
+            node_id: node_id.into(),
+            event_type,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the timestamp is too small to be milliseconds in the current era
+    pub fn timestamp_looks_like_seconds(&self) -> bool {
+        self.timestamp > 0 && self.timestamp < MIN_PLAUSIBLE_TIMESTAMP_MS
+    }
+
+    /// In debug builds, warn if the timestamp looks like seconds rather than
+    /// milliseconds. Returns whether a warning was logged.
+    pub fn debug_check_timestamp(&self) -> bool {
+        if cfg!(debug_assertions) && self.timestamp_looks_like_seconds() {
+            tracing::warn!(
+                "Trace event for node '{}' has timestamp {} which looks like seconds; \
+                 TraceEvent timestamps are milliseconds since the epoch",
+                self.node_id,
+                self.timestamp
+            );
+            return true;
+        }
+        false
+    }
+}
+
 impl Default for TraceEvent {
     fn default() -> Self {
         Self {
@@ -510,6 +1079,21 @@ impl Default for TraceEvent {
     }
 }
 
+/// One past execution of a node, as returned by the node history endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeExecutionRecord {
+    pub session_id: String,
+    pub execution_id: Option<String>,
+    pub status: TraceStatus,
+    /// When the execution started, in milliseconds since the Unix epoch
+    pub timestamp: i64,
+    /// Duration, sent as integer milliseconds
+    #[serde(default, with = "duration_ms")]
+    pub duration: Option<std::time::Duration>,
+    pub error: Option<TraceError>,
+}
+
 /// Trace event type
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -518,6 +1102,9 @@ pub enum TraceEventType {
     Output,
     Error,
     Log,
+    /// A node that finished but reported a warning
+    /// (see [`NodeWarningEvent`](crate::events::NodeWarningEvent))
+    Warning,
 }
 
 /// Trace data
@@ -659,6 +1246,367 @@ mod tests {
         assert_eq!(deserialized.id, template.id);
     }
 
+    fn port(id: &str, port_type: PortType, position: PortPosition) -> Port {
+        Port {
+            id: id.to_string(),
+            label: id.to_string(),
+            port_type,
+            position,
+            data_type: None,
+            required: None,
+            multiple: None,
+        }
+    }
+
+    #[test]
+    fn test_port_position_conventions() {
+        for conventional in [
+            port("in", PortType::Input, PortPosition::Left),
+            port("in", PortType::Input, PortPosition::Top),
+            port("out", PortType::Output, PortPosition::Right),
+            port("out", PortType::Output, PortPosition::Bottom),
+        ] {
+            assert_eq!(
+                conventional.validate_position_with(PortLayoutStrictness::Strict),
+                Ok(())
+            );
+        }
+
+        let warning = port("in", PortType::Input, PortPosition::Right)
+            .validate_position()
+            .unwrap_err();
+        assert_eq!(warning.field, "ports.in");
+        assert!(port("out", PortType::Output, PortPosition::Left)
+            .validate_position()
+            .is_err());
+
+        let bottom_input = port("in", PortType::Input, PortPosition::Bottom);
+        assert!(bottom_input.validate_position().is_ok());
+        assert!(bottom_input
+            .validate_position_with(PortLayoutStrictness::Strict)
+            .is_err());
+        assert!(port("in", PortType::Input, PortPosition::Right)
+            .validate_position_with(PortLayoutStrictness::Off)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_node_template_validate() {
+        let mut template: NodeTemplate = serde_json::from_value(serde_json::json!({
+            "id": "tpl", "type": "processor", "title": "T", "category": "c",
+            "description": "d", "icon": "i", "ports": []
+        }))
+        .unwrap();
+        template.ports = vec![
+            port("in", PortType::Input, PortPosition::Right),
+            port("out", PortType::Output, PortPosition::Right),
+        ];
+
+        let warnings = template.validate(PortLayoutStrictness::Lenient).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "ports.in");
+
+        template
+            .ports
+            .push(port("in", PortType::Input, PortPosition::Left));
+        assert!(template.validate(PortLayoutStrictness::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_node_template_validate_reports_offending_field() {
+        let valid: NodeTemplate = serde_json::from_value(serde_json::json!({
+            "id": "tpl", "type": "processor", "title": "T", "category": "c",
+            "description": "d", "icon": "i",
+            "ports": [{ "id": "in", "label": "In", "type": "input", "position": "left" }],
+            "properties": {
+                "retries": {
+                    "type": "number",
+                    "validation": { "min": 0, "max": 5 }
+                },
+                "mode": { "type": "select" }
+            },
+            "propertyRules": { "triggers": ["mode"], "rules": [] }
+        }))
+        .unwrap();
+        assert!(valid.validate(PortLayoutStrictness::Lenient).is_ok());
+
+        let field_of = |template: NodeTemplate| match template
+            .validate(PortLayoutStrictness::Lenient)
+            .unwrap_err()
+        {
+            crate::errors::ZealError::ValidationError { field, .. } => field,
+            other => panic!("unexpected error: {:?}", other),
+        };
+
+        let mut template = valid.clone();
+        template.category = " ".to_string();
+        assert_eq!(field_of(template), "category");
+
+        let mut template = valid.clone();
+        template.ports.clear();
+        assert_eq!(field_of(template), "ports");
+
+        let mut template = valid.clone();
+        let retries = template
+            .properties
+            .as_mut()
+            .unwrap()
+            .get_mut("retries")
+            .unwrap();
+        retries.validation.as_mut().unwrap().min = Some(10.0);
+        assert_eq!(field_of(template), "properties.retries.validation");
+
+        let mut template = valid;
+        template
+            .property_rules
+            .as_mut()
+            .unwrap()
+            .triggers
+            .push("missing".to_string());
+        assert_eq!(field_of(template), "propertyRules.triggers");
+    }
+
+    fn diff_template() -> NodeTemplate {
+        serde_json::from_value(serde_json::json!({
+            "id": "tpl", "type": "processor", "title": "T", "category": "c",
+            "description": "d", "icon": "i",
+            "ports": [
+                { "id": "in", "label": "In", "type": "input", "position": "left" },
+                { "id": "out", "label": "Out", "type": "output", "position": "right" }
+            ],
+            "properties": { "retries": { "type": "number" } }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_template_diff_port_removal() {
+        let before = diff_template();
+        assert!(before.diff(&before).is_empty());
+
+        let mut after = before.clone();
+        after.ports.retain(|port| port.id != "out");
+        after.ports[0].data_type = Some("string".to_string());
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.ports.removed, vec!["out"]);
+        assert!(diff.ports.added.is_empty());
+        assert_eq!(diff.ports.modified.len(), 1);
+        assert_eq!(diff.ports.modified[0].id, "in");
+        assert_eq!(diff.ports.modified[0].changes[0].field, "dataType");
+        assert!(diff.properties.is_empty() && diff.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_template_diff_property_addition() {
+        let before = diff_template();
+        let mut after = before.clone();
+        after.properties.as_mut().unwrap().insert(
+            "mode".to_string(),
+            serde_json::from_value(serde_json::json!({ "type": "select" })).unwrap(),
+        );
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.properties.added, vec!["mode"]);
+        assert!(diff.properties.removed.is_empty() && diff.properties.modified.is_empty());
+        assert!(diff.ports.is_empty());
+    }
+
+    #[test]
+    fn test_template_diff_metadata_change() {
+        let before = diff_template();
+        let mut after = before.clone();
+        after.title = "Renamed".to_string();
+        after.subtitle = Some("v2".to_string());
+
+        let diff = before.diff(&after);
+        let fields: Vec<_> = diff.metadata.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["subtitle", "title"]);
+        assert_eq!(diff.metadata[0].before, serde_json::Value::Null);
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["metadata"][1]["after"], "Renamed");
+    }
+
+    #[test]
+    fn test_template_breaking_changes() {
+        let mut before = diff_template();
+        before.properties.as_mut().unwrap().insert(
+            "url".to_string(),
+            serde_json::from_value(serde_json::json!({
+                "type": "string", "validation": { "required": true }
+            }))
+            .unwrap(),
+        );
+
+        let mut after = before.clone();
+        after.title = "Renamed".to_string();
+        after.properties.as_mut().unwrap().remove("retries");
+        assert!(before.breaking_changes(&after).is_empty());
+
+        after.ports.retain(|port| port.id != "out");
+        after.ports[0].data_type = Some("number".to_string());
+        after.properties.as_mut().unwrap().remove("url");
+        let fields: Vec<_> = before
+            .breaking_changes(&after)
+            .into_iter()
+            .map(|w| w.field)
+            .collect();
+        assert_eq!(fields, vec!["ports.out", "ports.in", "properties.url"]);
+    }
+
+    #[test]
+    fn test_embed_url_parsing() {
+        let response: CreateWorkflowResponse = serde_json::from_value(serde_json::json!({
+            "workflowId": "wf-1",
+            "graphId": "main",
+            "embedUrl": "https://zeal.example.com/embed/wf-1"
+        }))
+        .unwrap();
+        assert_eq!(response.embed_url.workflow_id(), Some("wf-1"));
+        assert_eq!(
+            response.embed_url.as_str(),
+            "https://zeal.example.com/embed/wf-1"
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["embedUrl"],
+            "https://zeal.example.com/embed/wf-1"
+        );
+
+        assert!("https://zeal.example.com/workflows"
+            .parse::<EmbedUrl>()
+            .unwrap()
+            .workflow_id()
+            .is_none());
+        assert!(serde_json::from_value::<EmbedUrl>(serde_json::json!("not a url")).is_err());
+    }
+
+    #[test]
+    fn test_embed_url_with_options() {
+        let url: EmbedUrl = "https://zeal.example.com/embed/wf-1?minimap=true&theme=dark"
+            .parse()
+            .unwrap();
+        let embedded = url.with_options(&EmbedOptions {
+            minimap: Some(false),
+            follow: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(
+            embedded.as_str(),
+            "https://zeal.example.com/embed/wf-1?theme=dark&minimap=false&follow=true"
+        );
+        assert_eq!(embedded.workflow_id(), Some("wf-1"));
+
+        let unchanged = "https://zeal.example.com/embed/wf-1"
+            .parse::<EmbedUrl>()
+            .unwrap();
+        assert_eq!(unchanged.with_options(&EmbedOptions::default()), unchanged);
+    }
+
+    #[test]
+    fn test_trace_event_now_uses_milliseconds() {
+        let before = chrono::Utc::now().timestamp_millis();
+        let event = TraceEvent::now("node-1", TraceEventType::Input);
+        let after = chrono::Utc::now().timestamp_millis();
+
+        assert_eq!(event.node_id, "node-1");
+        assert!(matches!(event.event_type, TraceEventType::Input));
+        assert!(event.timestamp >= before && event.timestamp <= after);
+        assert!(!event.timestamp_looks_like_seconds());
+        assert!(!event.debug_check_timestamp());
+    }
+
+    #[test]
+    fn test_trace_event_timestamp_in_seconds_is_flagged() {
+        let event = TraceEvent {
+            timestamp: chrono::Utc::now().timestamp(),
+            ..TraceEvent::now("node-1", TraceEventType::Output)
+        };
+
+        assert!(event.timestamp_looks_like_seconds());
+        assert_eq!(event.debug_check_timestamp(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn test_trace_event_duration_wire_format() {
+        let event = TraceEvent {
+            duration: Some(std::time::Duration::from_millis(1500)),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["duration"], serde_json::json!(1500));
+
+        let round_trip: TraceEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(
+            round_trip.duration,
+            Some(std::time::Duration::from_millis(1500))
+        );
+
+        let mut json = serde_json::to_value(TraceEvent::default()).unwrap();
+        assert!(json["duration"].is_null());
+        json.as_object_mut().unwrap().remove("duration");
+        let missing: TraceEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(missing.duration, None);
+    }
+
+    #[test]
+    fn test_trace_event_type_wire_format() {
+        for (event_type, wire) in [
+            (TraceEventType::Input, "input"),
+            (TraceEventType::Output, "output"),
+            (TraceEventType::Error, "error"),
+            (TraceEventType::Log, "log"),
+            (TraceEventType::Warning, "warning"),
+        ] {
+            assert_eq!(serde_json::to_value(&event_type).unwrap(), wire);
+        }
+
+        // Traces recorded before the warning type still deserialize
+        let event: TraceEvent = serde_json::from_value(serde_json::json!({
+            "timestamp": 1_700_000_000_000i64,
+            "nodeId": "node-1",
+            "eventType": "log",
+            "data": { "size": 2, "type": "application/json" }
+        }))
+        .unwrap();
+        assert!(matches!(event.event_type, TraceEventType::Log));
+
+        let event: TraceEvent = serde_json::from_value(serde_json::json!({
+            "timestamp": 1_700_000_000_000i64,
+            "nodeId": "node-1",
+            "eventType": "warning",
+            "data": { "size": 2, "type": "application/json" }
+        }))
+        .unwrap();
+        assert!(matches!(event.event_type, TraceEventType::Warning));
+    }
+
+    #[test]
+    fn test_add_node_response_warnings() {
+        let node = serde_json::json!({
+            "id": "node-1",
+            "type": "processor",
+            "position": { "x": 0.0, "y": 0.0 },
+            "metadata": {}
+        });
+
+        let without: AddNodeResponse =
+            serde_json::from_value(serde_json::json!({ "nodeId": "node-1", "node": node }))
+                .unwrap();
+        assert!(!without.has_warnings());
+
+        let with: AddNodeResponse = serde_json::from_value(serde_json::json!({
+            "nodeId": "node-1",
+            "node": node,
+            "warnings": ["Template 'http-v1' is deprecated", "Missing optional config 'timeout'"]
+        }))
+        .unwrap();
+        assert!(with.has_warnings());
+        assert_eq!(with.warnings.len(), 2);
+        assert_eq!(with.warnings[0], "Template 'http-v1' is deprecated");
+    }
+
     #[test]
     fn test_trace_event_default() {
         let event = TraceEvent::default();
@@ -674,4 +1622,71 @@ mod tests {
         assert_eq!(deserialized.x, 100.0);
         assert_eq!(deserialized.y, 200.0);
     }
+
+    /// Sets or clears an env var for the duration of a test, restoring the previous value on drop
+    struct EnvGuard {
+        name: &'static str,
+        previous: Option<std::ffi::OsString>,
+    }
+
+    impl EnvGuard {
+        fn set(name: &'static str, value: Option<&str>) -> Self {
+            let previous = std::env::var_os(name);
+            match value {
+                Some(value) => std::env::set_var(name, value),
+                None => std::env::remove_var(name),
+            }
+            Self { name, previous }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            match &self.previous {
+                Some(value) => std::env::set_var(self.name, value),
+                None => std::env::remove_var(self.name),
+            }
+        }
+    }
+
+    fn requirements(vars: &[&str]) -> RuntimeRequirements {
+        RuntimeRequirements {
+            executor: "test".to_string(),
+            version: None,
+            required_env_vars: Some(vars.iter().map(|v| v.to_string()).collect()),
+            capabilities: None,
+        }
+    }
+
+    #[test]
+    fn test_check_env_all_set() {
+        let _a = EnvGuard::set("ZEAL_SDK_TEST_ENV_SET_A", Some("1"));
+        let _b = EnvGuard::set("ZEAL_SDK_TEST_ENV_SET_B", Some(""));
+
+        let reqs = requirements(&["ZEAL_SDK_TEST_ENV_SET_A", "ZEAL_SDK_TEST_ENV_SET_B"]);
+        assert_eq!(reqs.check_env(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_env_reports_missing() {
+        let _set = EnvGuard::set("ZEAL_SDK_TEST_ENV_PRESENT", Some("value"));
+        let _unset = EnvGuard::set("ZEAL_SDK_TEST_ENV_MISSING", None);
+
+        let reqs = requirements(&["ZEAL_SDK_TEST_ENV_PRESENT", "ZEAL_SDK_TEST_ENV_MISSING"]);
+        assert_eq!(
+            reqs.check_env(),
+            Err(vec!["ZEAL_SDK_TEST_ENV_MISSING".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_check_env_without_requirements() {
+        let reqs = RuntimeRequirements {
+            executor: "test".to_string(),
+            version: None,
+            required_env_vars: None,
+            capabilities: None,
+        };
+        assert_eq!(reqs.check_env(), Ok(()));
+    }
 }
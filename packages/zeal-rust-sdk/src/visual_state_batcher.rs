@@ -0,0 +1,223 @@
+//! Coalescing of visual state updates
+//!
+//! [`VisualStateBatcher`] accepts [`VisualStateUpdate`]s as elements change
+//! and emits one combined update per flush interval, holding only the latest
+//! state of each element. A fast execution that moves many nodes through
+//! several states then reaches the editor as a few updates instead of one per
+//! transition.
+
+use crate::batching::{self, BatcherHandle, Pending};
+use crate::config::PerformanceConfig;
+use crate::errors::{Result, ZealError};
+use crate::events::{ElementType, VisualStateElement, VisualStateUpdate};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Coalesces visual state updates on a background task and emits them on a
+/// channel once per flush interval.
+///
+/// Dropping the batcher without calling [`close`](Self::close) still emits
+/// the changes it holds as one last update.
+#[derive(Debug)]
+pub struct VisualStateBatcher {
+    handle: BatcherHandle<mpsc::UnboundedSender<VisualStateUpdate>>,
+}
+
+impl VisualStateBatcher {
+    /// Start a batcher using `visual_state_flush_interval` from the
+    /// performance config. Returns the batcher and a receiver for the
+    /// combined updates.
+    pub fn spawn(
+        performance: &PerformanceConfig,
+    ) -> (Self, mpsc::UnboundedReceiver<VisualStateUpdate>) {
+        Self::with_interval(performance.visual_state_flush_interval)
+    }
+
+    /// Start a batcher that flushes `interval` after the first change it
+    /// holds
+    pub fn with_interval(interval: Duration) -> (Self, mpsc::UnboundedReceiver<VisualStateUpdate>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (output, flushed) = mpsc::unbounded_channel();
+        let pending = Coalesced {
+            elements: Vec::new(),
+            positions: HashMap::new(),
+            output,
+        };
+        let task = tokio::spawn(batching::run(receiver, pending, interval));
+
+        (
+            Self {
+                handle: BatcherHandle::new(sender, task),
+            },
+            flushed,
+        )
+    }
+
+    /// Queue an update. Never waits: updates are merged into the pending
+    /// batch as soon as the background task sees them.
+    pub fn update(&self, update: VisualStateUpdate) -> Result<()> {
+        let sender = self
+            .handle
+            .sender()
+            .ok_or_else(|| ZealError::other("Visual state batcher is closed"))?;
+        sender
+            .send(update)
+            .map_err(|_| ZealError::other("Visual state batcher task has stopped"))
+    }
+
+    /// Flush the pending changes and wait for the background task to finish
+    pub async fn close(mut self) {
+        self.handle.close().await;
+    }
+}
+
+/// Latest state of each changed element, in the order elements first changed
+#[derive(Debug)]
+struct Coalesced {
+    elements: Vec<VisualStateElement>,
+    positions: HashMap<(ElementType, String), usize>,
+    output: mpsc::UnboundedSender<VisualStateUpdate>,
+}
+
+impl Pending for Coalesced {
+    type Item = VisualStateUpdate;
+
+    fn push(&mut self, update: VisualStateUpdate) {
+        for element in update.elements {
+            match self
+                .positions
+                .entry((element.element_type.clone(), element.id.clone()))
+            {
+                Entry::Occupied(position) => self.elements[*position.get()] = element,
+                Entry::Vacant(position) => {
+                    position.insert(self.elements.len());
+                    self.elements.push(element);
+                }
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Changes only go out on the interval; an element's repeated changes
+    /// never grow the batch
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    async fn flush(&mut self) {
+        if self.elements.is_empty() {
+            return;
+        }
+        self.positions.clear();
+        let update = VisualStateUpdate {
+            elements: std::mem::take(&mut self.elements),
+        };
+        debug!("Flushing {} visual state changes", update.elements.len());
+        // Nobody listening is not an error; the changes are just dropped
+        let _ = self.output.send(update);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ElementState;
+
+    fn update(changes: &[(&str, ElementType, ElementState)]) -> VisualStateUpdate {
+        VisualStateUpdate {
+            elements: changes
+                .iter()
+                .map(|(id, element_type, state)| VisualStateElement {
+                    id: id.to_string(),
+                    element_type: element_type.clone(),
+                    state: state.clone(),
+                    progress: None,
+                    message: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn states(update: &VisualStateUpdate) -> Vec<(&str, ElementType, ElementState)> {
+        update
+            .elements
+            .iter()
+            .map(|e| (e.id.as_str(), e.element_type.clone(), e.state.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_coalescing_keeps_latest_state_per_element() {
+        use ElementState::*;
+        use ElementType::*;
+
+        let (batcher, mut flushed) = VisualStateBatcher::with_interval(Duration::from_secs(60));
+        batcher
+            .update(update(&[
+                ("node-1", Node, Running),
+                ("a", Connection, Running),
+            ]))
+            .unwrap();
+        batcher
+            .update(update(&[("node-2", Node, Running)]))
+            .unwrap();
+        batcher
+            .update(update(&[("node-1", Node, Success), ("a", Node, Pending)]))
+            .unwrap();
+        batcher
+            .update(update(&[("node-2", Node, Error), ("a", Connection, Error)]))
+            .unwrap();
+        batcher.close().await;
+
+        // One update, in first-changed order; a node and a connection may
+        // share an id without being merged
+        let combined = flushed.recv().await.unwrap();
+        assert_eq!(
+            states(&combined),
+            vec![
+                ("node-1", Node, Success),
+                ("a", Connection, Error),
+                ("node-2", Node, Error),
+                ("a", Node, Pending),
+            ]
+        );
+        assert!(flushed.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flushes_after_interval() {
+        let (batcher, mut flushed) = VisualStateBatcher::with_interval(Duration::from_millis(20));
+        batcher
+            .update(update(&[(
+                "node-1",
+                ElementType::Node,
+                ElementState::Running,
+            )]))
+            .unwrap();
+
+        let started = tokio::time::Instant::now();
+        let first = flushed.recv().await.unwrap();
+        assert_eq!(first.elements.len(), 1);
+        assert!(started.elapsed() >= Duration::from_millis(10));
+
+        // Each interval starts fresh
+        batcher
+            .update(update(&[(
+                "node-1",
+                ElementType::Node,
+                ElementState::Success,
+            )]))
+            .unwrap();
+        let second = flushed.recv().await.unwrap();
+        assert_eq!(second.elements[0].state, ElementState::Success);
+
+        batcher.close().await;
+        assert!(flushed.recv().await.is_none());
+    }
+}
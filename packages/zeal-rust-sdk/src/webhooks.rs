@@ -1,61 +1,123 @@
 //! Webhooks API for managing webhook subscriptions
 
-use crate::errors::{Result, ZealError};
+use crate::errors::{map_error_response, Result, ZealError};
+use crate::http::HttpClient;
+use crate::json;
 use crate::types::*;
+use futures_util::stream::{self, StreamExt};
+use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::Client;
+use std::collections::HashMap;
+
+/// Result of deleting every webhook in a namespace
+#[derive(Debug, Default)]
+pub struct DeleteWebhooksBatch {
+    /// IDs of the webhooks that were deleted
+    pub deleted: Vec<String>,
+    /// Per-webhook failures, keyed by webhook ID
+    pub errors: HashMap<String, ZealError>,
+}
+
+impl DeleteWebhooksBatch {
+    /// Number of webhooks removed
+    pub fn deleted_count(&self) -> usize {
+        self.deleted.len()
+    }
+
+    /// Check whether every webhook was deleted
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
 
 /// Webhooks API for managing webhook subscriptions
 pub struct WebhooksAPI {
     base_url: String,
-    client: Client,
+    client: HttpClient,
+    batch_concurrency: usize,
 }
 
 impl WebhooksAPI {
-    /// Create a new Webhooks API instance
+    /// Create a new Webhooks API instance with its own HTTP client and
+    /// connection pool (see [`ZealClient`](crate::ZealClient) for sharing one)
     pub fn new(base_url: &str) -> Self {
-        Self {
-            base_url: base_url.to_string(),
-            client: Client::new(),
-        }
+        Self::with_http_client(base_url, HttpClient::standalone())
     }
 
     /// Create a new Webhooks API instance with custom HTTP client
     pub fn with_client(base_url: &str, client: Client) -> Self {
+        Self::with_http_client(base_url, HttpClient::new(client))
+    }
+
+    /// Create with the SDK's shared HTTP transport
+    pub(crate) fn with_http_client(base_url: &str, client: HttpClient) -> Self {
         Self {
             base_url: base_url.to_string(),
             client,
+            batch_concurrency: crate::config::PerformanceConfig::default().max_connections_per_host,
         }
     }
 
+    /// Maximum concurrent requests for [`delete_all`](Self::delete_all)
+    /// (default: `max_connections_per_host`)
+    pub fn with_batch_concurrency(mut self, concurrency: usize) -> Self {
+        self.batch_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Get the base URL of the Zeal server
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
     /// Register a new webhook
     pub async fn register(&self, config: WebhookConfig) -> Result<WebhookRegistrationResponse> {
+        self.register_with_headers(config, HashMap::new()).await
+    }
+
+    /// Register a new webhook, sending `extra_headers` on the registration
+    /// request only (for example, a token for a gateway in front of Zeal).
+    /// Unlike [`WebhookConfig::headers`], these are not sent on deliveries.
+    pub async fn register_with_headers(
+        &self,
+        config: WebhookConfig,
+        extra_headers: HashMap<String, String>,
+    ) -> Result<WebhookRegistrationResponse> {
         let url = format!(
             "{}/api/zip/webhooks/register",
             self.base_url.trim_end_matches('/')
         );
 
-        let response = self
+        let mut request = self
             .client
             .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&config)
-            .send()
-            .await?;
+            .header("Content-Type", "application/json");
+        for (name, value) in &extra_headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                ZealError::validation_error(
+                    "headers".to_string(),
+                    format!("Invalid header name: {}", name),
+                )
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                ZealError::validation_error(
+                    "headers".to_string(),
+                    format!("Invalid value for header {}", name),
+                )
+            })?;
+            request = request.header(name, value);
+        }
+
+        let response = request.json(&config).send().await?;
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to register webhook: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("register webhook", status, &body));
         }
 
-        let registration_response = response.json::<WebhookRegistrationResponse>().await?;
+        let registration_response =
+            json::from_response::<WebhookRegistrationResponse>(response).await?;
         Ok(registration_response)
     }
 
@@ -71,18 +133,11 @@ impl WebhooksAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to list webhooks: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("list webhooks", status, &body));
         }
 
-        let webhooks = response.json::<Vec<WebhookRegistrationResponse>>().await?;
+        let webhooks = json::from_response::<Vec<WebhookRegistrationResponse>>(response).await?;
         Ok(webhooks)
     }
 
@@ -108,18 +163,11 @@ impl WebhooksAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to update webhook: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("update webhook", status, &body));
         }
 
-        let update_response = response.json::<WebhookRegistrationResponse>().await?;
+        let update_response = json::from_response::<WebhookRegistrationResponse>(response).await?;
         Ok(update_response)
     }
 
@@ -135,20 +183,62 @@ impl WebhooksAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to delete webhook: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("delete webhook", status, &body));
         }
 
         Ok(())
     }
 
+    /// Delete every webhook registered in `namespace`, running at most the
+    /// configured [batch concurrency](Self::with_batch_concurrency) requests
+    /// at a time.
+    ///
+    /// As a guard against accidental mass deletion, `confirm_namespace` must
+    /// repeat `namespace` exactly; otherwise nothing is listed or deleted. A
+    /// failure for one webhook is recorded in the batch instead of aborting
+    /// the others.
+    pub async fn delete_all(
+        &self,
+        namespace: &str,
+        confirm_namespace: &str,
+    ) -> Result<DeleteWebhooksBatch> {
+        if namespace.is_empty() || namespace != confirm_namespace {
+            return Err(ZealError::validation_error(
+                "confirm_namespace",
+                "must match the namespace whose webhooks are being deleted",
+            ));
+        }
+
+        let webhooks = self.list(namespace).await?;
+        let results = stream::iter(webhooks)
+            .map(|webhook| async move {
+                let result = self.delete(&webhook.webhook_id).await;
+                (webhook.webhook_id, result)
+            })
+            .buffer_unordered(self.batch_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut batch = DeleteWebhooksBatch::default();
+        for (webhook_id, result) in results {
+            match result {
+                Ok(()) => batch.deleted.push(webhook_id),
+                Err(err) => {
+                    batch.errors.insert(webhook_id, err);
+                }
+            }
+        }
+
+        tracing::info!(
+            "Deleted {} webhooks in namespace {} ({} failed)",
+            batch.deleted.len(),
+            namespace,
+            batch.errors.len()
+        );
+        Ok(batch)
+    }
+
     /// Get a specific webhook by ID
     pub async fn get(&self, webhook_id: &str) -> Result<WebhookRegistrationResponse> {
         let url = format!(
@@ -161,18 +251,11 @@ impl WebhooksAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to get webhook: {}", status),
-                Some(error_text),
-            ));
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("get webhook", status, &body));
         }
 
-        let webhook = response.json::<WebhookRegistrationResponse>().await?;
+        let webhook = json::from_response::<WebhookRegistrationResponse>(response).await?;
         Ok(webhook)
     }
 
@@ -188,18 +271,35 @@ impl WebhooksAPI {
 
         let status = response.status();
         if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(ZealError::api_error(
-                status.as_u16(),
-                format!("Failed to test webhook: {}", status),
-                Some(error_text),
+            let body = response.text().await.unwrap_or_default();
+            return Err(map_error_response("test webhook", status, &body));
+        }
+
+        let test_response = json::from_response::<TestWebhookResponse>(response).await?;
+        Ok(test_response)
+    }
+
+    /// Test a webhook endpoint and fail with [`ZealError::WebhookUnreachable`]
+    /// if Zeal could not deliver to it
+    pub async fn ensure_reachable(
+        &self,
+        webhook_id: &str,
+        webhook_url: &str,
+    ) -> Result<TestWebhookResponse> {
+        let test_response = self.test(webhook_id).await?;
+        if !test_response.success {
+            let detail = test_response.error.clone().unwrap_or_else(|| {
+                format!(
+                    "test delivery returned status {}",
+                    test_response.status_code
+                )
+            });
+            return Err(ZealError::webhook_unreachable(
+                webhook_url.to_string(),
+                detail,
             ));
         }
 
-        let test_response = response.json::<TestWebhookResponse>().await?;
         Ok(test_response)
     }
 }
@@ -212,3 +312,185 @@ pub struct TestWebhookResponse {
     pub response_time_ms: u64,
     pub error: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, path_regex, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mount_test_response(server: &MockServer, body: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(path("/api/zip/webhooks/wh-1/test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    fn registration_config() -> WebhookConfig {
+        WebhookConfig {
+            namespace: "test".to_string(),
+            url: "http://example.com/webhooks".to_string(),
+            events: Some(vec!["*".to_string()]),
+            headers: Some(HashMap::from([(
+                "x-delivery-secret".to_string(),
+                "delivery".to_string(),
+            )])),
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_with_headers_sends_extra_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/zip/webhooks/register"))
+            .and(header("x-gateway-token", "secret-token"))
+            .and(header("x-tenant", "acme"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "webhookId": "wh-1",
+                "namespace": "test",
+                "url": "http://example.com/webhooks",
+                "events": ["*"],
+                "isActive": true,
+                "createdAt": "2024-01-01T00:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let api = WebhooksAPI::new(&server.uri());
+        let response = api
+            .register_with_headers(
+                registration_config(),
+                HashMap::from([
+                    ("x-gateway-token".to_string(), "secret-token".to_string()),
+                    ("x-tenant".to_string(), "acme".to_string()),
+                ]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.webhook_id, "wh-1");
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(!requests[0]
+            .headers
+            .iter()
+            .any(|(name, _)| name.as_str() == "x-delivery-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_register_with_headers_rejects_invalid_header() {
+        let api = WebhooksAPI::new("http://127.0.0.1:1");
+        let err = api
+            .register_with_headers(
+                registration_config(),
+                HashMap::from([("bad header".to_string(), "value".to_string())]),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZealError::ValidationError { field, .. } if field == "headers"));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_reachable_reports_unreachable_endpoint() {
+        let server = MockServer::start().await;
+        mount_test_response(
+            &server,
+            serde_json::json!({
+                "success": false,
+                "status_code": 0,
+                "response_time_ms": 5000,
+                "error": "connect ECONNREFUSED 10.0.0.5:3001"
+            }),
+        )
+        .await;
+
+        let api = WebhooksAPI::new(&server.uri());
+        let err = api
+            .ensure_reachable("wh-1", "http://10.0.0.5:3001/webhooks")
+            .await
+            .unwrap_err();
+
+        match err {
+            ZealError::WebhookUnreachable { url, detail } => {
+                assert_eq!(url, "http://10.0.0.5:3001/webhooks");
+                assert!(detail.contains("ECONNREFUSED"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_reachable_passes_successful_test() {
+        let server = MockServer::start().await;
+        mount_test_response(
+            &server,
+            serde_json::json!({
+                "success": true,
+                "status_code": 200,
+                "response_time_ms": 12,
+                "error": null
+            }),
+        )
+        .await;
+
+        let api = WebhooksAPI::new(&server.uri());
+        let response = api
+            .ensure_reachable("wh-1", "http://example.com/webhooks")
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 200);
+    }
+
+    #[tokio::test]
+    async fn test_delete_all_removes_every_webhook_in_namespace() {
+        let server = MockServer::start().await;
+        let webhook = |id: &str| {
+            serde_json::json!({
+                "webhookId": id,
+                "namespace": "runtime",
+                "url": format!("https://hooks.example.com/{}", id),
+                "events": ["*"],
+                "isActive": true,
+                "createdAt": "2024-05-01T12:00:00Z"
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/zip/webhooks"))
+            .and(query_param("namespace", "runtime"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                webhook("wh-1"),
+                webhook("wh-2"),
+                webhook("wh-3")
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/zip/webhooks/wh-2"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"^/api/zip/webhooks/wh-[13]$"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let api = WebhooksAPI::new(&server.uri()).with_batch_concurrency(2);
+        let err = api.delete_all("runtime", "other").await.unwrap_err();
+        assert!(matches!(err, ZealError::ValidationError { .. }));
+
+        let mut batch = api.delete_all("runtime", "runtime").await.unwrap();
+        batch.deleted.sort();
+        assert_eq!(batch.deleted_count(), 2);
+        assert_eq!(batch.deleted, ["wh-1", "wh-3"]);
+        assert!(!batch.is_complete());
+        assert!(matches!(
+            batch.errors["wh-2"],
+            ZealError::ApiError { status: 403, .. }
+        ));
+    }
+}
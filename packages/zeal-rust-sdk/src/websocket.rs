@@ -0,0 +1,921 @@
+//! WebSocket client for real-time ZIP events
+//!
+//! [`ZealWebSocketClient`] connects to `{base_url}/ws/zip`, subscribes to a
+//! workflow and yields [`ZipWebSocketEvent`]s. After sending a
+//! [`SubscribeEvent`] the server answers with either a [`SubscribedEvent`] or a
+//! [`SubscribeErrorEvent`]; [`subscribe`] waits for that answer so a rejected or
+//! lost subscription surfaces as an error instead of a connection that never
+//! delivers events.
+
+use crate::config::WebSocketConfig;
+use crate::errors::{Result, ZealError};
+use crate::events::{
+    ExecutionFailure, PingEvent, PongEvent, SubscribeErrorEvent, SubscribeEvent, SubscribedEvent,
+    ZipWebSocketEvent,
+};
+use crate::json;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig as ProtocolConfig;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, warn};
+
+/// Client for the ZIP WebSocket endpoint
+#[derive(Debug, Clone)]
+pub struct ZealWebSocketClient {
+    url: String,
+    config: WebSocketConfig,
+}
+
+impl ZealWebSocketClient {
+    /// Create a client for `{base_url}/ws/zip`. `http(s)` base URLs are mapped
+    /// to `ws(s)`.
+    pub fn new(base_url: &str, config: WebSocketConfig) -> Result<Self> {
+        Self::with_path(base_url, crate::WS_PATH, config)
+    }
+
+    /// Create a client for a custom WebSocket path
+    pub fn with_path(base_url: &str, path: &str, config: WebSocketConfig) -> Result<Self> {
+        if !(config.latency_ema_alpha > 0.0 && config.latency_ema_alpha <= 1.0) {
+            return Err(ZealError::configuration_error(format!(
+                "latency_ema_alpha must be in (0, 1], got {}",
+                config.latency_ema_alpha
+            )));
+        }
+
+        let mut url = url::Url::parse(base_url)?;
+        let scheme = match url.scheme() {
+            "http" | "ws" => "ws",
+            "https" | "wss" => "wss",
+            other => {
+                return Err(ZealError::configuration_error(format!(
+                    "Unsupported URL scheme for WebSocket: {}",
+                    other
+                )))
+            }
+        };
+        url.set_scheme(scheme)
+            .map_err(|_| ZealError::configuration_error("Invalid WebSocket URL"))?;
+        url.set_path(path);
+
+        Ok(Self {
+            url: url.to_string(),
+            config,
+        })
+    }
+
+    /// WebSocket URL the client connects to
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Connect, subscribe to a workflow and stream its events.
+    ///
+    /// Returns once the subscription is acknowledged. Messages that cannot be
+    /// parsed are yielded as errors; ping/pong keep-alives are handled
+    /// internally and not yielded. The connection closes when the stream is
+    /// dropped.
+    ///
+    /// If the connection is lost it is re-established with exponential
+    /// backoff, starting at `reconnect_delay` and capped at
+    /// `max_reconnect_delay`, and the subscription is sent again. After
+    /// `max_reconnect_attempts` failed attempts a connection error is yielded
+    /// and the stream ends. A close frame from the server ends the stream
+    /// without reconnecting. See [`ZipEventStream::state`] to observe the
+    /// connection and [`ZipEventStream::latency`] for its round-trip latency.
+    pub async fn subscribe(
+        &self,
+        workflow_id: &str,
+        graph_id: Option<&str>,
+    ) -> Result<ZipEventStream> {
+        let subscriptions = vec![SubscribeEvent {
+            event_type: "subscribe".to_string(),
+            workflow_id: workflow_id.to_string(),
+            graph_id: graph_id.map(str::to_string),
+        }];
+        let socket = self.connect(&subscriptions).await?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let (state_sender, state) = watch::channel(SocketState::Connected);
+        let (latency_sender, latency) = watch::channel(Latency::default());
+        let task = tokio::spawn(self.clone().maintain(
+            socket,
+            subscriptions,
+            sender,
+            state_sender,
+            latency_sender,
+        ));
+
+        Ok(ZipEventStream {
+            receiver,
+            state,
+            latency,
+            task,
+        })
+    }
+
+    /// Open a connection and send every subscription, waiting for each ack
+    async fn connect(&self, subscriptions: &[SubscribeEvent]) -> Result<Socket> {
+        let protocol = ProtocolConfig {
+            max_message_size: Some(self.config.max_message_size),
+            max_frame_size: Some(self.config.max_frame_size),
+            ..Default::default()
+        };
+
+        let (mut socket, _) = tokio::time::timeout(
+            self.config.connection_timeout,
+            tokio_tungstenite::connect_async_with_config(&self.url, Some(protocol), false),
+        )
+        .await
+        .map_err(|_| ZealError::timeout_error(format!("connect to {}", self.url)))??;
+
+        for request in subscriptions {
+            subscribe(&mut socket, request, &self.config).await?;
+        }
+
+        Ok(socket)
+    }
+
+    /// Run the connection, reconnecting with backoff whenever it is lost
+    async fn maintain(
+        self,
+        mut socket: Socket,
+        subscriptions: Vec<SubscribeEvent>,
+        events: mpsc::UnboundedSender<Result<ZipWebSocketEvent>>,
+        state: watch::Sender<SocketState>,
+        latency: watch::Sender<Latency>,
+    ) {
+        loop {
+            match run_connection(&mut socket, &events, &latency, &self.config).await {
+                Disconnect::Closed => break,
+                Disconnect::Lost(reason) => warn!("ZIP WebSocket connection lost: {}", reason),
+            }
+
+            let mut attempt = 0;
+            socket = loop {
+                attempt += 1;
+                if attempt > self.config.max_reconnect_attempts {
+                    let _ = events.send(Err(ZealError::connection_error(format!(
+                        "Gave up reconnecting to {} after {} attempts",
+                        self.url, self.config.max_reconnect_attempts
+                    ))));
+                    state.send_replace(SocketState::Disconnected);
+                    return;
+                }
+
+                state.send_replace(SocketState::Reconnecting { attempt });
+                tokio::time::sleep(reconnect_backoff(&self.config, attempt)).await;
+
+                match self.connect(&subscriptions).await {
+                    Ok(socket) => break socket,
+                    Err(err) => warn!(
+                        "ZIP WebSocket reconnect attempt {} failed: {}",
+                        attempt, err
+                    ),
+                }
+            };
+
+            debug!("ZIP WebSocket reconnected after {} attempt(s)", attempt);
+            state.send_replace(SocketState::Connected);
+        }
+
+        let _ = socket.close(None).await;
+        state.send_replace(SocketState::Disconnected);
+    }
+}
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Delay before the given (1-based) reconnect attempt: `reconnect_delay`
+/// doubled per attempt, capped at `max_reconnect_delay`
+fn reconnect_backoff(config: &WebSocketConfig, attempt: usize) -> Duration {
+    let factor = u32::try_from(attempt.saturating_sub(1))
+        .ok()
+        .and_then(|shift| 1u32.checked_shl(shift))
+        .unwrap_or(u32::MAX);
+    config
+        .reconnect_delay
+        .saturating_mul(factor)
+        .min(config.max_reconnect_delay)
+}
+
+/// State of the connection behind a [`ZipEventStream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketState {
+    /// Connected and subscribed
+    Connected,
+    /// The connection was lost; waiting for the given (1-based) reconnect attempt
+    Reconnecting { attempt: usize },
+    /// Closed by the server, or reconnecting gave up. No more events will arrive.
+    Disconnected,
+}
+
+/// Round-trip latency measured from keep-alive pings
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Latency {
+    /// Round trip of the most recent ping
+    pub latest: Option<Duration>,
+    /// Exponential moving average of the round trips, weighted by
+    /// [`WebSocketConfig::latency_ema_alpha`]
+    pub ema: Option<Duration>,
+}
+
+impl Latency {
+    /// Fold a round-trip sample into the latest and smoothed latency
+    fn record(&mut self, round_trip: Duration, alpha: f64) {
+        self.latest = Some(round_trip);
+        self.ema = Some(match self.ema {
+            Some(ema) => ema.mul_f64(1.0 - alpha) + round_trip.mul_f64(alpha),
+            None => round_trip,
+        });
+    }
+}
+
+/// Stream of events from a WebSocket subscription
+#[derive(Debug)]
+pub struct ZipEventStream {
+    receiver: mpsc::UnboundedReceiver<Result<ZipWebSocketEvent>>,
+    state: watch::Receiver<SocketState>,
+    latency: watch::Receiver<Latency>,
+    task: JoinHandle<()>,
+}
+
+impl ZipEventStream {
+    /// Watch the connection state, e.g. to show that the socket is down
+    /// while it is reconnecting
+    pub fn state(&self) -> watch::Receiver<SocketState> {
+        self.state.clone()
+    }
+
+    /// Watch the ping round-trip latency, updated on every pong. It carries
+    /// over reconnects, so a rising average can inform reconnect decisions.
+    pub fn latency(&self) -> watch::Receiver<Latency> {
+        self.latency.clone()
+    }
+
+    /// Smoothed ping round-trip latency, or `None` before the first pong
+    pub fn latency_ema(&self) -> Option<Duration> {
+        self.latency.borrow().ema
+    }
+
+    /// Keep only `execution.failed` and `node.failed` events, as
+    /// [`ExecutionFailure`] summaries. Errors are passed through, so a
+    /// connection that gives up is still noticed.
+    pub fn failures(self) -> impl Stream<Item = Result<ExecutionFailure>> {
+        self.filter_map(|event| {
+            let failure = match event {
+                Ok(ZipWebSocketEvent::Execution(event)) => {
+                    ExecutionFailure::from_execution_event(&event).map(Ok)
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            };
+            futures_util::future::ready(failure)
+        })
+    }
+}
+
+impl Stream for ZipEventStream {
+    type Item = Result<ZipWebSocketEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for ZipEventStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Why [`run_connection`] returned
+enum Disconnect {
+    /// The server closed the connection, or the stream was dropped
+    Closed,
+    /// The connection failed and should be re-established
+    Lost(String),
+}
+
+/// Forward events from the socket and keep the connection alive with pings,
+/// recording each ping's round trip in `latency`
+async fn run_connection<S>(
+    socket: &mut S,
+    events: &mpsc::UnboundedSender<Result<ZipWebSocketEvent>>,
+    latency: &watch::Sender<Latency>,
+    config: &WebSocketConfig,
+) -> Disconnect
+where
+    S: Sink<Message, Error = WsError>
+        + Stream<Item = std::result::Result<Message, WsError>>
+        + Unpin,
+{
+    let mut ping = tokio::time::interval(config.ping_interval);
+    ping.tick().await;
+    let mut awaiting_pong: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            message = socket.next() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Ping(payload))) => {
+                        let _ = socket.send(Message::Pong(payload)).await;
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) => {
+                        debug!("ZIP WebSocket connection closed by the server");
+                        return Disconnect::Closed;
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => return Disconnect::Lost(err.to_string()),
+                    None => return Disconnect::Lost("connection reset".to_string()),
+                };
+
+                let item = match parse_message(&text) {
+                    Ok(Incoming::Ping(timestamp)) => {
+                        let pong = PongEvent {
+                            event_type: "pong".to_string(),
+                            timestamp,
+                        };
+                        if let Ok(pong) = serde_json::to_string(&pong) {
+                            let _ = socket.send(Message::Text(pong)).await;
+                        }
+                        continue;
+                    }
+                    Ok(Incoming::Pong) => {
+                        if let Some(sent) = awaiting_pong.take() {
+                            let round_trip = sent.elapsed();
+                            latency.send_modify(|latency| {
+                                latency.record(round_trip, config.latency_ema_alpha)
+                            });
+                        }
+                        continue;
+                    }
+                    Ok(Incoming::Event(event)) => Ok(*event),
+                    Err(err) => Err(err),
+                };
+                if events.send(item).is_err() {
+                    return Disconnect::Closed;
+                }
+            }
+            _ = ping.tick() => {
+                if let Some(sent) = awaiting_pong {
+                    if sent.elapsed() >= config.pong_timeout {
+                        return Disconnect::Lost("no pong received before the timeout".to_string());
+                    }
+                    continue;
+                }
+
+                let ping = PingEvent {
+                    event_type: "ping".to_string(),
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                };
+                let Ok(ping) = serde_json::to_string(&ping) else {
+                    continue;
+                };
+                if let Err(err) = socket.send(Message::Text(ping)).await {
+                    return Disconnect::Lost(err.to_string());
+                }
+                awaiting_pong = Some(Instant::now());
+            }
+        }
+    }
+}
+
+enum Incoming {
+    Ping(i64),
+    Pong,
+    Event(Box<ZipWebSocketEvent>),
+}
+
+fn parse_message(text: &str) -> Result<Incoming> {
+    let value: serde_json::Value = json::from_slice(text.as_bytes())?;
+    match value.get("type").and_then(|t| t.as_str()) {
+        Some("ping") => Ok(Incoming::Ping(
+            value
+                .get("timestamp")
+                .and_then(|t| t.as_i64())
+                .unwrap_or_default(),
+        )),
+        Some("pong") => Ok(Incoming::Pong),
+        _ => Ok(Incoming::Event(Box::new(ZipWebSocketEvent::from_json(
+            value,
+        )?))),
+    }
+}
+
+enum SubscribeAck {
+    Accepted(SubscribedEvent),
+    Rejected(SubscribeErrorEvent),
+}
+
+/// Send a subscribe request and wait for the server to acknowledge it.
+///
+/// The request is resent up to `config.subscribe_retries` times if no
+/// acknowledgement arrives within `config.subscribe_timeout`. A rejection is
+/// returned immediately as a WebSocket error. Other messages received while
+/// waiting are discarded.
+pub async fn subscribe<S>(
+    socket: &mut S,
+    request: &SubscribeEvent,
+    config: &WebSocketConfig,
+) -> Result<SubscribedEvent>
+where
+    S: Sink<Message, Error = WsError>
+        + Stream<Item = std::result::Result<Message, WsError>>
+        + Unpin,
+{
+    let payload = serde_json::to_string(request)?;
+    let attempts = config.subscribe_retries + 1;
+
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            warn!(
+                "Subscription to workflow '{}' not acknowledged, retrying ({}/{})",
+                request.workflow_id, attempt, attempts
+            );
+        }
+
+        socket.send(Message::Text(payload.clone())).await?;
+
+        let ack = tokio::time::timeout(
+            config.subscribe_timeout,
+            wait_for_ack(socket, &request.workflow_id),
+        )
+        .await;
+
+        match ack {
+            Ok(Ok(SubscribeAck::Accepted(event))) => return Ok(event),
+            Ok(Ok(SubscribeAck::Rejected(event))) => {
+                return Err(ZealError::websocket_error(format!(
+                    "Subscription to workflow '{}' rejected: {}",
+                    request.workflow_id, event.error
+                )))
+            }
+            Ok(Err(e)) => return Err(e),
+            Err(_) => continue,
+        }
+    }
+
+    Err(ZealError::timeout_error(format!(
+        "subscribe to workflow '{}' ({} attempts without acknowledgement)",
+        request.workflow_id, attempts
+    )))
+}
+
+async fn wait_for_ack<S>(socket: &mut S, workflow_id: &str) -> Result<SubscribeAck>
+where
+    S: Stream<Item = std::result::Result<Message, WsError>> + Unpin,
+{
+    while let Some(message) = socket.next().await {
+        let Message::Text(text) = message? else {
+            continue;
+        };
+        let Ok(value) = json::from_slice::<serde_json::Value>(text.as_bytes()) else {
+            continue;
+        };
+
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("subscribed") => {
+                let event: SubscribedEvent = serde_json::from_value(value)?;
+                if event.workflow_id == workflow_id {
+                    return Ok(SubscribeAck::Accepted(event));
+                }
+            }
+            Some("subscribe.error") => {
+                let event: SubscribeErrorEvent = serde_json::from_value(value)?;
+                if event
+                    .workflow_id
+                    .as_deref()
+                    .is_none_or(|id| id == workflow_id)
+                {
+                    return Ok(SubscribeAck::Rejected(event));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(ZealError::websocket_error(
+        "Connection closed before the subscription was acknowledged",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Start a WebSocket server that answers the n-th subscribe message
+    /// (1-based) with `reply`, ignoring earlier ones
+    async fn server(answer_on: usize, reply: serde_json::Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut received = 0;
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+                if value["type"] != "subscribe" {
+                    continue;
+                }
+                received += 1;
+                if received == answer_on {
+                    // Unrelated traffic before the acknowledgement is skipped
+                    ws.send(Message::Text(r#"{"type":"pong","timestamp":0}"#.into()))
+                        .await
+                        .unwrap();
+                    ws.send(Message::Text(reply.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    fn request() -> SubscribeEvent {
+        SubscribeEvent {
+            event_type: "subscribe".to_string(),
+            workflow_id: "wf-1".to_string(),
+            graph_id: None,
+        }
+    }
+
+    fn config(retries: usize) -> WebSocketConfig {
+        WebSocketConfig {
+            subscribe_timeout: Duration::from_millis(200),
+            subscribe_retries: retries,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_client_url() {
+        let client =
+            ZealWebSocketClient::new("https://zeal.example.com/app", WebSocketConfig::default())
+                .unwrap();
+        assert_eq!(client.url(), "wss://zeal.example.com/ws/zip");
+
+        let client =
+            ZealWebSocketClient::new("http://localhost:3000", WebSocketConfig::default()).unwrap();
+        assert_eq!(client.url(), "ws://localhost:3000/ws/zip");
+
+        assert!(ZealWebSocketClient::new("ftp://host", WebSocketConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_latency_ema_converges() {
+        let alpha = 0.25;
+        let mut latency = Latency::default();
+        let base = Instant::now();
+
+        // Pongs for pings sent every 30s: the first takes 100ms, the rest 20ms
+        let round_trips = std::iter::once(100).chain(std::iter::repeat(20)).take(40);
+        for (i, millis) in round_trips.enumerate() {
+            let sent = base + Duration::from_secs(30 * i as u64);
+            let received = sent + Duration::from_millis(millis);
+            latency.record(received - sent, alpha);
+
+            if i == 0 {
+                assert_eq!(latency.ema, Some(Duration::from_millis(100)));
+            }
+            if i == 1 {
+                // 0.75 * 100ms + 0.25 * 20ms
+                assert_eq!(latency.ema, Some(Duration::from_millis(80)));
+            }
+        }
+
+        assert_eq!(latency.latest, Some(Duration::from_millis(20)));
+        let ema = latency.ema.unwrap();
+        assert!(ema >= Duration::from_millis(20));
+        assert!(ema - Duration::from_millis(20) < Duration::from_micros(100));
+    }
+
+    #[test]
+    fn test_latency_ema_alpha_is_validated() {
+        for alpha in [0.0, -0.5, 1.5, f64::NAN] {
+            let config = WebSocketConfig {
+                latency_ema_alpha: alpha,
+                ..Default::default()
+            };
+            assert!(ZealWebSocketClient::new("http://localhost:3000", config).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_streams_events_and_answers_pings() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (pong_tx, pong_rx) = tokio::sync::oneshot::channel();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+            let Some(Ok(Message::Text(subscribe))) = ws.next().await else {
+                panic!("expected subscribe");
+            };
+            assert!(subscribe.contains(r#""workflowId":"wf-1""#));
+
+            for frame in [
+                serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }).to_string(),
+                serde_json::json!({
+                    "id": "evt-1",
+                    "timestamp": "2024-01-01T00:00:00Z",
+                    "workflowId": "wf-1",
+                    "type": "node.executing",
+                    "nodeId": "node-1",
+                    "inputConnections": []
+                })
+                .to_string(),
+                "not json".to_string(),
+                serde_json::json!({ "type": "ping", "timestamp": 42 }).to_string(),
+            ] {
+                ws.send(Message::Text(frame)).await.unwrap();
+            }
+
+            while let Some(Ok(message)) = ws.next().await {
+                if let Message::Text(text) = message {
+                    if text.contains(r#""type":"pong""#) {
+                        let _ = pong_tx.send(text);
+                        break;
+                    }
+                }
+            }
+            ws.close(None).await.unwrap();
+        });
+
+        let client = ZealWebSocketClient::new(
+            &format!("http://{}", addr),
+            WebSocketConfig {
+                subscribe_timeout: Duration::from_secs(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut events = client.subscribe("wf-1", None).await.unwrap();
+
+        let first = events.next().await.unwrap().unwrap();
+        assert!(matches!(
+            first,
+            ZipWebSocketEvent::Execution(crate::events::ZipExecutionEvent::NodeExecuting(ref e))
+                if e.node_id == "node-1"
+        ));
+        assert!(events.next().await.unwrap().is_err());
+
+        let pong = tokio::time::timeout(Duration::from_secs(1), pong_rx)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pong.contains(r#""timestamp":42"#));
+
+        assert!(events.next().await.is_none());
+    }
+
+    fn node_event(node_id: &str) -> String {
+        serde_json::json!({
+            "id": format!("evt-{}", node_id),
+            "timestamp": "2024-01-01T00:00:00Z",
+            "workflowId": "wf-1",
+            "type": "node.executing",
+            "nodeId": node_id,
+            "inputConnections": []
+        })
+        .to_string()
+    }
+
+    fn reconnect_config(max_reconnect_attempts: usize) -> WebSocketConfig {
+        WebSocketConfig {
+            subscribe_timeout: Duration::from_secs(1),
+            max_reconnect_attempts,
+            reconnect_delay: Duration::from_millis(10),
+            max_reconnect_delay: Duration::from_millis(50),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reconnect_backoff_is_exponential_and_capped() {
+        let config = WebSocketConfig {
+            reconnect_delay: Duration::from_millis(100),
+            max_reconnect_delay: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let delays: Vec<_> = [1, 2, 3, 4, 100]
+            .into_iter()
+            .map(|attempt| reconnect_backoff(&config, attempt).as_millis())
+            .collect();
+        assert_eq!(delays, [100, 200, 400, 500, 500]);
+    }
+
+    #[tokio::test]
+    async fn test_failures_keeps_failures_and_errors() {
+        use crate::events::*;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let stream = ZipEventStream {
+            receiver,
+            state: watch::channel(SocketState::Connected).1,
+            latency: watch::channel(Latency::default()).1,
+            task: tokio::spawn(async {}),
+        };
+        let execution = |event| Ok(ZipWebSocketEvent::Execution(event));
+        for item in [
+            execution(ZipExecutionEvent::NodeExecuting(
+                create_node_executing_event("wf-1", "node-1", vec![], None),
+            )),
+            execution(ZipExecutionEvent::NodeFailed(create_node_failed_event(
+                "wf-1",
+                "node-1",
+                vec![],
+                None,
+                None,
+            ))),
+            Ok(ZipWebSocketEvent::Control(ZipControlEvent::Pong(
+                PongEvent {
+                    event_type: "pong".to_string(),
+                    timestamp: 0,
+                },
+            ))),
+            execution(ZipExecutionEvent::ExecutionFailed(
+                create_execution_failed_event("wf-2", "exec-1", None, None),
+            )),
+            Err(ZealError::connection_error("gave up")),
+        ] {
+            sender.send(item).unwrap();
+        }
+        drop(sender);
+
+        let received: Vec<_> = stream.failures().collect().await;
+        assert_eq!(received.len(), 3);
+        assert!(matches!(
+            &received[0],
+            Ok(ExecutionFailure { scope: FailureScope::Node { node_id }, .. }) if node_id == "node-1"
+        ));
+        assert!(matches!(
+            &received[1],
+            Ok(ExecutionFailure { workflow_id, .. }) if workflow_id == "wf-2"
+        ));
+        assert!(matches!(
+            received[2],
+            Err(ZealError::ConnectionError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_client_reconnects_and_resubscribes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            for node_id in ["node-1", "node-2"] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+                let Some(Ok(Message::Text(subscribe))) = ws.next().await else {
+                    panic!("expected subscribe");
+                };
+                assert!(subscribe.contains(r#""workflowId":"wf-1""#));
+                ws.send(Message::Text(
+                    serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }).to_string(),
+                ))
+                .await
+                .unwrap();
+                ws.send(Message::Text(node_event(node_id))).await.unwrap();
+
+                if node_id == "node-1" {
+                    // Drop the connection without a close handshake
+                    drop(ws);
+                } else {
+                    ws.close(None).await.unwrap();
+                }
+            }
+        });
+
+        let client =
+            ZealWebSocketClient::new(&format!("http://{}", addr), reconnect_config(3)).unwrap();
+        let mut events = client.subscribe("wf-1", None).await.unwrap();
+        let mut state = events.state();
+        assert_eq!(*state.borrow(), SocketState::Connected);
+
+        let mut nodes = Vec::new();
+        while let Some(event) = events.next().await {
+            match event.unwrap() {
+                ZipWebSocketEvent::Execution(crate::events::ZipExecutionEvent::NodeExecuting(
+                    e,
+                )) => nodes.push(e.node_id),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(nodes, ["node-1", "node-2"]);
+
+        state
+            .wait_for(|s| *s == SocketState::Disconnected)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_gives_up_after_max_reconnect_attempts() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let _ = ws.next().await;
+            ws.send(Message::Text(
+                serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }).to_string(),
+            ))
+            .await
+            .unwrap();
+            // Dropping the listener refuses every reconnect attempt
+        });
+
+        let client =
+            ZealWebSocketClient::new(&format!("http://{}", addr), reconnect_config(2)).unwrap();
+        let mut events = client.subscribe("wf-1", None).await.unwrap();
+        let state = events.state();
+
+        let err = events.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, ZealError::ConnectionError { .. }));
+        assert!(err.to_string().contains("after 2 attempts"));
+        assert!(events.next().await.is_none());
+        assert_eq!(*state.borrow(), SocketState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_acknowledged() {
+        let url = server(
+            1,
+            serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }),
+        )
+        .await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let ack = subscribe(&mut socket, &request(), &config(0))
+            .await
+            .unwrap();
+        assert_eq!(ack.workflow_id, "wf-1");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_rejected() {
+        let url = server(
+            1,
+            serde_json::json!({
+                "type": "subscribe.error",
+                "workflowId": "wf-1",
+                "error": "Unauthorized"
+            }),
+        )
+        .await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let err = subscribe(&mut socket, &request(), &config(2))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZealError::WebSocketError { .. }));
+        assert!(err.to_string().contains("Unauthorized"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_retries_until_acknowledged() {
+        let url = server(
+            2,
+            serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }),
+        )
+        .await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let ack = subscribe(&mut socket, &request(), &config(1))
+            .await
+            .unwrap();
+        assert_eq!(ack.workflow_id, "wf-1");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_times_out_without_ack() {
+        let url = server(
+            usize::MAX,
+            serde_json::json!({ "type": "subscribed", "workflowId": "wf-1" }),
+        )
+        .await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let err = subscribe(&mut socket, &request(), &config(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ZealError::TimeoutError { .. }));
+    }
+}
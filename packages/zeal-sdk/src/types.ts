@@ -205,7 +205,7 @@ export interface TraceEvent {
   timestamp: number
   nodeId: string
   portId?: string
-  eventType: 'input' | 'output' | 'error' | 'log'
+  eventType: 'input' | 'output' | 'error' | 'log' | 'warning'
   data: {
     size: number
     type: string
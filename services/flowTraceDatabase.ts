@@ -175,20 +175,20 @@ export class FlowTraceDatabase {
     timestamp: string
     nodeId: string
     portId?: string
-    eventType: 'input' | 'output' | 'error' | 'log'
+    eventType: 'input' | 'output' | 'error' | 'log' | 'warning'
     data: any
     duration?: number
     metadata?: any
   }): Promise<void> {
     return FlowTraceTimescaleDB.addEvent({
       ...data,
-      eventType: data.eventType as 'input' | 'output' | 'error' | 'log' | 'start' | 'complete'
+      eventType: data.eventType as 'input' | 'output' | 'error' | 'log' | 'warning' | 'start' | 'complete'
     })
   }
 
-  // Get all events for a session
-  static async getSessionEvents(sessionId: string): Promise<any[]> {
-    return FlowTraceTimescaleDB.getSessionEvents(sessionId)
+  // Get all events for a session, optionally only those at or after `since`
+  static async getSessionEvents(sessionId: string, since?: Date): Promise<any[]> {
+    return FlowTraceTimescaleDB.getSessionEvents(sessionId, since)
   }
 
   // Get a single session by ID
@@ -168,7 +168,7 @@ export class FlowTraceTimescaleDB {
     timestamp: string
     nodeId: string
     portId?: string
-    eventType: 'input' | 'output' | 'error' | 'log' | 'start' | 'complete'
+    eventType: 'input' | 'output' | 'error' | 'log' | 'warning' | 'start' | 'complete'
     data: any
     duration?: number
     metadata?: any
@@ -544,16 +544,18 @@ export class FlowTraceTimescaleDB {
   }
 
   // Get all events for a session
-  static async getSessionEvents(sessionId: string): Promise<any[]> {
+  static async getSessionEvents(sessionId: string, since?: Date): Promise<any[]> {
     const db = await getTimescaleDB()
     
+    // Events sharing a timestamp keep insertion order so pollers can page by position
     const result = await db.query(
       `
       SELECT * FROM flow_trace_events 
       WHERE session_id = $1 
-      ORDER BY timestamp ASC
+        AND ($2::timestamptz IS NULL OR timestamp >= $2)
+      ORDER BY timestamp ASC, created_at ASC
       `,
-      [sessionId]
+      [sessionId, since ? since.toISOString() : null]
     )
     
     return result.rows.map((row: any) => ({
@@ -92,7 +92,7 @@ CREATE TABLE IF NOT EXISTS flow_trace_events (
   timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
   node_id TEXT NOT NULL,
   port_id TEXT,
-  event_type TEXT NOT NULL CHECK (event_type IN ('input', 'output', 'error', 'log', 'start', 'complete')),
+  event_type TEXT NOT NULL CHECK (event_type IN ('input', 'output', 'error', 'log', 'warning', 'start', 'complete')),
   data JSONB,
   duration INTEGER, -- milliseconds
   metadata JSONB,